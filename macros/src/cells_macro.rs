@@ -0,0 +1,59 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse2,
+    punctuated::Punctuated,
+    Expr, Lit, Result, Token,
+};
+
+struct CellsInput {
+    columns: Punctuated<Column, Token![,]>,
+}
+impl Parse for CellsInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            columns: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+struct Column {
+    header: Lit,
+    value: Expr,
+}
+impl Parse for Column {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let header = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let value = input.parse()?;
+        Ok(Self { header, value })
+    }
+}
+
+pub fn build(input: TokenStream) -> Result<TokenStream> {
+    let input: CellsInput = parse2(input)?;
+    let mut params = Vec::new();
+    let mut columns = Vec::new();
+    let mut values = Vec::new();
+    for (index, column) in input.columns.iter().enumerate() {
+        let param = format_ident!("T{index}");
+        let member = syn::Index::from(index);
+        let header = &column.header;
+        columns.push(quote!(::text_grid::CellsFormatter::column(f, #header, |x| &x.#member)));
+        values.push(&column.value);
+        params.push(param);
+    }
+    Ok(quote! {
+        {
+            struct __Cells<#(#params,)*>(#(#params,)*);
+            impl<#(#params: ::text_grid::Cells,)*> ::text_grid::Cells for __Cells<#(#params,)*> {
+                #[allow(unused_variables)]
+                fn fmt(f: &mut ::text_grid::CellsFormatter<Self>) {
+                    #(#columns;)*
+                }
+            }
+            __Cells(#(#values,)*)
+        }
+    })
+}
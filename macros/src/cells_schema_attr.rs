@@ -0,0 +1,87 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse2, spanned::Spanned, FnArg, GenericArgument, ItemFn, PathArguments, Result, Type};
+
+pub fn build(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    if !attr.is_empty() {
+        bail!(attr.span(), "`#[cells_schema]` does not take arguments");
+    }
+    let item: ItemFn = parse2(item)?;
+    let sig = &item.sig;
+    if !sig.generics.params.is_empty() {
+        bail!(
+            sig.generics.span(),
+            "`#[cells_schema]` not supported for generic functions"
+        );
+    }
+    if sig.inputs.len() != 1 {
+        bail!(
+            sig.inputs.span(),
+            "the function must have exactly one argument of type `&mut CellsFormatter<T>`"
+        );
+    }
+    let source = match &sig.inputs[0] {
+        FnArg::Typed(arg) => source_type(&arg.ty)?,
+        FnArg::Receiver(r) => bail!(r.span(), "`#[cells_schema]` not supported for methods"),
+    };
+    let vis = &item.vis;
+    let fn_ident = &sig.ident;
+    let schema_ident = format_ident!("{}Schema", to_pascal_case(&fn_ident.to_string()));
+    let schema_fn_ident = format_ident!("{}_schema", fn_ident);
+    let doc = format!("[`CellsSchema`](::text_grid::CellsSchema) defined by [`{fn_ident}`].");
+    let schema_fn_doc = format!("Create [`{schema_ident}`].");
+    Ok(quote! {
+        #item
+
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, Default)]
+        #vis struct #schema_ident;
+
+        #[automatically_derived]
+        impl ::text_grid::CellsSchema for #schema_ident {
+            type Source = #source;
+            fn fmt(&self, f: &mut ::text_grid::CellsFormatter<Self::Source>) {
+                #fn_ident(f)
+            }
+        }
+
+        #[doc = #schema_fn_doc]
+        #vis fn #schema_fn_ident() -> #schema_ident {
+            #schema_ident
+        }
+    })
+}
+
+fn source_type(ty: &Type) -> Result<&Type> {
+    if let Type::Reference(r) = ty {
+        if r.mutability.is_some() {
+            if let Type::Path(p) = &*r.elem {
+                if let Some(s) = p.path.segments.last() {
+                    if s.ident == "CellsFormatter" {
+                        if let PathArguments::AngleBracketed(args) = &s.arguments {
+                            if let Some(GenericArgument::Type(ty)) = args.args.last() {
+                                return Ok(ty);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    bail!(
+        ty.span(),
+        "the type of the argument must be `&mut CellsFormatter<T>`"
+    )
+}
+
+fn to_pascal_case(s: &str) -> String {
+    let mut result = String::new();
+    for word in s.trim_start_matches("r#").split('_') {
+        let mut chars = word.chars();
+        if let Some(c) = chars.next() {
+            result.extend(c.to_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+    result
+}
@@ -1,34 +1,126 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens};
 use structmeta::StructMeta;
 use syn::{
-    parse2, spanned::Spanned, Data, DataEnum, DataStruct, DeriveInput, Field, Fields, Member,
-    Result, Variant,
+    ext::IdentExt, parse2, spanned::Spanned, Data, DataEnum, DataStruct, DeriveInput, Field,
+    Fields, Ident, LitInt, LitStr, Member, Path, Result, Type, Variant,
 };
 
-use crate::bound::WhereClauseBuilder;
+use crate::{bound::WhereClauseBuilder, rename::RenameRule};
 
-#[derive(StructMeta)]
+#[derive(StructMeta, Default)]
 struct CellsAttr {
     dump: bool,
+    rename_all: Option<LitStr>,
+    variant_header: Option<LitStr>,
+    hide_variant: bool,
+}
+impl CellsAttr {
+    fn new(input: &DeriveInput) -> Result<Self> {
+        let mut this = Self::default();
+        for attr in &input.attrs {
+            if attr.meta.path().is_ident("cells") {
+                let attr: Self = attr.parse_args()?;
+                this.dump |= attr.dump;
+                if attr.rename_all.is_some() {
+                    this.rename_all = attr.rename_all;
+                }
+                if attr.variant_header.is_some() {
+                    this.variant_header = attr.variant_header;
+                }
+                this.hide_variant |= attr.hide_variant;
+            }
+        }
+        Ok(this)
+    }
+}
+
+#[derive(StructMeta, Default)]
+struct CellsFieldAttr {
+    header: Option<LitStr>,
+    header_key: Option<LitStr>,
+    format: Option<LitStr>,
+    group: Option<LitStr>,
+    with: Option<LitStr>,
+    order: Option<LitInt>,
+    flatten: bool,
+    skip_if_none: bool,
+    skip: bool,
+}
+impl CellsFieldAttr {
+    fn new(field: &Field) -> Result<Self> {
+        let mut this = Self::default();
+        for attr in &field.attrs {
+            if attr.meta.path().is_ident("cells") {
+                let attr: Self = attr.parse_args()?;
+                if attr.header.is_some() {
+                    this.header = attr.header;
+                }
+                if attr.header_key.is_some() {
+                    this.header_key = attr.header_key;
+                }
+                if attr.format.is_some() {
+                    this.format = attr.format;
+                }
+                if attr.group.is_some() {
+                    this.group = attr.group;
+                }
+                if attr.with.is_some() {
+                    this.with = attr.with;
+                }
+                if attr.order.is_some() {
+                    this.order = attr.order;
+                }
+                this.flatten |= attr.flatten;
+                this.skip_if_none |= attr.skip_if_none;
+                this.skip |= attr.skip;
+            }
+        }
+        Ok(this)
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => ty.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        Type::Group(ty) => is_option(&ty.elem),
+        Type::Paren(ty) => is_option(&ty.elem),
+        _ => false,
+    }
 }
 
 pub fn build(input: TokenStream) -> Result<TokenStream> {
     let input: DeriveInput = parse2(input)?;
-    let mut dump = false;
-    for attr in &input.attrs {
-        if attr.meta.path().is_ident("cells") {
-            let attr: CellsAttr = attr.parse_args()?;
-            dump |= attr.dump;
+    let attr = CellsAttr::new(&input)?;
+    let rename_all = attr
+        .rename_all
+        .as_ref()
+        .map(RenameRule::parse)
+        .transpose()?;
+    if !matches!(input.data, Data::Enum(_)) {
+        if let Some(header) = &attr.variant_header {
+            bail!(
+                header.span(),
+                "`variant_header` is supported only for enums"
+            );
         }
+        if attr.hide_variant {
+            bail!(input.span(), "`hide_variant` is supported only for enums");
+        }
+    }
+    if let (Some(header), true) = (&attr.variant_header, attr.hide_variant) {
+        bail!(
+            header.span(),
+            "`variant_header` cannot be used with `hide_variant`"
+        );
     }
     let (impl_g, self_g, _) = input.generics.split_for_impl();
     let mut wcb = WhereClauseBuilder::new(&input.generics);
     let code = match &input.data {
-        Data::Struct(data) => build_from_struct(data, &mut wcb)?,
-        Data::Enum(data) => build_from_enum(data)?,
+        Data::Struct(data) => build_from_struct(data, rename_all, &mut wcb)?,
+        Data::Enum(data) => build_from_enum(data, &attr, rename_all)?,
         Data::Union(_) => bail!(input.span(), "`#[derive(Cells)] not supported for unions"),
     };
     let self_ident = &input.ident;
@@ -42,32 +134,167 @@ pub fn build(input: TokenStream) -> Result<TokenStream> {
             }
         }
     };
-    if dump {
+    if attr.dump {
         panic!("dump :\n{code}");
     }
     Ok(code)
 }
-fn build_from_struct(data: &DataStruct, wcb: &mut WhereClauseBuilder) -> Result<TokenStream> {
+fn build_from_struct(
+    data: &DataStruct,
+    rename_all: Option<RenameRule>,
+    wcb: &mut WhereClauseBuilder,
+) -> Result<TokenStream> {
     let mut codes = Vec::new();
+    let mut groups: Vec<(Option<String>, Vec<TokenStream>)> = Vec::new();
+    let mut fields = Vec::new();
     for (index, field) in data.fields.iter().enumerate() {
-        if let Some(ident) = &field.ident {
-            let ident_str = ident.to_string();
-            codes.push(quote!(::text_grid::CellsFormatter::column(f, #ident_str, |x| &x.#ident)));
+        let attr = CellsFieldAttr::new(field)?;
+        if attr.skip {
+            continue;
+        }
+        let order = match &attr.order {
+            Some(order) => order.base10_parse::<i32>()?,
+            None => 0,
+        };
+        fields.push((order, index, field, attr));
+    }
+    fields.sort_by_key(|(order, ..)| *order);
+    for (_, index, field, attr) in fields {
+        let group = attr.group.as_ref().map(|g| g.value());
+        // Fields of the same group are placed together at the position of the first field of the group.
+        let i = match groups.iter().rposition(|g| g.0 == group) {
+            Some(i) if group.is_some() || i + 1 == groups.len() => i,
+            _ => {
+                groups.push((group, Vec::new()));
+                groups.len() - 1
+            }
+        };
+        let codes = &mut groups[i].1;
+        if attr.flatten {
+            if let Some(header) = &attr.header {
+                bail!(header.span(), "`header` cannot be used with `flatten`");
+            }
+            if let Some(key) = &attr.header_key {
+                bail!(key.span(), "`header_key` cannot be used with `flatten`");
+            }
+            if let Some(format) = &attr.format {
+                bail!(format.span(), "`format` cannot be used with `flatten`");
+            }
+        }
+        if let (Some(_), Some(with)) = (&attr.format, &attr.with) {
+            bail!(with.span(), "`with` cannot be used with `format`");
+        }
+        if let (Some(ident), false) = (&field.ident, attr.flatten) {
+            let ident_str = if let Some(header) = &attr.header {
+                header.value()
+            } else {
+                to_header(ident, rename_all)
+            };
+            let header = if let Some(key) = &attr.header_key {
+                quote!(::text_grid::Cell::header_key(::text_grid::cell(#ident_str), #key))
+            } else {
+                quote!(#ident_str)
+            };
+            let value = field_value(&attr, quote!(x.#ident))?;
+            if attr.skip_if_none && !is_option(&field.ty) {
+                bail!(
+                    field.ty.span(),
+                    "`skip_if_none` requires a field of type `Option<_>`"
+                );
+            }
+            if attr.skip_if_none {
+                codes.push(quote!(::text_grid::CellsFormatter::column_with_style(
+                    f,
+                    #header,
+                    ::text_grid::ColumnStyle::new().hide_if_empty(true),
+                    |x| #value
+                )));
+            } else {
+                codes.push(quote!(::text_grid::CellsFormatter::column(f, #header, |x| #value)));
+            }
         } else {
-            let m = Member::Unnamed(index.into());
-            codes.push(quote!(::text_grid::CellsFormatter::content(f, |x| &x.#m)));
+            if let Some(header) = &attr.header {
+                bail!(
+                    header.span(),
+                    "`header` is not supported for unnamed fields"
+                );
+            }
+            if let Some(key) = &attr.header_key {
+                bail!(
+                    key.span(),
+                    "`header_key` is not supported for unnamed fields"
+                );
+            }
+            if attr.skip_if_none {
+                bail!(
+                    field.span(),
+                    "`skip_if_none` is not supported for unnamed or flattened fields"
+                );
+            }
+            let m = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(index.into()),
+            };
+            let value = field_value(&attr, quote!(x.#m))?;
+            codes.push(quote!(::text_grid::CellsFormatter::content(f, |x| #value)));
         };
-        wcb.push_bounds_for_field(field);
+        if attr.format.is_none() && attr.with.is_none() {
+            wcb.push_bounds_for_field(field);
+        }
+    }
+    for (group, group_codes) in groups {
+        if let Some(group) = group {
+            codes.push(
+                quote!(::text_grid::CellsFormatter::column_with(f, #group, |f| {
+                    #(#group_codes;)*
+                })),
+            );
+        } else {
+            codes.extend(group_codes);
+        }
     }
     Ok(quote!(#(#codes;)*))
 }
-fn build_from_enum(data: &DataEnum) -> Result<TokenStream> {
+fn build_from_enum(
+    data: &DataEnum,
+    attr: &CellsAttr,
+    rename_all: Option<RenameRule>,
+) -> Result<TokenStream> {
     let mut key_to_offset = HashMap::new();
+    let mut skip_if_none = HashSet::new();
 
     let mut name_count = 0;
     let mut unnamed_count = 0;
     for variant in &data.variants {
         for (index, field) in variant.fields.iter().enumerate() {
+            let attr = CellsFieldAttr::new(field)?;
+            if let Some(header) = &attr.header {
+                bail!(header.span(), "`header` is not supported for enum fields");
+            }
+            if let Some(key) = &attr.header_key {
+                bail!(key.span(), "`header_key` is not supported for enum fields");
+            }
+            if let Some(format) = &attr.format {
+                bail!(format.span(), "`format` is not supported for enum fields");
+            }
+            if attr.flatten {
+                bail!(field.span(), "`flatten` is not supported for enum fields");
+            }
+            if let Some(group) = &attr.group {
+                bail!(group.span(), "`group` is not supported for enum fields");
+            }
+            if let Some(with) = &attr.with {
+                bail!(with.span(), "`with` is not supported for enum fields");
+            }
+            if let Some(order) = &attr.order {
+                bail!(order.span(), "`order` is not supported for enum fields");
+            }
+            if attr.skip {
+                continue;
+            }
+            if attr.skip_if_none {
+                skip_if_none.insert(FieldKey::new(index, field));
+            }
             key_to_offset
                 .entry(FieldKey::new(index, field))
                 .or_insert_with_key(|key| {
@@ -86,6 +313,15 @@ fn build_from_enum(data: &DataEnum) -> Result<TokenStream> {
                 });
         }
     }
+    let mut unnamed: Vec<_> = key_to_offset
+        .keys()
+        .filter(|key| !key.is_named())
+        .cloned()
+        .collect();
+    unnamed.sort_by_key(|key| key_to_offset[key]);
+    for (offset, key) in unnamed.into_iter().enumerate() {
+        key_to_offset.insert(key, offset);
+    }
     for (key, offset) in &mut key_to_offset {
         if key.is_named() {
             *offset += unnamed_count;
@@ -95,11 +331,15 @@ fn build_from_enum(data: &DataEnum) -> Result<TokenStream> {
     let mut name_arms = Vec::new();
     let mut columns = vec![Column::dummy(); key_to_offset.len()];
     for (key, offset) in &key_to_offset {
-        columns[*offset] = Column::new(key);
+        columns[*offset] = Column::new(key, rename_all);
+        columns[*offset].skip_if_none = skip_if_none.contains(key);
     }
     for variant in &data.variants {
         let mut exprs = vec![quote!(None); columns.len()];
         for (index, field) in variant.fields.iter().enumerate() {
+            if CellsFieldAttr::new(field)?.skip {
+                continue;
+            }
             let offset = key_to_offset[&FieldKey::new(index, field)];
             let var = to_var(index, field);
             exprs[offset] = quote!(Some(#var));
@@ -111,10 +351,23 @@ fn build_from_enum(data: &DataEnum) -> Result<TokenStream> {
         let ident_str = variant.ident.to_string();
         name_arms.push(quote!(Self::#pat => #ident_str));
     }
+    let variant = if attr.hide_variant {
+        quote!()
+    } else if let Some(header) = &attr.variant_header {
+        quote! {
+            ::text_grid::CellsFormatter::column(f, #header, |x| match x {
+                #(#name_arms,)*
+            });
+        }
+    } else {
+        quote! {
+            ::text_grid::CellsFormatter::content(f, |x| match x {
+                #(#name_arms,)*
+            });
+        }
+    };
     Ok(quote! {
-        ::text_grid::CellsFormatter::content(f, |x| match x {
-            #(#name_arms,)*
-        });
+        #variant
         #(#columns;)*
     })
 }
@@ -122,20 +375,20 @@ fn build_from_enum(data: &DataEnum) -> Result<TokenStream> {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum FieldKey {
     Unnamed(usize),
-    Named(String),
+    Named(Ident),
 }
 impl FieldKey {
     fn new(index: usize, field: &Field) -> Self {
         if let Some(ident) = &field.ident {
-            FieldKey::Named(ident.to_string())
+            FieldKey::Named(ident.clone())
         } else {
             FieldKey::Unnamed(index)
         }
     }
-    fn to_header(&self) -> String {
+    fn to_header(&self, rename_all: Option<RenameRule>) -> String {
         match self {
             FieldKey::Unnamed(index) => index.to_string(),
-            FieldKey::Named(name) => name.clone(),
+            FieldKey::Named(ident) => to_header(ident, rename_all),
         }
     }
     fn is_named(&self) -> bool {
@@ -145,19 +398,22 @@ impl FieldKey {
 
 #[derive(Clone)]
 struct Column {
-    key: FieldKey,
+    header: String,
+    skip_if_none: bool,
     arms: Vec<TokenStream>,
 }
 impl Column {
     fn dummy() -> Self {
         Self {
-            key: FieldKey::Unnamed(0),
+            header: String::new(),
+            skip_if_none: false,
             arms: Vec::new(),
         }
     }
-    fn new(key: &FieldKey) -> Self {
+    fn new(key: &FieldKey, rename_all: Option<RenameRule>) -> Self {
         Self {
-            key: key.clone(),
+            header: key.to_header(rename_all),
+            skip_if_none: false,
             arms: Vec::new(),
         }
     }
@@ -165,12 +421,44 @@ impl Column {
 impl ToTokens for Column {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let arms = &self.arms;
-        let header = self.key.to_header();
-        tokens.extend(quote!(
-            ::text_grid::CellsFormatter::column(f, #header, |x| match x {
-                #(#arms,)*
-            });
-        ))
+        let header = &self.header;
+        if self.skip_if_none {
+            tokens.extend(quote!(
+                ::text_grid::CellsFormatter::column_with_style(
+                    f,
+                    #header,
+                    ::text_grid::ColumnStyle::new().hide_if_empty(true),
+                    |x| match x {
+                        #(#arms,)*
+                    },
+                );
+            ))
+        } else {
+            tokens.extend(quote!(
+                ::text_grid::CellsFormatter::column(f, #header, |x| match x {
+                    #(#arms,)*
+                });
+            ))
+        }
+    }
+}
+
+fn field_value(attr: &CellsFieldAttr, expr: TokenStream) -> Result<TokenStream> {
+    Ok(if let Some(format) = &attr.format {
+        quote!(::text_grid::cell!(#format, &#expr))
+    } else if let Some(with) = &attr.with {
+        let with: Path = with.parse()?;
+        quote!(#with(&#expr))
+    } else {
+        quote!(&#expr)
+    })
+}
+
+fn to_header(ident: &Ident, rename_all: Option<RenameRule>) -> String {
+    if let Some(rule) = rename_all {
+        rule.apply(&ident.unraw().to_string())
+    } else {
+        ident.to_string()
     }
 }
 
@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use structmeta::{NameArgs, StructMeta};
+use syn::{parse2, spanned::Spanned, DeriveInput, Path, Result, Type};
+
+use crate::bound::{Bound, Bounds, WhereClauseBuilder};
+
+#[derive(StructMeta, Default)]
+struct CellsSchemaAttr {
+    source: Option<Type>,
+    fmt: Option<Path>,
+    fmt_footer: Option<Path>,
+    bound: Option<NameArgs<Vec<Bound>>>,
+    dump: bool,
+}
+
+pub fn build(input: TokenStream) -> Result<TokenStream> {
+    let input: DeriveInput = parse2(input)?;
+    let mut attr = CellsSchemaAttr::default();
+    for a in &input.attrs {
+        if a.meta.path().is_ident("cells") {
+            let a: CellsSchemaAttr = a.parse_args()?;
+            if a.source.is_some() {
+                attr.source = a.source;
+            }
+            if a.fmt.is_some() {
+                attr.fmt = a.fmt;
+            }
+            if a.fmt_footer.is_some() {
+                attr.fmt_footer = a.fmt_footer;
+            }
+            if a.bound.is_some() {
+                attr.bound = a.bound;
+            }
+            attr.dump |= a.dump;
+        }
+    }
+    let Some(source) = &attr.source else {
+        bail!(
+            input.span(),
+            "`#[derive(CellsSchema)]` requires `#[cells(source = ...)]`"
+        );
+    };
+    let Some(fmt) = &attr.fmt else {
+        bail!(
+            input.span(),
+            "`#[derive(CellsSchema)]` requires `#[cells(fmt = ...)]`"
+        );
+    };
+    let fmt_footer = attr.fmt_footer.as_ref().map(|fmt_footer| {
+        quote! {
+            fn fmt_footer(&self, f: &mut ::text_grid::CellsFormatter<[&Self::Source]>) {
+                #fmt_footer(self, f)
+            }
+        }
+    });
+    let (impl_g, self_g, _) = input.generics.split_for_impl();
+    let mut wcb = WhereClauseBuilder::new(&input.generics);
+    wcb.push_bounds(&Bounds::from(&attr.bound));
+    let wheres = wcb.build(|ty| quote!(#ty : ::text_grid::Cells));
+    let self_ident = &input.ident;
+    let code = quote! {
+        #[automatically_derived]
+        impl #impl_g ::text_grid::CellsSchema for #self_ident #self_g #wheres {
+            type Source = #source;
+            fn fmt(&self, f: &mut ::text_grid::CellsFormatter<Self::Source>) {
+                #fmt(self, f)
+            }
+            #fmt_footer
+        }
+    };
+    if attr.dump {
+        panic!("dump :\n{code}");
+    }
+    Ok(code)
+}
@@ -2,9 +2,31 @@
 mod syn_utils;
 
 mod bound;
+mod cells_macro;
+mod cells_schema_attr;
 mod derive_cells;
+mod derive_cells_schema;
+mod rename;
 
 #[proc_macro_derive(Cells, attributes(cells))]
 pub fn derive_cells(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     syn_utils::into_macro_output(derive_cells::build(input.into()))
 }
+
+#[proc_macro_derive(CellsSchema, attributes(cells))]
+pub fn derive_cells_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    syn_utils::into_macro_output(derive_cells_schema::build(input.into()))
+}
+
+#[proc_macro_attribute]
+pub fn cells_schema(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    syn_utils::into_macro_output(cells_schema_attr::build(attr.into(), item.into()))
+}
+
+#[proc_macro]
+pub fn cells(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    syn_utils::into_macro_output(cells_macro::build(input.into()))
+}
@@ -0,0 +1,377 @@
+//! [`Aggregator`]s used to calculate the totals rows of grids.
+
+use std::cmp::{max_by, min_by, Ordering};
+use std::fmt::Write;
+use std::rc::Rc;
+
+use crate::{GridBuilder, HeaderLayout};
+
+/// Accumulate the values of a column to display in a totals row.
+///
+/// Register aggregators with [`GridBuilder::aggregate`](crate::GridBuilder::aggregate)
+/// and display the result with [`GridBuilder::push_totals`](crate::GridBuilder::push_totals).
+///
+/// The aggregators for common statistics are in this module.
+pub trait Aggregator {
+    /// Add the string of a body cell.
+    fn push(&mut self, value: &str);
+
+    /// Write the aggregated value.
+    fn fmt(&self, s: &mut String);
+}
+
+impl<A: ?Sized + Aggregator> Aggregator for Box<A> {
+    fn push(&mut self, value: &str) {
+        A::push(self, value)
+    }
+    fn fmt(&self, s: &mut String) {
+        A::fmt(self, s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Integer(i128),
+    Float(f64),
+}
+impl Number {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Ok(value) = value.parse() {
+            return Some(Self::Integer(value));
+        }
+        let value = value.parse::<f64>().ok()?;
+        value.is_finite().then_some(Self::Float(value))
+    }
+    fn to_f64(self) -> f64 {
+        match self {
+            Self::Integer(value) => value as f64,
+            Self::Float(value) => value,
+        }
+    }
+    fn add(self, other: Self) -> Self {
+        if let (Self::Integer(a), Self::Integer(b)) = (self, other) {
+            if let Some(value) = a.checked_add(b) {
+                return Self::Integer(value);
+            }
+        }
+        Self::Float(self.to_f64() + other.to_f64())
+    }
+    fn cmp(self, other: Self) -> Ordering {
+        match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => a.cmp(&b),
+            _ => self.to_f64().total_cmp(&other.to_f64()),
+        }
+    }
+    fn fmt(self, s: &mut String) {
+        match self {
+            Self::Integer(value) => write!(s, "{value}").unwrap(),
+            Self::Float(value) => write!(s, "{value}").unwrap(),
+        }
+    }
+}
+
+/// [`Aggregator`] that calculates the sum of numeric values.
+///
+/// Integers are summed exactly, and floating-point arithmetic is used only after a non-integral value is found.
+/// Values that are not numbers are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Sum {
+    value: Option<Number>,
+}
+impl Sum {
+    /// Create a `Sum` without values.
+    ///
+    /// If no number is added, the result is empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Aggregator for Sum {
+    fn push(&mut self, value: &str) {
+        if let Some(value) = Number::parse(value) {
+            self.value = Some(self.value.map_or(value, |x| x.add(value)));
+        }
+    }
+    fn fmt(&self, s: &mut String) {
+        if let Some(value) = self.value {
+            value.fmt(s);
+        }
+    }
+}
+
+/// [`Aggregator`] that calculates the minimum of numeric values.
+///
+/// Values that are not numbers are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Min {
+    value: Option<Number>,
+}
+impl Min {
+    /// Create a `Min` without values.
+    ///
+    /// If no number is added, the result is empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Aggregator for Min {
+    fn push(&mut self, value: &str) {
+        if let Some(value) = Number::parse(value) {
+            self.value = Some(
+                self.value
+                    .map_or(value, |x| min_by(x, value, |a, b| a.cmp(*b))),
+            );
+        }
+    }
+    fn fmt(&self, s: &mut String) {
+        if let Some(value) = self.value {
+            value.fmt(s);
+        }
+    }
+}
+
+/// [`Aggregator`] that calculates the maximum of numeric values.
+///
+/// Values that are not numbers are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Max {
+    value: Option<Number>,
+}
+impl Max {
+    /// Create a `Max` without values.
+    ///
+    /// If no number is added, the result is empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Aggregator for Max {
+    fn push(&mut self, value: &str) {
+        if let Some(value) = Number::parse(value) {
+            self.value = Some(
+                self.value
+                    .map_or(value, |x| max_by(x, value, |a, b| a.cmp(*b))),
+            );
+        }
+    }
+    fn fmt(&self, s: &mut String) {
+        if let Some(value) = self.value {
+            value.fmt(s);
+        }
+    }
+}
+
+/// [`Aggregator`] that calculates the arithmetic mean of numeric values.
+///
+/// Values that are not numbers are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Mean {
+    sum: Option<Number>,
+    count: usize,
+}
+impl Mean {
+    /// Create a `Mean` without values.
+    ///
+    /// If no number is added, the result is empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Aggregator for Mean {
+    fn push(&mut self, value: &str) {
+        if let Some(value) = Number::parse(value) {
+            self.sum = Some(self.sum.map_or(value, |x| x.add(value)));
+            self.count += 1;
+        }
+    }
+    fn fmt(&self, s: &mut String) {
+        if let Some(sum) = self.sum {
+            write!(s, "{}", sum.to_f64() / self.count as f64).unwrap();
+        }
+    }
+}
+
+/// [`Aggregator`] that counts non-empty values.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Count {
+    count: usize,
+}
+impl Count {
+    /// Create a `Count` without values.
+    ///
+    /// If no value is added, the result is `0`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl Aggregator for Count {
+    fn push(&mut self, value: &str) {
+        if !value.trim().is_empty() {
+            self.count += 1;
+        }
+    }
+    fn fmt(&self, s: &mut String) {
+        write!(s, "{}", self.count).unwrap();
+    }
+}
+
+/// [`Aggregator`] defined by closures.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let mut g = GridBuilder::new();
+/// g.aggregate(0, AggregateFn::new(0, |n, s: &str| *n += s.len(), |n| format!("{n} chars")));
+/// g.extend_body(["abc", "de"]);
+/// g.push_totals();
+/// assert_eq!(format!("\n{g}"), r#"
+///  abc     |
+///  de      |
+/// ---------|
+///  5 chars |
+/// "#);
+/// ```
+#[derive(Clone)]
+pub struct AggregateFn<S, P, R> {
+    state: S,
+    push: P,
+    result: R,
+}
+impl<S, P, R> AggregateFn<S, P, R>
+where
+    P: FnMut(&mut S, &str),
+    R: Fn(&S) -> String,
+{
+    /// Create an aggregator from an initial state, a function to add a value, and a function to get the result.
+    pub fn new(init: S, push: P, result: R) -> Self {
+        AggregateFn {
+            state: init,
+            push,
+            result,
+        }
+    }
+}
+impl<S, P, R> Aggregator for AggregateFn<S, P, R>
+where
+    P: FnMut(&mut S, &str),
+    R: Fn(&S) -> String,
+{
+    fn push(&mut self, value: &str) {
+        (self.push)(&mut self.state, value);
+    }
+    fn fmt(&self, s: &mut String) {
+        s.push_str(&(self.result)(&self.state));
+    }
+}
+
+/// Function that creates an [`Aggregator`] in its initial state.
+pub(crate) type AggregatorFn = Rc<dyn Fn() -> Box<dyn Aggregator>>;
+
+pub(crate) fn aggregator_fn(aggregator: impl Aggregator + Clone + 'static) -> AggregatorFn {
+    Rc::new(move || Box::new(aggregator.clone()))
+}
+
+/// [`Aggregator`]s for the columns specified by their headers.
+///
+/// Use with [`to_grid_with_totals`](crate::to_grid_with_totals).
+/// A column is specified by the header of its innermost column or by the headers of its ancestors and itself joined with `.`.
+/// Headers that do not match any column are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct Item {
+///     name: &'static str,
+///     price: u32,
+/// }
+/// impl Cells for Item {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |x| x.name);
+///         f.column("price", |x| x.price);
+///     }
+/// }
+///
+/// let rows = [
+///     Item { name: "apple", price: 100 },
+///     Item { name: "orange", price: 250 },
+/// ];
+/// let g = to_grid_with_totals(rows, |a| a.label("sum").sum("price"));
+/// assert_eq!(format!("\n{g}"), r#"
+///   name  | price |
+/// --------|-------|
+///  apple  |   100 |
+///  orange |   250 |
+/// --------|-------|
+///     sum |   350 |
+/// "#);
+/// ```
+#[derive(Default)]
+pub struct Aggregations {
+    columns: Vec<(String, AggregatorFn)>,
+    label: Option<String>,
+}
+impl Aggregations {
+    /// Create a new `Aggregations` without aggregators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aggregate the column specified by `header` with `aggregator`.
+    ///
+    /// If an aggregator is already specified for the column, it is replaced.
+    pub fn column(
+        mut self,
+        header: impl Into<String>,
+        aggregator: impl Aggregator + Clone + 'static,
+    ) -> Self {
+        self.columns
+            .push((header.into(), aggregator_fn(aggregator)));
+        self
+    }
+
+    /// Calculate the sum of the column specified by `header` using [`Sum`].
+    pub fn sum(self, header: impl Into<String>) -> Self {
+        self.column(header, Sum::new())
+    }
+
+    /// Calculate the arithmetic mean of the column specified by `header` using [`Mean`].
+    pub fn mean(self, header: impl Into<String>) -> Self {
+        self.column(header, Mean::new())
+    }
+
+    /// Calculate the minimum of the column specified by `header` using [`Min`].
+    pub fn min(self, header: impl Into<String>) -> Self {
+        self.column(header, Min::new())
+    }
+
+    /// Calculate the maximum of the column specified by `header` using [`Max`].
+    pub fn max(self, header: impl Into<String>) -> Self {
+        self.column(header, Max::new())
+    }
+
+    /// Count the non-empty values of the column specified by `header` using [`Count`].
+    pub fn count(self, header: impl Into<String>) -> Self {
+        self.column(header, Count::new())
+    }
+
+    /// Display `text` in the first column of the totals row if the column is not aggregated.
+    pub fn label(mut self, text: impl Into<String>) -> Self {
+        self.label = Some(text.into());
+        self
+    }
+
+    /// Register the aggregators with `g` using the columns of `headers`.
+    pub(crate) fn register(self, g: &mut GridBuilder, headers: &HeaderLayout) {
+        if let Some(label) = self.label {
+            g.aggregate(0, AggregateFn::new((), |_, _| {}, move |_| label.clone()));
+        }
+        for (header, aggregator) in self.columns {
+            if let Some(column) = headers.find_by_name(&header) {
+                g.aggregate_with(column, aggregator);
+            }
+        }
+    }
+}
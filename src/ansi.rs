@@ -0,0 +1,210 @@
+use std::fmt::*;
+use unicode_width::UnicodeWidthStr;
+
+/// Terminal colors displayed using ANSI escape sequences.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// 256-color palette index.
+    Ansi256(u8),
+    /// 24-bit color.
+    Rgb(u8, u8, u8),
+}
+impl Color {
+    fn write_sgr(self, f: &mut impl Write, background: bool) -> Result {
+        let base = if background { 40 } else { 30 };
+        let code = match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::BrightBlack => 60,
+            Color::BrightRed => 61,
+            Color::BrightGreen => 62,
+            Color::BrightYellow => 63,
+            Color::BrightBlue => 64,
+            Color::BrightMagenta => 65,
+            Color::BrightCyan => 66,
+            Color::BrightWhite => 67,
+            Color::Ansi256(n) => return write!(f, "{};5;{n}", base + 8),
+            Color::Rgb(r, g, b) => return write!(f, "{};2;{r};{g};{b}", base + 8),
+        };
+        write!(f, "{}", base + code)
+    }
+}
+
+/// Color gradient used by [`GridBuilder::heatmap`](crate::GridBuilder::heatmap).
+///
+/// The color of each cell is linearly interpolated between `low` (the column minimum)
+/// and `high` (the column maximum).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Heatmap {
+    low: (u8, u8, u8),
+    high: (u8, u8, u8),
+    background: bool,
+}
+impl Heatmap {
+    /// Create a gradient from `low` to `high` applied to the text color.
+    pub const fn new(low: (u8, u8, u8), high: (u8, u8, u8)) -> Self {
+        Heatmap {
+            low,
+            high,
+            background: false,
+        }
+    }
+
+    /// Set whether the gradient is applied to the background color instead of the text color.
+    pub const fn background(self, value: bool) -> Self {
+        Heatmap {
+            background: value,
+            ..self
+        }
+    }
+
+    pub(crate) fn is_background(&self) -> bool {
+        self.background
+    }
+
+    /// Get the color at position `t` (0.0 to 1.0) of the gradient.
+    pub(crate) fn color(&self, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |low: u8, high: u8| (low as f64 + (high as f64 - low as f64) * t).round() as u8;
+        Color::Rgb(
+            mix(self.low.0, self.high.0),
+            mix(self.low.1, self.high.1),
+            mix(self.low.2, self.high.2),
+        )
+    }
+}
+impl Default for Heatmap {
+    /// Gradient from blue to red.
+    fn default() -> Self {
+        Self::new((0, 0, 255), (255, 0, 0))
+    }
+}
+
+/// Set of SGR attributes applied to a text.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Sgr {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+impl Sgr {
+    pub fn is_empty(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && !self.bold
+    }
+    pub fn write_start(&self, f: &mut impl Write) -> Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+        write!(f, "\x1b[")?;
+        let mut first = true;
+        if self.bold {
+            write!(f, "1")?;
+            first = false;
+        }
+        for (color, background) in [(self.fg, false), (self.bg, true)] {
+            if let Some(color) = color {
+                if !first {
+                    write!(f, ";")?;
+                }
+                color.write_sgr(f, background)?;
+                first = false;
+            }
+        }
+        write!(f, "m")
+    }
+    pub fn write_end(&self, f: &mut impl Write) -> Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+        write!(f, "\x1b[0m")
+    }
+}
+
+/// Write an OSC 8 hyperlink.
+///
+/// Control characters in `url` are percent-encoded so that they cannot terminate the sequence.
+pub(crate) fn write_link(f: &mut impl Write, text: impl Display, url: impl Display) -> Result {
+    write!(f, "\x1b]8;;")?;
+    for c in url.to_string().chars() {
+        if c.is_ascii_control() {
+            write!(f, "%{:02X}", c as u8)?;
+        } else {
+            f.write_char(c)?;
+        }
+    }
+    write!(f, "\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Returns the display width of the text, excluding CSI and OSC escape sequences.
+pub(crate) fn text_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut rest = s;
+    while let Some(start) = rest.find('\x1b') {
+        width += rest[..start].width();
+        rest = &rest[start..];
+        rest = &rest[escape_len(rest).unwrap_or(1)..];
+    }
+    width + rest.width()
+}
+
+/// Returns the text without CSI and OSC escape sequences.
+pub(crate) fn strip_escapes(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('\x1b') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        rest = &rest[escape_len(rest).unwrap_or(1)..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Returns the length of the CSI or OSC escape sequence at the start of `s`.
+pub(crate) fn escape_len(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix('\x1b')?;
+    if let Some(rest) = rest.strip_prefix('[') {
+        let end = rest.find(|c| !('\x20'..='\x3f').contains(&c));
+        Some(match end {
+            Some(end) if ('\x40'..='\x7e').contains(&rest[end..].chars().next().unwrap()) => {
+                2 + end + 1
+            }
+            Some(end) => 2 + end,
+            None => s.len(),
+        })
+    } else if let Some(rest) = rest.strip_prefix(']') {
+        let (end, len) = match (rest.find('\x07'), rest.find("\x1b\\")) {
+            (Some(bel), Some(st)) if st < bel => (st, 2),
+            (Some(bel), _) => (bel, 1),
+            (None, Some(st)) => (st, 2),
+            (None, None) => (rest.len(), 0),
+        };
+        Some(2 + end + len)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,258 @@
+use std::{borrow::Borrow, io::Write, sync::Arc};
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchOptions, StringArray,
+    UInt64Array,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+
+use crate::{
+    cells_csv_writer::{header_record, CsvBodyWriter},
+    Cells, CellsSchema, DefaultCellsSchema,
+};
+
+/// Convert rows to an Arrow [`RecordBatch`] using the columns defined by [`Cells`](crate::Cells).
+///
+/// Each column of the schema becomes a field named by the headers joined by `.`, as in csv.
+///
+/// Every field is `Utf8`, because the formatted values alone do not tell whether a value such as `01234` is a number.
+/// Use [`ArrowOptions::column_type`] to store a column as another type.
+///
+/// This function is available only when the `arrow` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "a", value: 300 },
+///     RowData { name: "b", value: 2 },
+/// ];
+/// let batch = to_record_batch(rows)?;
+/// assert_eq!(batch.num_rows(), 2);
+/// assert_eq!(batch.schema().field(0).name(), "name");
+/// assert_eq!(batch.schema().field(1).data_type(), &arrow_schema::DataType::Utf8);
+/// # Ok::<(), arrow_schema::ArrowError>(())
+/// ```
+pub fn to_record_batch(
+    rows: impl IntoIterator<Item = impl Cells>,
+) -> Result<RecordBatch, ArrowError> {
+    ArrowOptions::new().to_record_batch(rows)
+}
+
+/// Convert rows to an Arrow [`RecordBatch`] using the columns defined by [`CellsSchema`].
+///
+/// See [`to_record_batch`] for details.
+pub fn to_record_batch_with_schema<T>(
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> Result<RecordBatch, ArrowError> {
+    ArrowOptions::new().to_record_batch_with_schema(rows, schema)
+}
+
+/// Options used when converting rows to an Arrow [`RecordBatch`].
+///
+/// This type is available only when the `arrow` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// use arrow_schema::DataType;
+/// struct RowData {
+///     code: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("code", |s| s.code);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [RowData { code: "01234", value: 300 }];
+/// let batch = ArrowOptions::new()
+///     .column_type("value", DataType::Int64)
+///     .to_record_batch(rows)?;
+/// assert_eq!(batch.schema().field(0).data_type(), &DataType::Utf8);
+/// assert_eq!(batch.schema().field(1).data_type(), &DataType::Int64);
+/// # Ok::<(), arrow_schema::ArrowError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArrowOptions {
+    column_types: Vec<(String, DataType)>,
+}
+
+impl ArrowOptions {
+    /// Create a new `ArrowOptions` in which every column is `Utf8`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the type of the column named `name`.
+    ///
+    /// `name` is the headers of the column joined by `.`.
+    /// The supported types are `Utf8`, `Boolean`, `Int64`, `UInt64` and `Float64`.
+    /// Empty values are null, and a value that cannot be parsed as `data_type` is an error.
+    pub fn column_type(mut self, name: &str, data_type: DataType) -> Self {
+        self.column_types.retain(|(n, _)| n != name);
+        self.column_types.push((name.to_string(), data_type));
+        self
+    }
+
+    /// Convert rows to an Arrow [`RecordBatch`] using the columns defined by [`Cells`](crate::Cells).
+    pub fn to_record_batch(
+        &self,
+        rows: impl IntoIterator<Item = impl Cells>,
+    ) -> Result<RecordBatch, ArrowError> {
+        self.to_record_batch_with_schema(rows, DefaultCellsSchema::default())
+    }
+
+    /// Convert rows to an Arrow [`RecordBatch`] using the columns defined by [`CellsSchema`].
+    pub fn to_record_batch_with_schema<T>(
+        &self,
+        rows: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+    ) -> Result<RecordBatch, ArrowError> {
+        let headers = header_record(&schema);
+        let mut columns = vec![Vec::new(); headers.len()];
+        let mut body = CsvBodyWriter::new();
+        let mut row_count = 0;
+        for item in rows {
+            let record = body.record(&schema, item.borrow());
+            for (column, value) in columns.iter_mut().zip(record) {
+                column.push(value.to_string());
+            }
+            row_count += 1;
+        }
+        let mut fields = Vec::new();
+        let mut arrays = Vec::new();
+        for (header, values) in headers.iter().zip(columns) {
+            let data_type = self
+                .column_types
+                .iter()
+                .find(|(name, _)| name == header)
+                .map_or(&DataType::Utf8, |(_, t)| t);
+            let array = to_array(header, data_type, values)?;
+            fields.push(Field::new(header, array.data_type().clone(), true));
+            arrays.push(array);
+        }
+        let options = RecordBatchOptions::new().with_row_count(Some(row_count));
+        RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), arrays, &options)
+    }
+}
+
+fn to_array(
+    header: &str,
+    data_type: &DataType,
+    values: Vec<String>,
+) -> Result<ArrayRef, ArrowError> {
+    Ok(match data_type {
+        DataType::Utf8 => Arc::new(StringArray::from(values)),
+        DataType::Boolean => Arc::new(BooleanArray::from(parse_all::<bool>(
+            header, data_type, &values,
+        )?)),
+        DataType::Int64 => Arc::new(Int64Array::from(parse_all::<i64>(
+            header, data_type, &values,
+        )?)),
+        DataType::UInt64 => Arc::new(UInt64Array::from(parse_all::<u64>(
+            header, data_type, &values,
+        )?)),
+        DataType::Float64 => Arc::new(Float64Array::from(parse_all::<f64>(
+            header, data_type, &values,
+        )?)),
+        _ => {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "unsupported type {data_type} for column `{header}`"
+            )))
+        }
+    })
+}
+fn parse_all<N: std::str::FromStr>(
+    header: &str,
+    data_type: &DataType,
+    values: &[String],
+) -> Result<Vec<Option<N>>, ArrowError> {
+    values
+        .iter()
+        .map(|s| {
+            let s = s.trim();
+            if s.is_empty() {
+                Ok(None)
+            } else {
+                s.parse().map(Some).map_err(|_| {
+                    ArrowError::ParseError(format!(
+                        "cannot parse `{s}` in column `{header}` as {data_type}"
+                    ))
+                })
+            }
+        })
+        .collect()
+}
+
+/// Write rows in the Arrow IPC file format using the columns defined by [`Cells`](crate::Cells).
+///
+/// See [`to_record_batch`] for how columns are converted.
+///
+/// This function is available only when the `arrow` feature is enabled.
+pub fn write_arrow_ipc(
+    writer: impl Write,
+    rows: impl IntoIterator<Item = impl Cells>,
+) -> Result<(), ArrowError> {
+    write_arrow_ipc_with_schema(writer, rows, DefaultCellsSchema::default())
+}
+
+/// Write rows in the Arrow IPC file format using the columns defined by [`CellsSchema`].
+///
+/// See [`to_record_batch`] for how columns are converted.
+pub fn write_arrow_ipc_with_schema<T>(
+    writer: impl Write,
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> Result<(), ArrowError> {
+    let batch = to_record_batch_with_schema(rows, schema)?;
+    let mut w = FileWriter::try_new(writer, &batch.schema())?;
+    w.write(&batch)?;
+    w.finish()
+}
+
+/// Write rows in the Parquet format using the columns defined by [`Cells`](crate::Cells).
+///
+/// See [`to_record_batch`] for how columns are converted.
+///
+/// This function is available only when the `parquet` feature is enabled.
+#[cfg(feature = "parquet")]
+pub fn write_parquet(
+    writer: impl Write + Send,
+    rows: impl IntoIterator<Item = impl Cells>,
+) -> Result<(), parquet::errors::ParquetError> {
+    write_parquet_with_schema(writer, rows, DefaultCellsSchema::default())
+}
+
+/// Write rows in the Parquet format using the columns defined by [`CellsSchema`].
+///
+/// See [`to_record_batch`] for how columns are converted.
+#[cfg(feature = "parquet")]
+pub fn write_parquet_with_schema<T>(
+    writer: impl Write + Send,
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> Result<(), parquet::errors::ParquetError> {
+    let batch = to_record_batch_with_schema(rows, schema)?;
+    let mut w = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+    w.write(&batch)?;
+    w.close()?;
+    Ok(())
+}
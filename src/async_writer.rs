@@ -0,0 +1,164 @@
+use std::{borrow::Borrow, io};
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    cells_csv_writer::CsvRowWriter, Cells, CellsSchema, CsvOptions, DefaultCellsSchema, GridBuilder,
+};
+
+const FLUSH_INTERVAL: usize = 64;
+
+/// Write a table using the columns defined by [`Cells`](crate::Cells) to [`AsyncWrite`].
+///
+/// The column widths are calculated from all rows, so the whole table is rendered into memory
+/// before anything is written.
+/// The rendered text is then written in chunks of lines, flushing `writer` after each chunk.
+/// Use [`write_csv_async`] to write rows without buffering the whole output.
+///
+/// This function is available only when the `tokio` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     a: u32,
+///     b: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("a", |s| s.a);
+///         f.column("b", |s| s.b);
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> std::io::Result<()> {
+/// let rows = [RowData { a: 300, b: 1 }, RowData { a: 2, b: 200 }];
+/// let mut out = Vec::new();
+/// write_grid_async(&mut out, rows).await?;
+/// assert_eq!(String::from_utf8(out).unwrap(), r#"
+///   a  |  b  |
+/// -----|-----|
+///  300 |   1 |
+///    2 | 200 |
+/// "#[1..]);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn write_grid_async(
+    writer: impl AsyncWrite + Unpin,
+    rows: impl IntoIterator<Item = impl Cells>,
+) -> io::Result<()> {
+    write_grid_async_with_schema(writer, rows, DefaultCellsSchema::default()).await
+}
+
+/// Write a table using the columns defined by [`CellsSchema`] to [`AsyncWrite`].
+///
+/// See [`write_grid_async`] for details.
+pub async fn write_grid_async_with_schema<T>(
+    mut writer: impl AsyncWrite + Unpin,
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> io::Result<()> {
+    // `GridBuilder` may contain values that are not `Send`, so it is not held across `await`.
+    let text = GridBuilder::from_iter_with_schema(rows, schema).to_string();
+    let mut rest = text.as_str();
+    while !rest.is_empty() {
+        let end = rest
+            .match_indices('\n')
+            .nth(FLUSH_INTERVAL - 1)
+            .map_or(rest.len(), |(i, _)| i + 1);
+        writer.write_all(&rest.as_bytes()[..end]).await?;
+        writer.flush().await?;
+        rest = &rest[end..];
+    }
+    Ok(())
+}
+
+/// Write csv using the columns defined by [`Cells`](crate::Cells) to [`AsyncWrite`].
+///
+/// Each row is written as soon as it is formatted, and `writer` is flushed periodically.
+///
+/// This function is available only when the `tokio` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     a: u32,
+///     b: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("a", |s| s.a);
+///         f.column("b", |s| s.b);
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> std::io::Result<()> {
+/// let rows = [RowData { a: 300, b: 1 }, RowData { a: 2, b: 200 }];
+/// let mut out = Vec::new();
+/// write_csv_async(&mut out, rows).await?;
+/// assert_eq!(String::from_utf8(out).unwrap(), "a,b\n300,1\n2,200\n");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn write_csv_async(
+    writer: impl AsyncWrite + Unpin,
+    rows: impl IntoIterator<Item = impl Cells>,
+) -> io::Result<()> {
+    CsvOptions::new().write_csv_async(writer, rows).await
+}
+
+/// Write csv using the columns defined by [`CellsSchema`] to [`AsyncWrite`].
+///
+/// See [`write_csv_async`] for details.
+pub async fn write_csv_async_with_schema<T>(
+    writer: impl AsyncWrite + Unpin,
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> io::Result<()> {
+    CsvOptions::new()
+        .write_csv_async_with_schema(writer, rows, schema)
+        .await
+}
+
+impl CsvOptions {
+    /// Write csv using the columns defined by [`Cells`](crate::Cells) to [`AsyncWrite`].
+    ///
+    /// This method is available only when the `tokio` feature is enabled.
+    pub async fn write_csv_async(
+        &self,
+        writer: impl AsyncWrite + Unpin,
+        rows: impl IntoIterator<Item = impl Cells>,
+    ) -> io::Result<()> {
+        self.write_csv_async_with_schema(writer, rows, DefaultCellsSchema::default())
+            .await
+    }
+
+    /// Write csv using the columns defined by [`CellsSchema`] to [`AsyncWrite`].
+    ///
+    /// This method is available only when the `tokio` feature is enabled.
+    pub async fn write_csv_async_with_schema<T>(
+        &self,
+        mut writer: impl AsyncWrite + Unpin,
+        rows: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+    ) -> io::Result<()> {
+        let mut buf = Vec::new();
+        let mut w = CsvRowWriter::new(&mut self.csv_writer(&mut buf), &schema, self)?;
+        writer.write_all(&buf).await?;
+        for (i, item) in rows.into_iter().enumerate() {
+            buf.clear();
+            w.write_row(&mut self.csv_writer(&mut buf), &schema, item.borrow())?;
+            writer.write_all(&buf).await?;
+            if (i + 1) % FLUSH_INTERVAL == 0 {
+                writer.flush().await?;
+            }
+        }
+        writer.flush().await
+    }
+}
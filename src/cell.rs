@@ -1,13 +1,20 @@
-use crate::{Cells, CellsFormatter};
+use crate::ansi::{write_link, Sgr};
+use crate::{Cells, CellsFormatter, Color};
 
 use self::HorizontalAlignment::*;
 use std::{cmp::min, fmt::*};
 
 /// Cell`s style.
-#[derive(Clone, Copy, Default)]
-
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CellStyle {
     pub(crate) align_h: Option<HorizontalAlignment>,
+    pub(crate) pad_left: Option<usize>,
+    pub(crate) pad_right: Option<usize>,
+    pub(crate) min_width: Option<usize>,
+    pub(crate) color: Option<Color>,
+    pub(crate) background: Option<Color>,
+    pub(crate) bold: Option<bool>,
 }
 impl CellStyle {
     pub fn new() -> Self {
@@ -20,23 +27,97 @@ impl CellStyle {
     pub fn or(self, style: CellStyle) -> CellStyle {
         CellStyle {
             align_h: self.align_h.or(style.align_h),
+            pad_left: self.pad_left.or(style.pad_left),
+            pad_right: self.pad_right.or(style.pad_right),
+            min_width: self.min_width.or(style.min_width),
+            color: self.color.or(style.color),
+            background: self.background.or(style.background),
+            bold: self.bold.or(style.bold),
         }
     }
 
     pub fn align_h(self, value: HorizontalAlignment) -> Self {
         CellStyle {
             align_h: Some(value),
+            ..self
+        }
+    }
+
+    /// Set the number of spaces added to the left of the cell content in addition to the column padding.
+    pub fn pad_left(self, value: usize) -> Self {
+        CellStyle {
+            pad_left: Some(value),
+            ..self
+        }
+    }
+
+    /// Set the number of spaces added to the right of the cell content in addition to the column padding.
+    pub fn pad_right(self, value: usize) -> Self {
+        CellStyle {
+            pad_right: Some(value),
+            ..self
+        }
+    }
+
+    /// Set the minimum width of the cell including the padding specified by the cell.
+    pub fn min_width(self, value: usize) -> Self {
+        CellStyle {
+            min_width: Some(value),
+            ..self
+        }
+    }
+
+    /// Set the text color displayed using ANSI escape sequences.
+    pub fn color(self, value: Color) -> Self {
+        CellStyle {
+            color: Some(value),
+            ..self
+        }
+    }
+
+    /// Set the background color displayed using ANSI escape sequences.
+    pub fn background(self, value: Color) -> Self {
+        CellStyle {
+            background: Some(value),
+            ..self
+        }
+    }
+
+    /// Set whether the text is displayed in bold using ANSI escape sequences.
+    pub fn bold(self, value: bool) -> Self {
+        CellStyle {
+            bold: Some(value),
+            ..self
+        }
+    }
+
+    pub(crate) fn sgr(&self) -> Sgr {
+        Sgr {
+            fg: self.color,
+            bg: self.background,
+            bold: self.bold.unwrap_or(false),
         }
     }
 }
 
 /// Horizontal alignments for cell's content.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HorizontalAlignment {
     Left,
     Center,
     Right,
 }
+impl HorizontalAlignment {
+    /// Returns the alignment mirrored horizontally.
+    pub(crate) fn mirrored(self) -> Self {
+        match self {
+            Left => Right,
+            Center => Center,
+            Right => Left,
+        }
+    }
+}
 
 /// A data structure that can be formatted into a cell.
 ///
@@ -66,6 +147,14 @@ pub trait RawCell {
     /// Output the cell text to given buffer.
     fn fmt(&self, s: &mut String);
 
+    /// Output the cell text to given buffer, and return an error if formatting failed.
+    ///
+    /// The default implementation calls [`fmt`](Self::fmt) and never fails.
+    fn try_fmt(&self, s: &mut String) -> Result {
+        self.fmt(s);
+        Ok(())
+    }
+
     /// Return cell's style.
     fn style(&self) -> CellStyle {
         CellStyle::default()
@@ -74,6 +163,18 @@ pub trait RawCell {
     fn style_for_body(&self) -> CellStyle {
         CellStyle::default()
     }
+
+    /// Return the text of the footnote attached to the cell.
+    fn footnote(&self) -> Option<&str> {
+        None
+    }
+
+    /// Return the key used to localize the cell when it is used as a header.
+    ///
+    /// See [`HeaderResolver`](crate::HeaderResolver) for details.
+    fn header_key(&self) -> Option<&str> {
+        None
+    }
 }
 impl RawCell for () {
     fn fmt(&self, _: &mut String) {}
@@ -82,23 +183,41 @@ impl<T: ?Sized + RawCell> RawCell for &T {
     fn fmt(&self, s: &mut String) {
         T::fmt(*self, s)
     }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        T::try_fmt(*self, s)
+    }
     fn style(&self) -> CellStyle {
         T::style(*self)
     }
     fn style_for_body(&self) -> CellStyle {
         T::style_for_body(*self)
     }
+    fn footnote(&self) -> Option<&str> {
+        T::footnote(*self)
+    }
+    fn header_key(&self) -> Option<&str> {
+        T::header_key(*self)
+    }
 }
 impl<T: ?Sized + RawCell> RawCell for &mut T {
     fn fmt(&self, s: &mut String) {
         T::fmt(*self, s)
     }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        T::try_fmt(*self, s)
+    }
     fn style(&self) -> CellStyle {
         T::style(*self)
     }
     fn style_for_body(&self) -> CellStyle {
         T::style_for_body(*self)
     }
+    fn footnote(&self) -> Option<&str> {
+        T::footnote(*self)
+    }
+    fn header_key(&self) -> Option<&str> {
+        T::header_key(*self)
+    }
 }
 impl<T: RawCell> RawCell for Option<T> {
     fn fmt(&self, s: &mut String) {
@@ -106,6 +225,12 @@ impl<T: RawCell> RawCell for Option<T> {
             value.fmt(s);
         }
     }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        match self {
+            Some(value) => value.try_fmt(s),
+            None => Ok(()),
+        }
+    }
     fn style(&self) -> CellStyle {
         if let Some(value) = self {
             value.style()
@@ -120,6 +245,12 @@ impl<T: RawCell> RawCell for Option<T> {
             CellStyle::default()
         }
     }
+    fn footnote(&self) -> Option<&str> {
+        self.as_ref().and_then(|value| value.footnote())
+    }
+    fn header_key(&self) -> Option<&str> {
+        self.as_ref().and_then(|value| value.header_key())
+    }
 }
 impl<T: RawCell, E: RawCell> RawCell for std::result::Result<T, E> {
     fn fmt(&self, s: &mut String) {
@@ -128,6 +259,12 @@ impl<T: RawCell, E: RawCell> RawCell for std::result::Result<T, E> {
             Err(value) => value.fmt(s),
         }
     }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        match self {
+            Ok(value) => value.try_fmt(s),
+            Err(value) => value.try_fmt(s),
+        }
+    }
     fn style(&self) -> CellStyle {
         match self {
             Ok(value) => value.style(),
@@ -140,17 +277,31 @@ impl<T: RawCell, E: RawCell> RawCell for std::result::Result<T, E> {
             Err(value) => value.style_for_body(),
         }
     }
+    fn footnote(&self) -> Option<&str> {
+        match self {
+            Ok(value) => value.footnote(),
+            Err(value) => value.footnote(),
+        }
+    }
+    fn header_key(&self) -> Option<&str> {
+        match self {
+            Ok(value) => value.header_key(),
+            Err(value) => value.header_key(),
+        }
+    }
 }
 
 struct DisplayCellSource<T: Display>(T);
 impl<T: Display> RawCell for DisplayCellSource<T> {
     fn fmt(&self, s: &mut String) {
-        write!(s, "{}", self.0).unwrap()
+        let _ = write!(s, "{}", self.0);
     }
 }
 
 /// Create [`Cell`] from [`Display`].
 ///
+/// This never panics. If [`Display::fmt`] returns an error, the text written before the error is used.
+///
 /// The returned value owns the value passed in.
 /// Therefore, the returned value can not be move out of the lifetime of the passed value.
 ///
@@ -178,12 +329,25 @@ pub fn cell(value: impl Display) -> Cell<impl RawCell> {
 struct FmtFnCellSource<F>(F);
 impl<F: Fn(&mut String) -> Result> RawCell for FmtFnCellSource<F> {
     fn fmt(&self, s: &mut String) {
-        (self.0)(s).unwrap()
+        let _ = (self.0)(s);
+    }
+}
+
+struct TryFmtFnCellSource<F>(F);
+impl<F: Fn(&mut String) -> Result> RawCell for TryFmtFnCellSource<F> {
+    fn fmt(&self, s: &mut String) {
+        let _ = (self.0)(s);
+    }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        (self.0)(s)
     }
 }
 
 /// Create [`Cell`] from closure that call [`std::write!`] macro.
 ///
+/// This never panics. If the closure returns an error, the text written before the error is used.
+/// Use [`try_cell_by`] to report the error when the grid is displayed.
+///
 /// # Examples
 ///
 /// ```
@@ -198,6 +362,62 @@ pub fn cell_by<F: Fn(&mut String) -> Result>(f: F) -> Cell<impl RawCell> {
     Cell::new(FmtFnCellSource(f))
 }
 
+/// Create [`Cell`] from closure that call [`std::write!`] macro, and whose formatting error is reported.
+///
+/// If the closure returns an error, the cell is displayed as `#ERROR`
+/// and [`GridBuilder::try_to_string`](crate::GridBuilder::try_to_string) returns [`CellError`](crate::CellError).
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+///
+/// let mut g = GridBuilder::new();
+/// g.push(|b| {
+///     b.push("a");
+///     b.push(try_cell_by(|_| Err(std::fmt::Error)));
+/// });
+/// assert_eq!(g.to_string(), " a | #ERROR |\n");
+///
+/// let e = g.try_to_string().unwrap_err();
+/// assert_eq!((e.row(), e.column()), (0, 1));
+/// ```
+pub fn try_cell_by<F: Fn(&mut String) -> Result>(f: F) -> Cell<impl RawCell> {
+    Cell::new(TryFmtFnCellSource(f))
+}
+
+struct LinkCellSource<T, U> {
+    text: T,
+    url: U,
+}
+impl<T: Display, U: Display> RawCell for LinkCellSource<T, U> {
+    fn fmt(&self, s: &mut String) {
+        let _ = write_link(s, &self.text, &self.url);
+    }
+}
+
+/// Create [`Cell`] displayed as a terminal hyperlink using the OSC 8 escape sequence.
+///
+/// Only `text` is counted in the width of the cell.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let mut g = GridBuilder::new();
+/// g.push(|b| {
+///     b.push(cell_link("#1", "https://example.com/1"));
+///     b.push("x");
+/// });
+/// assert_eq!(
+///     format!("{g}"),
+///     " \x1b]8;;https://example.com/1\x1b\\#1\x1b]8;;\x1b\\ | x |\n"
+/// );
+/// ```
+pub fn cell_link(text: impl Display, url: impl Display) -> Cell<impl RawCell> {
+    Cell::new(LinkCellSource { text, url })
+}
+
 /// Create [`Cell`] via runtime expression interpolation, as in [`format!`].
 ///
 /// Use the `format!` syntax to create [`Cell`]. See [`std::fmt`] for more information.
@@ -287,6 +507,68 @@ macro_rules! cell {
     };
 }
 
+/// Create [`Cell`] via runtime expression interpolation, and whose formatting error is reported.
+///
+/// This is the same as [`cell!`](crate::cell!) except that it uses [`try_cell_by`] instead of [`cell_by`].
+#[macro_export]
+macro_rules! try_cell {
+    ($ ( $ arg : tt ) *) => { {
+            use std::fmt::Write;
+            $crate::try_cell_by(move |f| write!(f, $($arg)*))
+        }
+    };
+}
+
+struct FootnoteCellSource<T> {
+    source: T,
+    footnote: String,
+}
+impl<T: RawCell> RawCell for FootnoteCellSource<T> {
+    fn fmt(&self, s: &mut String) {
+        self.source.fmt(s)
+    }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        self.source.try_fmt(s)
+    }
+    fn style(&self) -> CellStyle {
+        self.source.style()
+    }
+    fn style_for_body(&self) -> CellStyle {
+        self.source.style_for_body()
+    }
+    fn footnote(&self) -> Option<&str> {
+        Some(&self.footnote)
+    }
+    fn header_key(&self) -> Option<&str> {
+        self.source.header_key()
+    }
+}
+
+struct HeaderKeyCellSource<T> {
+    source: T,
+    key: String,
+}
+impl<T: RawCell> RawCell for HeaderKeyCellSource<T> {
+    fn fmt(&self, s: &mut String) {
+        self.source.fmt(s)
+    }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        self.source.try_fmt(s)
+    }
+    fn style(&self) -> CellStyle {
+        self.source.style()
+    }
+    fn style_for_body(&self) -> CellStyle {
+        self.source.style_for_body()
+    }
+    fn footnote(&self) -> Option<&str> {
+        self.source.footnote()
+    }
+    fn header_key(&self) -> Option<&str> {
+        Some(&self.key)
+    }
+}
+
 /// Implementation of [`RawCell`] that can specify styles.
 pub struct Cell<T> {
     source: T,
@@ -296,9 +578,18 @@ impl<T: RawCell> RawCell for Cell<T> {
     fn fmt(&self, s: &mut String) {
         self.source.fmt(s)
     }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        self.source.try_fmt(s)
+    }
     fn style(&self) -> CellStyle {
         self.style
     }
+    fn footnote(&self) -> Option<&str> {
+        self.source.footnote()
+    }
+    fn header_key(&self) -> Option<&str> {
+        self.source.header_key()
+    }
 }
 impl<T: RawCell> Cells for Cell<T> {
     fn fmt(f: &mut CellsFormatter<Self>) {
@@ -328,6 +619,72 @@ impl<T: RawCell> Cell<T> {
         self.with_align_h(Center)
     }
 
+    /// Return the cell with extra padding on both sides.
+    ///
+    /// The padding is added on top of the column padding.
+    ///
+    /// ```rust
+    /// use text_grid::*;
+    ///
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| b.push("group"));
+    /// g.push(|b| b.push(cell("item").pad(2)));
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  group    |
+    ///    item   |
+    /// "#);
+    /// ```
+    pub fn pad(self, value: usize) -> Self {
+        self.pad_left(value).pad_right(value)
+    }
+
+    /// Return the cell with extra padding on the left side.
+    pub fn pad_left(self, value: usize) -> Self {
+        Cell {
+            source: self.source,
+            style: self.style.pad_left(value),
+        }
+    }
+
+    /// Return the cell with extra padding on the right side.
+    pub fn pad_right(self, value: usize) -> Self {
+        Cell {
+            source: self.source,
+            style: self.style.pad_right(value),
+        }
+    }
+
+    /// Return the cell that guarantees a minimum width of the column.
+    ///
+    /// ```rust
+    /// use text_grid::*;
+    ///
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push(cell("a").min_width(5));
+    ///     b.push("b");
+    /// });
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  a     | b |
+    /// "#);
+    /// ```
+    pub fn min_width(self, value: usize) -> Self {
+        Cell {
+            source: self.source,
+            style: self.style.min_width(value),
+        }
+    }
+
+    /// Return the cell with the specified style applied.
+    ///
+    /// Each element of the style that is not empty overrides the current style.
+    pub fn with_style(self, style: CellStyle) -> Self {
+        Cell {
+            source: self.source,
+            style: style.or(self.style),
+        }
+    }
+
     /// Return the cell with aligned baseline.
     ///
     /// ```rust
@@ -371,12 +728,81 @@ impl<T: RawCell> Cell<T> {
             style: self.style.or(style),
         }
     }
+    /// Return the cell with a footnote.
+    ///
+    /// A numbered marker such as `[1]` is appended to the cell,
+    /// and the footnotes are listed with their numbers below the table.
+    /// Cells with the same footnote text share the same number.
+    ///
+    /// ```rust
+    /// use text_grid::*;
+    ///
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push(cell("value").footnote("sampled"));
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(cell(100).right().footnote("approximate"));
+    /// });
+    /// g.push(|b| {
+    ///     b.push("b");
+    ///     b.push(2);
+    /// });
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | value[1] |
+    /// ------|----------|
+    ///  a    |   100[2] |
+    ///  b    |        2 |
+    /// [1] sampled
+    /// [2] approximate
+    /// "#);
+    /// ```
+    pub fn footnote(self, text: impl Display) -> Cell<impl RawCell> {
+        Cell {
+            source: FootnoteCellSource {
+                source: self.source,
+                footnote: text.to_string(),
+            },
+            style: self.style,
+        }
+    }
+
+    /// Return the cell with the key used to localize it when it is used as a header.
+    ///
+    /// If the [`HeaderResolver`](crate::HeaderResolver) of the grid resolves `key`,
+    /// the resolved text is displayed instead of the text of the cell.
+    ///
+    /// ```rust
+    /// use text_grid::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let ja = HashMap::from([("col.name".to_string(), "名前".to_string())]);
+    /// let mut g = GridBuilder::new();
+    /// g.set_header_resolver(ja);
+    /// g.push_header(|b| b.push(cell("name").header_key("col.name")));
+    /// g.push(|b| b.push("apple"));
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  名前  |
+    /// -------|
+    ///  apple |
+    /// "#);
+    /// ```
+    pub fn header_key(self, key: impl Display) -> Cell<impl RawCell> {
+        Cell {
+            source: HeaderKeyCellSource {
+                source: self.source,
+                key: key.to_string(),
+            },
+            style: self.style,
+        }
+    }
+
     fn with_align_h(self, align_h: HorizontalAlignment) -> Self {
         Cell {
             source: self.source,
-            style: CellStyle {
-                align_h: Some(align_h),
-            },
+            style: self.style.align_h(align_h),
         }
     }
 }
@@ -394,9 +820,7 @@ macro_rules! impl_cell_source {
                 write!(s, "{}", self).unwrap()
             }
             fn style_for_body(&self) -> CellStyle {
-                CellStyle {
-                    align_h: Some($align),
-                }
+                CellStyle::new().align_h($align)
             }
         }
         impl Cells for $t {
@@ -501,6 +925,58 @@ macro_rules! cells_f {
     };
 }
 
+/// Format of float numbers used by [`CellsFormatter::column_float`](crate::CellsFormatter::column_float).
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// assert_eq!(FloatFormat::new().format(1.5), "1.5");
+/// assert_eq!(FloatFormat::fixed(2).format(1.5), "1.50");
+/// assert_eq!(FloatFormat::exp(1).format(1500.0), "1.5e3");
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct FloatFormat(FloatFormatKind);
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+enum FloatFormatKind {
+    #[default]
+    Display,
+    Fixed(usize),
+    Exp(usize),
+}
+
+impl FloatFormat {
+    /// Format with [`Display`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Format with `precision` digits after the decimal point, like `{:.2}`.
+    pub fn fixed(precision: usize) -> Self {
+        Self(FloatFormatKind::Fixed(precision))
+    }
+
+    /// Format in scientific notation with `precision` digits after the decimal point, like `{:.2e}`.
+    pub fn exp(precision: usize) -> Self {
+        Self(FloatFormatKind::Exp(precision))
+    }
+
+    /// Returns the string of `value` in this format.
+    pub fn format(&self, value: f64) -> String {
+        match self.0 {
+            FloatFormatKind::Display => value.to_string(),
+            FloatFormatKind::Fixed(precision) => format!("{value:.precision$}"),
+            FloatFormatKind::Exp(precision) => format!("{value:.precision$e}"),
+        }
+    }
+
+    /// Create [`Cells`] of `value` in this format, aligned in the same way as [`cells_f`].
+    pub fn cells(&self, value: f64) -> impl Cells {
+        cells_f(self.format(value))
+    }
+}
+
 /// Create [`Cells`] for float numbers from [`Display`].
 ///
 /// Format in the same way as [`cells_f!`] macro.
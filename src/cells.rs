@@ -1,4 +1,5 @@
 use std::marker::PhantomData;
+use std::ops::Deref;
 
 use derive_ex::derive_ex;
 
@@ -75,6 +76,247 @@ use crate::{CellsFormatter, RawCell};
 ///  C |   | 2 | 3 |
 /// ");
 /// ```
+///
+/// ## `#[cells(skip)]`
+/// Fields with `#[cells(skip)]` are not displayed.
+/// The types of the skipped fields do not need to implement [`Cells`].
+///
+/// ```
+/// use text_grid::{to_grid, Cells};
+///
+/// struct Secret(&'static str);
+///
+/// #[derive(Cells)]
+/// struct X {
+///     name: &'static str,
+///     #[cells(skip)]
+///     password: Secret,
+/// }
+/// let g = to_grid(vec![X { name: "a", password: Secret("p") }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  name |
+/// ------|
+///  a    |
+/// "#);
+/// ```
+///
+/// ## `#[cells(header = "...")]`
+/// Use the specified text as the column header instead of the field name.
+///
+/// ## `#[cells(rename_all = "...")]`
+/// Convert the field names of all columns to the specified case.
+/// The possible values are `"lowercase"`, `"UPPERCASE"`, `"PascalCase"`, `"camelCase"`, `"snake_case"`,
+/// `"SCREAMING_SNAKE_CASE"`, `"kebab-case"`, `"SCREAMING-KEBAB-CASE"` and `"Title Case"`.
+/// `#[cells(header = "...")]` takes precedence over this attribute.
+///
+/// ```
+/// use text_grid::{to_grid, Cells};
+///
+/// #[derive(Cells)]
+/// #[cells(rename_all = "Title Case")]
+/// struct X {
+///     file_name: &'static str,
+///     #[cells(header = "bytes")]
+///     file_size: u32,
+/// }
+/// let g = to_grid(vec![X { file_name: "a.txt", file_size: 10 }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  File Name | bytes |
+/// -----------|-------|
+///  a.txt     |    10 |
+/// "#);
+/// ```
+///
+/// ## `#[cells(format = "...")]`
+/// Format the field with the specified format string using [`cell!`](crate::cell!).
+/// The type of the field does not need to implement [`Cells`].
+///
+/// ```
+/// use text_grid::{to_grid, Cells};
+///
+/// #[derive(Cells)]
+/// struct X {
+///     name: &'static str,
+///     #[cells(format = "{:.2}")]
+///     value: f64,
+/// }
+/// let g = to_grid(vec![X { name: "a", value: 1.0 }, X { name: "b", value: 10.125 }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  name | value |
+/// ------|-------|
+///  a    | 1.00  |
+///  b    | 10.12 |
+/// "#);
+/// ```
+///
+/// ## `#[cells(flatten)]`
+/// Display the columns of the field at the same level as the other fields, without the group header of the field name.
+///
+/// ```
+/// use text_grid::{to_grid, Cells};
+///
+/// #[derive(Cells)]
+/// struct Meta {
+///     id: u32,
+/// }
+///
+/// #[derive(Cells)]
+/// struct X {
+///     #[cells(flatten)]
+///     meta: Meta,
+///     name: &'static str,
+/// }
+/// let g = to_grid(vec![X { meta: Meta { id: 1 }, name: "a" }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  id | name |
+/// ----|------|
+///   1 | a    |
+/// "#);
+/// ```
+///
+/// ## `#[cells(group = "...")]`
+/// Display the fields with the same group under a shared header.
+///
+/// All fields of a group are displayed together at the position of the first field of the group,
+/// even if other fields are declared between them.
+///
+/// ```
+/// use text_grid::{to_grid, Cells};
+///
+/// #[derive(Cells)]
+/// struct X {
+///     name: &'static str,
+///     #[cells(group = "latency")]
+///     min: u32,
+///     #[cells(group = "latency")]
+///     max: u32,
+/// }
+/// let g = to_grid(vec![X { name: "a", min: 1, max: 20 }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  name |  latency  |
+/// ------|-----------|
+///       | min | max |
+/// ------|-----|-----|
+///  a    |   1 |  20 |
+/// "#);
+/// ```
+///
+/// ## `#[cells(with = "...")]`
+/// Display the field using the function at the specified path.
+/// The function takes a reference to the field and returns a value that implements [`Cells`].
+/// The type of the field does not need to implement [`Cells`].
+///
+/// ```
+/// use std::time::Duration;
+/// use text_grid::{cell, to_grid, Cells};
+///
+/// fn millis(d: &Duration) -> u128 {
+///     d.as_millis()
+/// }
+///
+/// #[derive(Cells)]
+/// struct X {
+///     name: &'static str,
+///     #[cells(with = "millis")]
+///     time: Duration,
+/// }
+/// let g = to_grid(vec![X { name: "a", time: Duration::from_secs(2) }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  name | time |
+/// ------|------|
+///  a    | 2000 |
+/// "#);
+/// ```
+///
+/// ## `#[cells(variant_header = "...")]`, `#[cells(hide_variant)]`
+/// For enums, `variant_header` sets the header of the column of variant names,
+/// and `hide_variant` hides the column.
+///
+/// ```
+/// use text_grid::{to_grid, Cells};
+///
+/// #[derive(Cells)]
+/// #[cells(variant_header = "kind")]
+/// enum X {
+///     A,
+///     B { x: u32 },
+/// }
+/// let g = to_grid(vec![X::A, X::B { x: 1 }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  kind | x |
+/// ------|---|
+///  A    |   |
+///  B    | 1 |
+/// "#);
+/// ```
+///
+/// ## `#[cells(order = N)]`
+/// Display the columns in ascending order of `N` instead of the declaration order.
+/// Fields without `order` are treated as `order = 0`, and fields with the same order keep the declaration order.
+///
+/// ```
+/// use text_grid::{to_grid, Cells};
+///
+/// #[derive(Cells)]
+/// struct X {
+///     a: u32,
+///     #[cells(order = -1)]
+///     b: u32,
+///     #[cells(order = 1)]
+///     c: u32,
+///     d: u32,
+/// }
+/// let g = to_grid(vec![X { a: 1, b: 2, c: 3, d: 4 }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  b | a | d | c |
+/// ---|---|---|---|
+///  2 | 1 | 4 | 3 |
+/// "#);
+/// ```
+///
+/// ## `#[cells(skip_if_none)]`
+/// Hide the column when the values of all rows are `None`.
+/// See [`ColumnStyle::hide_if_empty`](crate::ColumnStyle::hide_if_empty) for details.
+///
+/// For structs, this can only be used for fields of type `Option<_>`.
+/// For enums, the value of a row whose variant does not have the field is treated as `None`.
+/// Since the column is hidden when all of its cells are empty,
+/// `Some` values that are displayed as an empty string, such as `Some("")`, are treated like `None`.
+///
+/// ```
+/// use text_grid::{to_grid, Cells};
+///
+/// #[derive(Cells)]
+/// struct X {
+///     name: &'static str,
+///     #[cells(skip_if_none)]
+///     error: Option<&'static str>,
+/// }
+/// let g = to_grid(vec![X { name: "a", error: None }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  name |
+/// ------|
+///  a    |
+/// "#);
+///
+/// let g = to_grid(vec![X { name: "a", error: None }, X { name: "b", error: Some("failed") }]);
+/// assert_eq!(format!("\n{g}"), r#"
+///  name | error  |
+/// ------|--------|
+///  a    |        |
+///  b    | failed |
+/// "#);
+/// ```
+///
+/// ```compile_fail
+/// use text_grid::Cells;
+///
+/// #[derive(Cells)]
+/// struct X {
+///     #[cells(skip_if_none)]
+///     error: &'static str,
+/// }
+/// ```
 pub use text_grid_macros::Cells;
 
 /// A data structure that can be formatted into cells.
@@ -85,6 +327,46 @@ pub use text_grid_macros::Cells;
 pub trait Cells {
     /// Define columns. see [`CellsFormatter`] for details.
     fn fmt(f: &mut CellsFormatter<Self>);
+
+    /// Define footer cells calculated from all rows.
+    ///
+    /// This is used by [`DefaultCellsSchema`]. See [`CellsSchema::fmt_footer`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use text_grid::*;
+    /// struct Item {
+    ///     name: &'static str,
+    ///     price: u32,
+    /// }
+    /// impl Cells for Item {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("name", |x| x.name);
+    ///         f.column("price", |x| x.price);
+    ///     }
+    ///     fn fmt_footer(f: &mut CellsFormatter<[&Self]>) {
+    ///         f.column("name", |_| "total");
+    ///         f.column("price", |xs| xs.iter().map(|x| x.price).sum::<u32>());
+    ///     }
+    /// }
+    ///
+    /// let rows = [
+    ///     Item { name: "apple", price: 100 },
+    ///     Item { name: "orange", price: 250 },
+    /// ];
+    /// let g = to_grid(&rows);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///   name  | price |
+    /// --------|-------|
+    ///  apple  |   100 |
+    ///  orange |   250 |
+    /// --------|-------|
+    ///  total  |   350 |
+    /// "#);
+    /// ```
+    fn fmt_footer(f: &mut CellsFormatter<[&Self]>) {
+        f.empty_footer(|f| Self::fmt(&mut f.filter_map(|_| None)));
+    }
 }
 impl Cells for () {
     fn fmt(_: &mut CellsFormatter<Self>) {}
@@ -93,11 +375,31 @@ impl<T: ?Sized + Cells> Cells for &T {
     fn fmt(f: &mut CellsFormatter<Self>) {
         T::fmt(&mut f.unref());
     }
+    fn fmt_footer(f: &mut CellsFormatter<[&Self]>) {
+        fmt_footer_deref::<Self, T>(f)
+    }
 }
 impl<T: ?Sized + Cells> Cells for &mut T {
     fn fmt(f: &mut CellsFormatter<Self>) {
         T::fmt(&mut f.unref());
     }
+    fn fmt_footer(f: &mut CellsFormatter<[&Self]>) {
+        fmt_footer_deref::<Self, T>(f)
+    }
+}
+impl<T: ?Sized + Cells> Cells for Box<T> {
+    fn fmt(f: &mut CellsFormatter<Self>) {
+        T::fmt(&mut f.map(|x| &**x));
+    }
+    fn fmt_footer(f: &mut CellsFormatter<[&Self]>) {
+        fmt_footer_deref::<Self, T>(f)
+    }
+}
+fn fmt_footer_deref<P: Deref<Target = T>, T: ?Sized + Cells>(f: &mut CellsFormatter<[&P]>) {
+    f.map_with(
+        |xs| xs.iter().map(|x| &***x).collect::<Vec<&T>>(),
+        |f| T::fmt_footer(&mut f.map(|xs| xs.as_slice())),
+    );
 }
 impl<T: Cells, const N: usize> Cells for [T; N] {
     fn fmt(f: &mut CellsFormatter<Self>) {
@@ -117,6 +419,103 @@ impl<T: Cells, E: RawCell> Cells for std::result::Result<T, E> {
     }
 }
 
+/// An object-safe counterpart of [`Cells`].
+///
+/// Since [`Cells::fmt`] does not take a value, `Box<dyn Cells>` cannot be used.
+/// `Box<dyn DynCells>` implements [`Cells`] instead,
+/// so that rows of different types can be displayed in the same grid.
+/// This trait is implemented for all types that implement [`Cells`].
+///
+/// The columns depend on the value of each row, so no header rows are displayed,
+/// and formats that require the columns in advance, such as csv, are not supported.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct Disk {
+///     name: &'static str,
+///     size: u64,
+/// }
+/// impl Cells for Disk {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("size", |s| s.size);
+///     }
+/// }
+/// struct Note(&'static str);
+/// impl Cells for Note {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("note", |s| s.0);
+///     }
+/// }
+///
+/// let rows: Vec<Box<dyn DynCells>> = vec![
+///     Box::new(Disk { name: "sda", size: 512 }),
+///     Box::new(Note("offline")),
+///     Box::new(Disk { name: "sdb", size: 2048 }),
+/// ];
+/// let mut g = GridBuilder::new();
+/// g.extend_body(&rows);
+/// assert_eq!(format!("\n{g}"), r#"
+///  sda     |  512 |
+///  offline |
+///  sdb     | 2048 |
+/// "#);
+/// ```
+pub trait DynCells {
+    /// Define columns for this value. see [`CellsFormatter`] for details.
+    fn fmt_dyn(&self, f: &mut CellsFormatter<()>);
+}
+impl<T: Cells> DynCells for T {
+    fn fmt_dyn(&self, f: &mut CellsFormatter<()>) {
+        T::fmt(&mut f.with_source(self));
+    }
+}
+impl Cells for dyn DynCells + '_ {
+    fn fmt(f: &mut CellsFormatter<Self>) {
+        if let Some(source) = f.source() {
+            source.fmt_dyn(&mut f.with_source(&()));
+        }
+    }
+}
+
+/// Derive [`CellsSchema`](trait@CellsSchema) for a type that holds the options of the columns.
+///
+/// `#[cells(source = ...)]` specifies [`CellsSchema::Source`],
+/// and `#[cells(fmt = ...)]` specifies the function called by [`CellsSchema::fmt`] with `&self`.
+/// `#[cells(fmt_footer = ...)]` optionally specifies the function called by [`CellsSchema::fmt_footer`].
+/// `#[cells(bound(...))]` adds where predicates such as `T: Cells` to the implementation.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+///
+/// #[derive(CellsSchema)]
+/// #[cells(source = Vec<u32>, fmt = Self::fmt_columns)]
+/// struct Columns {
+///     len: usize,
+/// }
+/// impl Columns {
+///     fn fmt_columns(&self, f: &mut CellsFormatter<Vec<u32>>) {
+///         for i in 0..self.len {
+///             f.column(i, |s| s[i]);
+///         }
+///     }
+/// }
+///
+/// let rows = [vec![1, 2, 3], vec![4, 5, 6]];
+/// let g = to_grid_with_schema(rows, Columns { len: 2 });
+/// assert_eq!(format!("\n{g}"), r#"
+///  0 | 1 |
+/// ---|---|
+///  1 | 2 |
+///  4 | 5 |
+/// "#);
+/// ```
+pub use text_grid_macros::CellsSchema;
+
 /// Column definitions.
 ///
 /// Define columns using [`CellsFormatter`].
@@ -159,6 +558,55 @@ pub trait CellsSchema {
 
     /// Define column information. see [`CellsFormatter`] for details.
     fn fmt(&self, f: &mut CellsFormatter<Self::Source>);
+
+    /// Define footer cells calculated from all rows.
+    ///
+    /// The footer is displayed below the body rows with a separator.
+    /// The cells should be defined in the same column layout as [`fmt`](Self::fmt).
+    /// Headers passed to [`CellsFormatter::column`] are ignored.
+    ///
+    /// By default, the column layout of [`fmt`](Self::fmt) is used with empty cells.
+    /// If all footer cells are empty, the footer is not displayed.
+    ///
+    /// # Examples
+    /// ```
+    /// use text_grid::*;
+    ///
+    /// struct Item {
+    ///     name: &'static str,
+    ///     price: u32,
+    /// }
+    /// struct ItemSchema;
+    ///
+    /// impl CellsSchema for ItemSchema {
+    ///     type Source = Item;
+    ///     fn fmt(&self, f: &mut CellsFormatter<Item>) {
+    ///         f.column("name", |x| x.name);
+    ///         f.column("price", |x| x.price);
+    ///     }
+    ///     fn fmt_footer(&self, f: &mut CellsFormatter<[&Item]>) {
+    ///         f.column("name", |_| "total");
+    ///         f.column("price", |xs| xs.iter().map(|x| x.price).sum::<u32>());
+    ///     }
+    /// }
+    ///
+    /// let rows = [
+    ///     Item { name: "apple", price: 100 },
+    ///     Item { name: "orange", price: 250 },
+    /// ];
+    /// let g = to_grid_with_schema(rows, ItemSchema);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///   name  | price |
+    /// --------|-------|
+    ///  apple  |   100 |
+    ///  orange |   250 |
+    /// --------|-------|
+    ///  total  |   350 |
+    /// "#);
+    /// ```
+    fn fmt_footer(&self, f: &mut CellsFormatter<[&Self::Source]>) {
+        f.empty_footer(|f| self.fmt(&mut f.filter_map(|_| None)));
+    }
 }
 
 /// Extension trait for [`CellsSchema`].
@@ -189,6 +637,11 @@ impl<T: CellsSchema> CellsSchema for Vec<T> {
             s.fmt(f);
         }
     }
+    fn fmt_footer(&self, f: &mut CellsFormatter<[&Self::Source]>) {
+        for s in self {
+            s.fmt_footer(f);
+        }
+    }
 }
 impl<T: CellsSchema> CellsSchema for [T] {
     type Source = T::Source;
@@ -197,12 +650,20 @@ impl<T: CellsSchema> CellsSchema for [T] {
             s.fmt(f);
         }
     }
+    fn fmt_footer(&self, f: &mut CellsFormatter<[&Self::Source]>) {
+        for s in self {
+            s.fmt_footer(f);
+        }
+    }
 }
 impl<T: ?Sized + CellsSchema> CellsSchema for &T {
     type Source = T::Source;
     fn fmt(&self, f: &mut CellsFormatter<Self::Source>) {
         T::fmt(self, f)
     }
+    fn fmt_footer(&self, f: &mut CellsFormatter<[&Self::Source]>) {
+        T::fmt_footer(self, f)
+    }
 }
 
 /// [`CellsSchema`] implementation that use [`Cells`].
@@ -215,8 +676,74 @@ impl<T: Cells + ?Sized> CellsSchema for DefaultCellsSchema<T> {
     fn fmt(&self, f: &mut CellsFormatter<Self::Source>) {
         T::fmt(f);
     }
+    fn fmt_footer(&self, f: &mut CellsFormatter<[&Self::Source]>) {
+        T::fmt_footer(f);
+    }
 }
 
+/// Create a value that implements [`Cells`] from pairs of a header and a value.
+///
+/// Each pair `"header": value` defines a column.
+/// The header must be a literal, and the value must implement [`Cells`].
+///
+/// Each use of `cells!` creates its own anonymous type,
+/// so it can be used to build one-off tables without declaring a struct.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let files = [("a.txt", 120), ("bb.txt", 5)];
+/// let g = to_grid(files.iter().map(|x| cells! { "name": x.0, "size": x.1 }));
+/// assert_eq!(format!("\n{g}"), r#"
+///   name  | size |
+/// --------|------|
+///  a.txt  |  120 |
+///  bb.txt |    5 |
+/// "#);
+/// ```
+pub use text_grid_macros::cells;
+
+/// Define a [`CellsSchema`] type from a function.
+///
+/// Applying `#[cells_schema]` to a function `fn name(f: &mut CellsFormatter<T>)` generates
+/// a unit struct `NameSchema` that implements `CellsSchema<Source = T>`,
+/// and a function `name_schema()` that returns it.
+/// The visibility of the generated items is the same as that of the function.
+///
+/// Unlike [`cells_schema()`], the schema has a nameable type, so it can be stored in fields and returned from functions.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+///
+/// struct Item {
+///     name: &'static str,
+///     price: u32,
+/// }
+///
+/// #[cells_schema]
+/// fn price_list(f: &mut CellsFormatter<Item>) {
+///     f.column("name", |x| x.name);
+///     f.column("price", |x| x.price);
+/// }
+///
+/// let rows = [
+///     Item { name: "apple", price: 100 },
+///     Item { name: "orange", price: 250 },
+/// ];
+/// let schema: PriceListSchema = price_list_schema();
+/// let g = to_grid_with_schema(rows, schema);
+/// assert_eq!(format!("\n{g}"), r#"
+///   name  | price |
+/// --------|-------|
+///  apple  |   100 |
+///  orange |   250 |
+/// "#);
+/// ```
+pub use text_grid_macros::cells_schema;
+
 /// Create [`CellsSchema`] from a closure.
 ///
 /// # Examples
@@ -282,6 +809,14 @@ macro_rules! impl_for_tuple {
             fn fmt(&self, f: &mut CellsFormatter<Self::Source>) {
                 $(self.$idx.fmt(&mut f.map(|x| &x.$idx));)*
             }
+            fn fmt_footer(&self, f: &mut CellsFormatter<[&Self::Source]>) {
+                $(
+                    f.map_with(
+                        |xs| xs.iter().map(|x| &x.$idx).collect::<Vec<_>>(),
+                        |f| self.$idx.fmt_footer(&mut f.map(|xs| xs.as_slice())),
+                    );
+                )*
+            }
         }
     };
 }
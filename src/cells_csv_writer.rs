@@ -1,29 +1,427 @@
-use std::{borrow::Borrow, io::Write};
+use std::{
+    borrow::{Borrow, Cow},
+    io::Write,
+};
 
-use csv::{StringRecord, Writer};
+use csv::{QuoteStyle, StringRecord, Terminator, Writer, WriterBuilder};
 
-use crate::{CellsFormatter, CellsSchema, CellsWrite, RawCell};
+use crate::{Cells, CellsFormatter, CellsSchema, CellsWrite, DefaultCellsSchema, RawCell};
 
-pub fn write_csv<T>(
+/// Write csv records using the columns defined by [`CellsSchema`] to [`csv::Writer`].
+///
+/// The header row is written first, followed by a record for each row of `source`.
+/// Use this to write csv with the settings of [`csv::WriterBuilder`].
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     a: u32,
+///     b: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("a", |s| s.a);
+///         f.column("b", |s| s.b);
+///     }
+/// }
+///
+/// let rows = [RowData { a: 300, b: 1 }, RowData { a: 2, b: 200 }];
+/// let mut w = csv::WriterBuilder::new().delimiter(b';').from_writer(Vec::new());
+/// write_csv_records(&mut w, rows, DefaultCellsSchema::default())?;
+/// let s = String::from_utf8(w.into_inner().unwrap()).unwrap();
+/// assert_eq!(s, "a;b\n300;1\n2;200\n");
+/// # Ok::<(), csv::Error>(())
+/// ```
+pub fn write_csv_records<T>(
     csv_writer: &mut Writer<impl Write>,
     source: impl IntoIterator<Item = impl Borrow<T>>,
     schema: impl CellsSchema<Source = T>,
-    separator: &str,
 ) -> csv::Result<()> {
-    let source = source.into_iter();
-    let mut w = CsvHeaderWriter::new(separator);
-    schema.fmt(&mut CellsFormatter::new(&mut w, None));
-    csv_writer.write_record(&w.record)?;
+    write_csv_records_with_options(csv_writer, source, schema, &CsvOptions::new())
+}
 
-    let mut w = CsvBodyWriter::new();
+fn write_csv_records_with_options<T>(
+    csv_writer: &mut Writer<impl Write>,
+    source: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+    options: &CsvOptions,
+) -> csv::Result<()> {
+    let mut w = CsvRowWriter::new(csv_writer, &schema, options)?;
     for item in source {
-        schema.fmt(&mut CellsFormatter::new(&mut w, Some(item.borrow())));
-        csv_writer.write_record(&w.record)?;
-        w.record.clear();
+        w.write_row(csv_writer, &schema, item.borrow())?;
     }
     Ok(())
 }
 
+pub(crate) struct CsvRowWriter<'a> {
+    options: &'a CsvOptions,
+    mask: Vec<bool>,
+    body: CsvBodyWriter,
+}
+impl<'a> CsvRowWriter<'a> {
+    pub(crate) fn new<T: ?Sized>(
+        csv_writer: &mut Writer<impl Write>,
+        schema: &impl CellsSchema<Source = T>,
+        options: &'a CsvOptions,
+    ) -> csv::Result<Self> {
+        let headers = header_record(schema);
+        let mask: Vec<bool> = headers
+            .iter()
+            .map(|h| options.is_column_visible(h))
+            .collect();
+        if !options.hide_headers {
+            options.write_record(csv_writer, &headers, &mask)?;
+        }
+        Ok(Self {
+            options,
+            mask,
+            body: CsvBodyWriter::new(),
+        })
+    }
+    pub(crate) fn write_row<T: ?Sized>(
+        &mut self,
+        csv_writer: &mut Writer<impl Write>,
+        schema: &impl CellsSchema<Source = T>,
+        item: &T,
+    ) -> csv::Result<()> {
+        let record = self.body.record(schema, item);
+        self.options.write_record(csv_writer, record, &self.mask)
+    }
+}
+
+/// Returns the headers of the columns, with the headers of nested columns joined by `.`.
+pub(crate) fn header_record<T: ?Sized>(schema: &impl CellsSchema<Source = T>) -> StringRecord {
+    let mut w = CsvHeaderWriter::new(".");
+    schema.fmt(&mut CellsFormatter::new(&mut w, None));
+    w.record
+}
+
+/// Options used when generating csv.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: i32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "=1+2", value: -3 },
+///     RowData { name: "@SUM(A1)", value: 4 },
+/// ];
+/// let csv = CsvOptions::new().escape_formulas(true).to_csv(rows);
+/// assert_eq!(csv, "name,value\n'=1+2,-3\n'@SUM(A1),4\n");
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CsvOptions {
+    escape_formulas: bool,
+    include_columns: Option<Vec<String>>,
+    exclude_columns: Vec<String>,
+    delimiter: Option<u8>,
+    quote_style: CsvQuoteStyle,
+    terminator: CsvTerminator,
+    hide_headers: bool,
+}
+
+impl CsvOptions {
+    /// Create a new `CsvOptions` with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `CsvOptions` for tab-separated values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let csv = CsvOptions::tsv().to_csv([["a b", "c"], ["d", "e\tf"]]);
+    /// assert_eq!(csv, "0\t1\na b\tc\nd\t\"e\tf\"\n");
+    /// ```
+    pub fn tsv() -> Self {
+        Self::new().delimiter(b'\t')
+    }
+
+    /// Set the field delimiter.
+    ///
+    /// The default for this is `b','`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not an ASCII character.
+    pub fn delimiter(mut self, value: u8) -> Self {
+        assert!(value.is_ascii(), "delimiter must be an ASCII character");
+        self.delimiter = Some(value);
+        self
+    }
+
+    /// Set when fields are quoted.
+    ///
+    /// The default for this is [`CsvQuoteStyle::Necessary`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let csv = CsvOptions::new()
+    ///     .quote_style(CsvQuoteStyle::NonNumeric)
+    ///     .to_csv([["a", "1"]]);
+    /// assert_eq!(csv, "0,1\n\"a\",1\n");
+    /// ```
+    pub fn quote_style(mut self, value: CsvQuoteStyle) -> Self {
+        self.quote_style = value;
+        self
+    }
+
+    /// Set the record terminator.
+    ///
+    /// The default for this is [`CsvTerminator::Lf`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is [`CsvTerminator::Any`] with a byte that is not an ASCII character.
+    pub fn terminator(mut self, value: CsvTerminator) -> Self {
+        if let CsvTerminator::Any(b) = value {
+            assert!(b.is_ascii(), "terminator must be an ASCII character");
+        }
+        self.terminator = value;
+        self
+    }
+
+    /// If false, the header record is not written.
+    ///
+    /// The default for this is `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let csv = CsvOptions::new()
+    ///     .headers(false)
+    ///     .terminator(CsvTerminator::Crlf)
+    ///     .to_csv([[1, 2], [3, 4]]);
+    /// assert_eq!(csv, "1,2\r\n3,4\r\n");
+    /// ```
+    pub fn headers(mut self, value: bool) -> Self {
+        self.hide_headers = !value;
+        self
+    }
+
+    /// If true, `'` is prepended to values that may be interpreted as formulas by spreadsheet applications.
+    ///
+    /// Values starting with `=`, `+`, `-`, `@`, tab or carriage return are escaped, except for numbers such as `-1`.
+    ///
+    /// The default for this is `false`.
+    pub fn escape_formulas(mut self, value: bool) -> Self {
+        self.escape_formulas = value;
+        self
+    }
+
+    /// Output only the columns whose header path matches one of `paths`.
+    ///
+    /// The header path is the headers of nested columns joined by `.`, as in the csv header.
+    /// A path also matches the columns nested within it, so `"y"` matches both `"y.b"` and `"y.c"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct X {
+    ///     a: u32,
+    ///     y: Y,
+    /// }
+    /// struct Y {
+    ///     b: u32,
+    ///     c: u32,
+    /// }
+    /// impl Cells for X {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("a", |s| s.a);
+    ///         f.column("y", |s| [s.y.b, s.y.c]);
+    ///         f.column("d", |s| s.y.b + s.y.c);
+    ///     }
+    /// }
+    /// let rows = [X { a: 1, y: Y { b: 2, c: 3 } }];
+    /// let csv = CsvOptions::new().include_columns(["a", "d"]).to_csv(&rows);
+    /// assert_eq!(csv, "a,d\n1,5\n");
+    /// let csv = CsvOptions::new().exclude_columns(["a"]).to_csv(&rows);
+    /// assert_eq!(csv, "y.0,y.1,d\n2,3,5\n");
+    /// ```
+    pub fn include_columns(mut self, paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.include_columns = Some(paths.into_iter().map(|p| p.into()).collect());
+        self
+    }
+
+    /// Do not output the columns whose header path matches one of `paths`.
+    ///
+    /// Paths are matched in the same way as [`include_columns`](Self::include_columns).
+    pub fn exclude_columns(mut self, paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.exclude_columns = paths.into_iter().map(|p| p.into()).collect();
+        self
+    }
+
+    /// Generate csv using the columns defined by [`Cells`](crate::Cells).
+    pub fn to_csv(&self, rows: impl IntoIterator<Item = impl Cells>) -> String {
+        self.to_csv_with_schema(rows, DefaultCellsSchema::default())
+    }
+
+    /// Generate csv using the columns defined by [`CellsSchema`].
+    pub fn to_csv_with_schema<T>(
+        &self,
+        rows: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+    ) -> String {
+        let mut bytes = Vec::new();
+        self.write_csv_with_schema(&mut bytes, rows, schema)
+            .unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    /// Write csv using the columns defined by [`Cells`](crate::Cells) to `writer`.
+    pub fn write_csv(
+        &self,
+        writer: impl Write,
+        rows: impl IntoIterator<Item = impl Cells>,
+    ) -> csv::Result<()> {
+        self.write_csv_with_schema(writer, rows, DefaultCellsSchema::default())
+    }
+
+    /// Write csv using the columns defined by [`CellsSchema`] to `writer`.
+    ///
+    /// Each row is written as soon as it is formatted, so that the whole csv is not held in memory.
+    /// The csv is flushed to `writer` before this function returns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     a: u32,
+    ///     b: u32,
+    /// }
+    /// impl Cells for RowData {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("a", |s| s.a);
+    ///         f.column("b", |s| s.b);
+    ///     }
+    /// }
+    ///
+    /// let rows = (0..3).map(|i| RowData { a: i, b: i * 10 });
+    /// let mut out = Vec::new();
+    /// CsvOptions::tsv().write_csv_with_schema(&mut out, rows, DefaultCellsSchema::default())?;
+    /// assert_eq!(String::from_utf8(out).unwrap(), "a\tb\n0\t0\n1\t10\n2\t20\n");
+    /// # Ok::<(), csv::Error>(())
+    /// ```
+    pub fn write_csv_with_schema<T>(
+        &self,
+        writer: impl Write,
+        rows: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+    ) -> csv::Result<()> {
+        let mut csv_writer = self.csv_writer(writer);
+        write_csv_records_with_options(&mut csv_writer, rows, schema, self)?;
+        csv_writer.flush()?;
+        Ok(())
+    }
+
+    pub(crate) fn csv_writer<W: Write>(&self, writer: W) -> Writer<W> {
+        let mut b = WriterBuilder::new();
+        if let Some(delimiter) = self.delimiter {
+            b.delimiter(delimiter);
+        }
+        b.quote_style(match self.quote_style {
+            CsvQuoteStyle::Necessary => QuoteStyle::Necessary,
+            CsvQuoteStyle::Always => QuoteStyle::Always,
+            CsvQuoteStyle::NonNumeric => QuoteStyle::NonNumeric,
+            CsvQuoteStyle::Never => QuoteStyle::Never,
+        });
+        b.terminator(match self.terminator {
+            CsvTerminator::Lf => Terminator::Any(b'\n'),
+            CsvTerminator::Crlf => Terminator::CRLF,
+            CsvTerminator::Any(b) => Terminator::Any(b),
+        });
+        b.from_writer(writer)
+    }
+
+    fn is_column_visible(&self, header: &str) -> bool {
+        let matches = |path: &String| {
+            header
+                .strip_prefix(path.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        };
+        if let Some(include_columns) = &self.include_columns {
+            if !include_columns.iter().any(matches) {
+                return false;
+            }
+        }
+        !self.exclude_columns.iter().any(matches)
+    }
+
+    fn write_record(
+        &self,
+        csv_writer: &mut Writer<impl Write>,
+        record: &StringRecord,
+        mask: &[bool],
+    ) -> csv::Result<()> {
+        let fields = record
+            .iter()
+            .zip(mask)
+            .filter(|&(_, &visible)| visible)
+            .map(|(field, _)| field);
+        csv_writer.write_record(fields.map(|field| {
+            if self.escape_formulas && is_formula(field) {
+                Cow::Owned(format!("'{field}").into_bytes())
+            } else {
+                Cow::Borrowed(field.as_bytes())
+            }
+        }))
+    }
+}
+
+/// When fields are quoted in csv.
+///
+/// Used by [`CsvOptions::quote_style`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum CsvQuoteStyle {
+    /// Quote fields only when necessary, such as when they contain the delimiter, a quote or a line break.
+    #[default]
+    Necessary,
+    /// Quote all fields.
+    Always,
+    /// Quote all fields that are not numbers.
+    NonNumeric,
+    /// Never quote fields, even if the output may be invalid csv.
+    Never,
+}
+
+/// Record terminator of csv.
+///
+/// Used by [`CsvOptions::terminator`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum CsvTerminator {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    Crlf,
+    /// The specified byte, which must be an ASCII character.
+    Any(u8),
+}
+
+fn is_formula(value: &str) -> bool {
+    value.starts_with(['=', '+', '-', '@', '\t', '\r']) && value.parse::<f64>().is_err()
+}
+
 struct CsvHeaderWriter<'a> {
     pub record: StringRecord,
     value: String,
@@ -68,15 +466,15 @@ impl CellsWrite for CsvHeaderWriter<'_> {
     }
 }
 
-struct CsvBodyWriter {
-    pub record: StringRecord,
+pub(crate) struct CsvBodyWriter {
+    record: StringRecord,
     value: String,
     is_merged: bool,
     has_content: bool,
 }
 
 impl CsvBodyWriter {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             record: StringRecord::new(),
             value: String::new(),
@@ -84,6 +482,17 @@ impl CsvBodyWriter {
             has_content: false,
         }
     }
+
+    /// Returns the values of the columns for `item`.
+    pub(crate) fn record<T: ?Sized>(
+        &mut self,
+        schema: &impl CellsSchema<Source = T>,
+        item: &T,
+    ) -> &StringRecord {
+        self.record.clear();
+        schema.fmt(&mut CellsFormatter::new(self, Some(item)));
+        &self.record
+    }
 }
 
 impl CellsWrite for CsvBodyWriter {
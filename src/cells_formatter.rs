@@ -1,4 +1,5 @@
 use crate::cell::*;
+use crate::grid_builder::ColumnStyle;
 use crate::Cells;
 use crate::CellsSchema;
 
@@ -11,14 +12,33 @@ pub struct CellsFormatter<'a, 'b, T: ?Sized> {
     w: &'a mut dyn CellsWrite,
     d: Option<&'b T>,
     stretch: bool,
+    empty_footer_depth: Option<&'a std::cell::Cell<usize>>,
 }
 
 impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
-    pub(crate) fn new(w: &'a mut dyn CellsWrite, d: Option<&'b T>) -> Self {
+    /// Create a formatter that passes the cells to `w`.
+    ///
+    /// If `d` is `None`, only the headers are written and [`CellsWrite::content`] receives `None`.
+    /// Pass it to [`CellsSchema::fmt`] or [`Cells::fmt`] to visit the columns.
+    /// See [`CellsWrite`] for details.
+    pub fn new(w: &'a mut dyn CellsWrite, d: Option<&'b T>) -> Self {
         Self {
             w,
             d,
             stretch: false,
+            empty_footer_depth: None,
+        }
+    }
+
+    /// Create a formatter that counts the nesting of [`empty_footer`](Self::empty_footer) in `depth`.
+    pub(crate) fn with_empty_footer_depth(
+        w: &'a mut dyn CellsWrite,
+        d: Option<&'b T>,
+        depth: &'a std::cell::Cell<usize>,
+    ) -> Self {
+        Self {
+            empty_footer_depth: Some(depth),
+            ..Self::new(w, d)
         }
     }
 
@@ -58,6 +78,252 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
         self.column_with(header, |cf| cf.content(f));
     }
 
+    /// Define column only if `cond` is true.
+    ///
+    /// - cond : If false, the column is not defined.
+    /// - header : Column header's cell.
+    /// - f : A function to obtain cell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     name: &'static str,
+    ///     path: &'static str,
+    /// }
+    /// fn schema(verbose: bool) -> impl CellsSchema<Source = RowData> {
+    ///     cells_schema(move |f: &mut CellsFormatter<RowData>| {
+    ///         f.column("name", |s| s.name);
+    ///         f.column_if(verbose, "path", |s| s.path);
+    ///     })
+    /// }
+    ///
+    /// let rows = [RowData { name: "a", path: "/tmp/a" }];
+    /// let g = to_grid_with_schema(&rows, schema(false));
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name |
+    /// ------|
+    ///  a    |
+    /// "#);
+    ///
+    /// let g = to_grid_with_schema(&rows, schema(true));
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name |  path  |
+    /// ------|--------|
+    ///  a    | /tmp/a |
+    /// "#);
+    /// ```
+    pub fn column_if<U: Cells>(
+        &mut self,
+        cond: bool,
+        header: impl RawCell,
+        f: impl FnOnce(&'b T) -> U,
+    ) {
+        if cond {
+            self.column(header, f);
+        }
+    }
+
+    /// Define column with a unit displayed below the header.
+    ///
+    /// - header : Column header's cell.
+    /// - unit : Cell displayed in an extra header row, such as a unit or a data type.
+    /// - f : A function to obtain cell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     name: &'static str,
+    ///     time: u32,
+    ///     size: u32,
+    /// }
+    /// impl Cells for RowData {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("name", |s| s.name);
+    ///         f.column_with_unit("time", "ms", |s| s.time);
+    ///         f.column_with_unit("size", "MiB", |s| s.size);
+    ///     }
+    /// }
+    ///
+    /// let rows = [
+    ///     RowData { name: "a", time: 300, size: 1 },
+    ///     RowData { name: "b", time: 2, size: 20 },
+    /// ];
+    /// let g = to_grid(rows);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | time | size |
+    ///       |  ms  | MiB  |
+    /// ------|------|------|
+    ///  a    |  300 |    1 |
+    ///  b    |    2 |   20 |
+    /// "#);
+    /// ```
+    pub fn column_with_unit<U: Cells>(
+        &mut self,
+        header: impl RawCell,
+        unit: impl RawCell,
+        f: impl FnOnce(&'b T) -> U,
+    ) {
+        self.w.column_start(&header);
+        self.content(f);
+        self.w.column_unit(&unit);
+        self.w.column_end(&header);
+    }
+
+    /// Define column of float numbers formatted with [`FloatFormat`].
+    ///
+    /// The numbers are aligned at the decimal point in the same way as [`cells_f`].
+    /// If `f` returns `None`, the cell is empty.
+    ///
+    /// - header : Column header's cell.
+    /// - f : A function to obtain the number.
+    /// - format : Format of the number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     name: &'static str,
+    ///     score: f64,
+    /// }
+    /// impl Cells for RowData {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("name", |s| s.name);
+    ///         f.column_float("score", |s| s.score, FloatFormat::fixed(2));
+    ///     }
+    /// }
+    ///
+    /// let rows = [
+    ///     RowData { name: "a", score: 1.0 },
+    ///     RowData { name: "b", score: 123.456 },
+    /// ];
+    /// let g = to_grid(rows);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | score  |
+    /// ------|--------|
+    ///  a    |   1.00 |
+    ///  b    | 123.46 |
+    /// "#);
+    /// ```
+    pub fn column_float<U: Into<Option<f64>>>(
+        &mut self,
+        header: impl RawCell,
+        f: impl FnOnce(&'b T) -> U,
+        format: FloatFormat,
+    ) {
+        self.column(header, |x| f(x).into().map(|value| format.cells(value)));
+    }
+
+    /// Define column with [`ColumnStyle`].
+    ///
+    /// - header : Column header's cell.
+    /// - style : Style of the column.
+    /// - f : A function to obtain cells.
+    ///
+    /// [`stretch`](ColumnStyle::stretch), [`min_width`](ColumnStyle::min_width), [`align_h`](ColumnStyle::align_h)
+    /// and [`header_align`](ColumnStyle::header_align) are applied to each column generated by `f`,
+    /// and [`column_end`](ColumnStyle::column_end) is applied to the last one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     a: u32,
+    ///     b: u32,
+    /// }
+    /// impl Cells for RowData {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column_with_style("a", ColumnStyle::new().min_width(5), |s| s.a);
+    ///         f.column("b", |s| s.b);
+    ///     }
+    /// }
+    ///
+    /// let rows = [RowData { a: 1, b: 2 }];
+    /// let g = to_grid(rows);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///    a   | b |
+    /// -------|---|
+    ///      1 | 2 |
+    /// "#);
+    /// ```
+    pub fn column_with_style<U: Cells>(
+        &mut self,
+        header: impl RawCell,
+        style: ColumnStyle,
+        f: impl FnOnce(&'b T) -> U,
+    ) {
+        self.w.column_start(&header);
+        self.w.column_style(&style);
+        self.content(f);
+        self.w.column_end(&header);
+    }
+
+    /// Define column with a style calculated from the value.
+    ///
+    /// - header : Column header's cell.
+    /// - f : A function to obtain cells.
+    /// - style : A function to obtain the style applied to the cells.
+    ///
+    /// Each element of the style that is not empty overrides the style of the cells,
+    /// except for the horizontal alignment specified by the cells.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     name: &'static str,
+    ///     status: &'static str,
+    /// }
+    /// impl Cells for RowData {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("name", |s| s.name);
+    ///         f.column_styled("status", |s| s.status, |&v| {
+    ///             if v == "FAIL" {
+    ///                 CellStyle::new().color(Color::Red)
+    ///             } else {
+    ///                 CellStyle::new()
+    ///             }
+    ///         });
+    ///     }
+    /// }
+    ///
+    /// let rows = [
+    ///     RowData { name: "a", status: "OK" },
+    ///     RowData { name: "b", status: "FAIL" },
+    /// ];
+    /// let g = to_grid(rows);
+    /// assert_eq!(format!("\n{g}"), "
+    ///  name | status |
+    /// ------|--------|
+    ///  a    | OK     |
+    ///  b    | \x1b[31mFAIL\x1b[0m   |
+    /// ");
+    /// ```
+    pub fn column_styled<U: Cells>(
+        &mut self,
+        header: impl RawCell,
+        f: impl FnOnce(&'b T) -> U,
+        style: impl FnOnce(&U) -> CellStyle,
+    ) {
+        self.column_with(header, |cf| {
+            cf.map_with(f, |cf| {
+                let style = cf.d.map(style).unwrap_or_default();
+                U::fmt(&mut CellsFormatter {
+                    w: &mut StyledCellsWrite { w: cf.w, style },
+                    d: cf.d,
+                    stretch: cf.stretch,
+                    empty_footer_depth: cf.empty_footer_depth,
+                })
+            })
+        });
+    }
+
     /// Define column with schema.
     ///
     /// - f : A function to obtain cells.
@@ -145,6 +411,24 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
         self.w.column_end(&header);
     }
 
+    /// Define column group only if `cond` is true.
+    ///
+    /// - cond : If false, the column group is not defined.
+    /// - header : Column group header's cell.
+    /// - f : A function to define columns in the group.
+    ///
+    /// See [`column_if`](Self::column_if) and [`column_with`](Self::column_with) for details.
+    pub fn column_with_if(
+        &mut self,
+        cond: bool,
+        header: impl RawCell,
+        f: impl FnOnce(&mut CellsFormatter<'_, 'b, T>),
+    ) {
+        if cond {
+            self.column_with(header, f);
+        }
+    }
+
     /// Define column content. Used to create shared header column.
     ///
     /// - f : A function to obtain cells.
@@ -204,6 +488,21 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
         );
     }
 
+    /// Returns the source value, or `None` when formatting the header.
+    pub(crate) fn source(&self) -> Option<&'b T> {
+        self.d
+    }
+
+    /// Creates a [`CellsFormatter`] whose source value is `d` if this formatter has a source value.
+    pub(crate) fn with_source<'c, U: ?Sized>(&'c mut self, d: &'c U) -> CellsFormatter<'c, 'c, U> {
+        CellsFormatter {
+            w: self.w,
+            d: self.d.map(|_| d),
+            stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
+        }
+    }
+
     /// Creates a [`CellsFormatter`] whose source value was converted.
     ///
     /// If you want to convert to an owned value instead of a reference, use [`map_with`](Self::map_with) instead.
@@ -212,6 +511,7 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
             w: self.w,
             d: self.d.map(m),
             stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
         }
     }
 
@@ -227,24 +527,30 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
             w: self.w,
             d: self.d.map(m).as_ref(),
             stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
         });
     }
 
     /// Creates a [`CellsFormatter`] that outputs the body cell only when the source value satisfies the condition.
-    pub fn filter(&mut self, f: impl FnOnce(&T) -> bool) -> CellsFormatter<T> {
+    pub fn filter(&mut self, f: impl FnOnce(&T) -> bool) -> CellsFormatter<'_, '_, T> {
         CellsFormatter {
             w: self.w,
             d: self.d.filter(|data| f(data)),
             stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
         }
     }
 
     /// Creates a [`CellsFormatter`] that both filters and maps.
-    pub fn filter_map<U>(&mut self, f: impl FnOnce(&T) -> Option<&U>) -> CellsFormatter<'_, 'b, U> {
+    pub fn filter_map<U: ?Sized>(
+        &mut self,
+        f: impl FnOnce(&T) -> Option<&U>,
+    ) -> CellsFormatter<'_, 'b, U> {
         CellsFormatter {
             w: self.w,
             d: self.d.and_then(f),
             stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
         }
     }
 
@@ -258,6 +564,7 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
             w: self.w,
             d: self.d.and_then(f).as_ref(),
             stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
         });
     }
 
@@ -274,12 +581,71 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
             w: self.w,
             d: d.as_ref().and_then(|x| x.as_ref().ok()),
             stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
         });
         if let Some(Err(e)) = &d {
             self.w.merged_body_end(e);
         }
     }
 
+    /// Define contents that can be displayed as a single body cell spanning all of them.
+    ///
+    /// - f : A function to obtain the merged cell. If it returns `None`, `contents` is displayed as usual.
+    /// - contents : A function to define the contents to be merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     name: &'static str,
+    ///     score: Option<(u32, u32)>,
+    /// }
+    /// impl Cells for RowData {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("name", |s| s.name);
+    ///         f.content_merged(
+    ///             |s| s.score.is_none().then(|| cell("absent").center()),
+    ///             |f| {
+    ///                 f.column("a", |s| s.score.map(|x| x.0));
+    ///                 f.column("b", |s| s.score.map(|x| x.1));
+    ///             },
+    ///         );
+    ///     }
+    /// }
+    ///
+    /// let rows = [
+    ///     RowData { name: "x", score: Some((10, 200)) },
+    ///     RowData { name: "y", score: None },
+    /// ];
+    /// let g = to_grid(rows);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | a  |  b  |
+    /// ------|----|-----|
+    ///  x    | 10 | 200 |
+    ///  y    |  absent  |
+    /// "#);
+    /// ```
+    pub fn content_merged<U: RawCell>(
+        &mut self,
+        f: impl FnOnce(&'b T) -> Option<U>,
+        contents: impl FnOnce(&mut CellsFormatter<T>),
+    ) {
+        let cell = self.d.and_then(f);
+        if let Some(cell) = &cell {
+            self.w.merged_body_start(cell);
+        }
+        contents(&mut CellsFormatter {
+            w: self.w,
+            d: if cell.is_some() { None } else { self.d },
+            stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
+        });
+        if let Some(cell) = &cell {
+            self.w.merged_body_end(cell);
+        }
+    }
+
     /// Return `CellsFormatter` that generates the columns to be stretched preferentially.
     ///
     /// See [`ColumnStyle::stretch`](crate::ColumnStyle::stretch) for details.
@@ -288,6 +654,7 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
             w: self.w,
             d: self.d,
             stretch: true,
+            empty_footer_depth: self.empty_footer_depth,
         }
     }
 
@@ -295,31 +662,169 @@ impl<'a, 'b, T: ?Sized> CellsFormatter<'a, 'b, T> {
     pub fn with(&mut self, f: impl Fn(&mut Self)) {
         f(self);
     }
+
+    /// Apply `f` to self, marking the cells written by `f` as the empty cells of an undefined footer.
+    pub(crate) fn empty_footer(&mut self, f: impl FnOnce(&mut Self)) {
+        if let Some(depth) = self.empty_footer_depth {
+            depth.set(depth.get() + 1);
+        }
+        f(self);
+        if let Some(depth) = self.empty_footer_depth {
+            depth.set(depth.get() - 1);
+        }
+    }
 }
 impl<T: ?Sized> CellsFormatter<'_, '_, &T> {
-    pub fn unref(&mut self) -> CellsFormatter<T> {
+    pub fn unref(&mut self) -> CellsFormatter<'_, '_, T> {
         CellsFormatter {
             w: self.w,
             d: self.d.map(|x| &**x),
             stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
         }
     }
 }
 impl<T: ?Sized> CellsFormatter<'_, '_, &mut T> {
-    pub fn unref(&mut self) -> CellsFormatter<T> {
+    pub fn unref(&mut self) -> CellsFormatter<'_, '_, T> {
         CellsFormatter {
             w: self.w,
             d: self.d.map(|x| &**x),
             stretch: self.stretch,
+            empty_footer_depth: self.empty_footer_depth,
+        }
+    }
+}
+
+struct StyledCellsWrite<'a> {
+    w: &'a mut dyn CellsWrite,
+    style: CellStyle,
+}
+impl CellsWrite for StyledCellsWrite<'_> {
+    fn content(&mut self, cell: Option<&dyn RawCell>, stretch: bool) {
+        let cell = cell.map(|cell| StyledCell(cell, self.style));
+        self.w
+            .content(cell.as_ref().map(|x| x as &dyn RawCell), stretch);
+    }
+    fn merged_body_start(&mut self, cell: &dyn RawCell) {
+        self.w.merged_body_start(&StyledCell(cell, self.style));
+    }
+    fn merged_body_end(&mut self, cell: &dyn RawCell) {
+        self.w.merged_body_end(&StyledCell(cell, self.style));
+    }
+    fn column_start(&mut self, header: &dyn RawCell) {
+        self.w.column_start(header);
+    }
+    fn column_end(&mut self, header: &dyn RawCell) {
+        self.w.column_end(header);
+    }
+    fn column_unit(&mut self, unit: &dyn RawCell) {
+        self.w.column_unit(unit);
+    }
+    fn column_style(&mut self, style: &ColumnStyle) {
+        self.w.column_style(style);
+    }
+}
+
+struct StyledCell<'a>(&'a dyn RawCell, CellStyle);
+
+impl RawCell for StyledCell<'_> {
+    fn fmt(&self, s: &mut String) {
+        self.0.fmt(s);
+    }
+    fn try_fmt(&self, s: &mut String) -> std::fmt::Result {
+        self.0.try_fmt(s)
+    }
+    fn style(&self) -> CellStyle {
+        let style = self.0.style();
+        CellStyle {
+            align_h: style.align_h,
+            ..self.1.or(style)
         }
     }
+    fn style_for_body(&self) -> CellStyle {
+        self.0.style_for_body()
+    }
+    fn footnote(&self) -> Option<&str> {
+        self.0.footnote()
+    }
+    fn header_key(&self) -> Option<&str> {
+        self.0.header_key()
+    }
 }
 
-pub(crate) trait CellsWrite {
+/// Visitor that receives the columns and cells defined by [`Cells`] or [`CellsSchema`].
+///
+/// Implement this trait to write the cells to your own sink,
+/// and pass it to [`CellsSchema::fmt`] through [`CellsFormatter::new`].
+///
+/// For each column, methods are called in the following order.
+///
+/// - [`column_start`](Self::column_start) with the header of the column.
+/// - [`column_style`](Self::column_style) if the column has a style.
+/// - [`content`](Self::content) for cells, or nested `column_start` .. `column_end` for child columns.
+/// - [`column_unit`](Self::column_unit) if the column has a unit.
+/// - [`column_end`](Self::column_end) with the same header.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+///
+/// #[derive(Default)]
+/// struct Pairs {
+///     path: Vec<String>,
+///     values: Vec<(String, String)>,
+/// }
+/// impl CellsWrite for Pairs {
+///     fn content(&mut self, cell: Option<&dyn RawCell>, _stretch: bool) {
+///         let mut s = String::new();
+///         if let Some(cell) = cell {
+///             cell.fmt(&mut s);
+///         }
+///         self.values.push((self.path.join("."), s));
+///     }
+///     fn merged_body_start(&mut self, _cell: &dyn RawCell) {}
+///     fn merged_body_end(&mut self, _cell: &dyn RawCell) {}
+///     fn column_start(&mut self, header: &dyn RawCell) {
+///         let mut s = String::new();
+///         header.fmt(&mut s);
+///         self.path.push(s);
+///     }
+///     fn column_end(&mut self, _header: &dyn RawCell) {
+///         self.path.pop();
+///     }
+/// }
+///
+/// struct RowData {
+///     name: &'static str,
+///     size: [u32; 2],
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("size", |s| s.size);
+///     }
+/// }
+///
+/// let mut w = Pairs::default();
+/// let row = RowData { name: "a", size: [1, 2] };
+/// DefaultCellsSchema::default().fmt(&mut CellsFormatter::new(&mut w, Some(&row)));
+/// assert_eq!(
+///     w.values,
+///     [
+///         ("name".to_string(), "a".to_string()),
+///         ("size.0".to_string(), "1".to_string()),
+///         ("size.1".to_string(), "2".to_string()),
+///     ]
+/// );
+/// ```
+pub trait CellsWrite {
     /// Called once for each cell.
     /// In the case of merged cells, it is also called for each unmerged cells.
     ///
     /// `cell`: Cell's value. If `None`, it is merged cells or headers.
+    ///
+    /// `stretch`: If true, the column should be stretched to fill the remaining width.
     fn content(&mut self, cell: Option<&dyn RawCell>, stretch: bool);
 
     /// Called when merged body start.
@@ -337,4 +842,14 @@ pub(crate) trait CellsWrite {
 
     /// Called at the end of cells separated by ruled lines.
     fn column_end(&mut self, header: &dyn RawCell);
+
+    /// Called before `column_end` of the column that has a unit.
+    fn column_unit(&mut self, unit: &dyn RawCell) {
+        let _ = unit;
+    }
+
+    /// Called just after `column_start` of the column that has a style.
+    fn column_style(&mut self, style: &ColumnStyle) {
+        let _ = style;
+    }
 }
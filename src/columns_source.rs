@@ -0,0 +1,132 @@
+use std::fmt::Display;
+
+use crate::{Cells, CellsFormatter, CellsSchema, GridBuilder};
+
+/// Data source that provides values as parallel columns instead of rows.
+///
+/// The `i`-th row of the table consists of the `i`-th values of each column.
+/// All columns must have the same length.
+///
+/// `ColumnsSource` implements [`CellsSchema`] whose source is the row index,
+/// so it can also be combined with other schemas.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let names = ["apple", "orange"];
+/// let prices = [100, 250];
+/// let g = ColumnsSource::new()
+///     .column("name", &names)
+///     .column("price", &prices)
+///     .to_grid();
+/// assert_eq!(format!("\n{g}"), r#"
+///   name  | price |
+/// --------|-------|
+///  apple  |   100 |
+///  orange |   250 |
+/// "#);
+/// ```
+#[allow(clippy::type_complexity)]
+pub struct ColumnsSource<'a> {
+    columns: Vec<Box<dyn Fn(&mut CellsFormatter<usize>) + 'a>>,
+    len: Option<usize>,
+}
+
+impl Default for ColumnsSource<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ColumnsSource<'a> {
+    /// Create a new `ColumnsSource` without columns.
+    pub fn new() -> Self {
+        Self {
+            columns: Vec::new(),
+            len: None,
+        }
+    }
+
+    /// Add a column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `values` differs from the length of the columns already added.
+    pub fn column<T: Cells>(self, header: impl Display + 'a, values: &'a [T]) -> Self {
+        self.column_with(header, values, |x| x)
+    }
+
+    /// Add a column whose cells are obtained by applying `f` to each value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `values` differs from the length of the columns already added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let names = ["a", "b"];
+    /// let ratios = [0.5, 0.125];
+    /// let g = ColumnsSource::new()
+    ///     .column("name", &names)
+    ///     .column_with("ratio", &ratios, |x| cell!("{:.1}%", x * 100.0).right())
+    ///     .to_grid();
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | ratio |
+    /// ------|-------|
+    ///  a    | 50.0% |
+    ///  b    | 12.5% |
+    /// "#);
+    /// ```
+    pub fn column_with<T, U: Cells>(
+        mut self,
+        header: impl Display + 'a,
+        values: &'a [T],
+        f: impl Fn(&'a T) -> U + 'a,
+    ) -> Self {
+        if let Some(len) = self.len {
+            assert_eq!(
+                len,
+                values.len(),
+                "column `{header}` has {} values, but the other columns have {len} values",
+                values.len(),
+            );
+        }
+        self.len = Some(values.len());
+        self.columns.push(Box::new(move |cf| {
+            cf.column(crate::cell(&header), |&i| f(&values[i]))
+        }));
+        self
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.len.unwrap_or(0)
+    }
+
+    /// Returns `true` if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Create a [`GridBuilder`] that contains all rows.
+    pub fn to_grid_builder(&self) -> GridBuilder {
+        GridBuilder::from_iter_with_schema(0..self.len(), self)
+    }
+
+    /// Generate a table that contains all rows.
+    pub fn to_grid(&self) -> String {
+        self.to_grid_builder().to_string()
+    }
+}
+
+impl CellsSchema for ColumnsSource<'_> {
+    type Source = usize;
+    fn fmt(&self, f: &mut CellsFormatter<usize>) {
+        for column in &self.columns {
+            column(f);
+        }
+    }
+}
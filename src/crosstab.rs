@@ -0,0 +1,84 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{cell, GridBuilder};
+
+/// Generate a two-dimensional table that summarizes the rows for each pair of keys.
+///
+/// The rows of the table are the values of `row_key_fn` and the columns are the values of `col_key_fn`,
+/// both sorted in ascending order.
+/// Each cell shows `agg` applied to the rows with the corresponding keys,
+/// and the last row and column show `agg` applied to all rows of each column and row.
+/// Cells without rows are empty.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let jobs = [
+///     ("ok", "prod"),
+///     ("ok", "dev"),
+///     ("failed", "prod"),
+///     ("ok", "prod"),
+/// ];
+/// let g = to_crosstab(&jobs, |x| x.0, |x| x.1, |rows| rows.len());
+/// assert_eq!(format!("\n{g}"), r#"
+///         | dev | prod | total |
+/// --------|-----|------|-------|
+///  failed |     |    1 |     1 |
+///  ok     |   1 |    2 |     3 |
+/// --------|-----|------|-------|
+///  total  |   1 |    3 |     4 |
+/// "#);
+/// ```
+pub fn to_crosstab<T, R: Ord + Display, C: Ord + Display, V: Display>(
+    rows: impl IntoIterator<Item = T>,
+    row_key_fn: impl Fn(&T) -> R,
+    col_key_fn: impl Fn(&T) -> C,
+    agg: impl Fn(&[&T]) -> V,
+) -> String {
+    let source: Vec<T> = rows.into_iter().collect();
+    let keys: Vec<(R, C)> = source
+        .iter()
+        .map(|x| (row_key_fn(x), col_key_fn(x)))
+        .collect();
+    let mut cells: BTreeMap<(&R, &C), Vec<&T>> = BTreeMap::new();
+    let mut row_totals: BTreeMap<&R, Vec<&T>> = BTreeMap::new();
+    let mut col_totals: BTreeMap<&C, Vec<&T>> = BTreeMap::new();
+    for (x, (r, c)) in source.iter().zip(&keys) {
+        cells.entry((r, c)).or_default().push(x);
+        row_totals.entry(r).or_default().push(x);
+        col_totals.entry(c).or_default().push(x);
+    }
+    let all: Vec<&T> = source.iter().collect();
+    let agg_cell = |rows: Option<&Vec<&T>>| match rows {
+        Some(rows) if !rows.is_empty() => cell(agg(rows).to_string()).right(),
+        _ => cell(String::new()),
+    };
+
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("");
+        for c in col_totals.keys() {
+            b.push(cell(c));
+        }
+        b.push("total");
+    });
+    for (r, rows) in &row_totals {
+        g.push(|b| {
+            b.push(cell(r));
+            for c in col_totals.keys() {
+                b.push(agg_cell(cells.get(&(*r, *c))));
+            }
+            b.push(agg_cell(Some(rows)));
+        });
+    }
+    g.push_separator();
+    g.push(|b| {
+        b.push("total");
+        for rows in col_totals.values() {
+            b.push(agg_cell(Some(rows)));
+        }
+        b.push(agg_cell(Some(&all)));
+    });
+    g.to_string()
+}
@@ -0,0 +1,65 @@
+use std::borrow::Borrow;
+
+use crate::{CellsSchema, GridBuilder};
+
+/// Options used when creating a grid showing the differences between two sets of rows.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let old = [(1, "a"), (2, "b"), (3, "c")];
+/// let new = [(1, "a"), (2, "x"), (4, "d")];
+/// let g = DiffOptions::new().colors(false).to_grid_with_schema(
+///     old,
+///     new,
+///     DefaultCellsSchema::default(),
+///     |x: &(i32, &str)| x.0,
+/// );
+/// assert_eq!(format!("\n{g}"), "
+///   1a |
+/// * 2x |
+/// + 4d |
+/// - 3c |
+/// ");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DiffOptions {
+    pub(crate) colors: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiffOptions {
+    /// Create a new `DiffOptions` with default settings.
+    pub fn new() -> Self {
+        Self { colors: true }
+    }
+
+    /// Set whether to color the added, removed and changed rows.
+    ///
+    /// When `false`, the rows are distinguished only by the `+`, `-` and `*` markers.
+    ///
+    /// The default for this is `true`.
+    pub fn colors(mut self, value: bool) -> Self {
+        self.colors = value;
+        self
+    }
+
+    /// Create a grid showing the differences between `old` and `new`.
+    ///
+    /// See [`GridBuilder::from_diff_with_schema`] for details.
+    pub fn to_grid_with_schema<T, K: Ord>(
+        &self,
+        old: impl IntoIterator<Item = impl Borrow<T>>,
+        new: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+        key: impl Fn(&T) -> K,
+    ) -> GridBuilder {
+        GridBuilder::diff_with_options(old, new, schema, key, self)
+    }
+}
@@ -0,0 +1,60 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{aggregator::Sum, cell, GridBuilder};
+
+const BAR_WIDTH: usize = 20;
+
+/// Generate a table showing the number of values in each bucket.
+///
+/// Each row shows a bucket returned by `bucket_fn`, the number of values, the percentage of all values,
+/// and a bar proportional to the number of values.
+/// Buckets are sorted in ascending order, and the total number of values is shown in the last row.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let latencies = [3, 12, 15, 7, 18, 25, 11, 4];
+/// let g = to_frequency_grid(latencies, |x| x / 10 * 10);
+/// assert_eq!(format!("\n{g}"), r#"
+///  bucket | count | percent |                      |
+/// --------|-------|---------|----------------------|
+///  0      |     3 |   37.5% | ###############      |
+///  10     |     4 |   50.0% | #################### |
+///  20     |     1 |   12.5% | #####                |
+/// --------|-------|---------|----------------------|
+///         |     8 |         |                      |
+/// "#);
+/// ```
+pub fn to_frequency_grid<T, K: Ord + Display>(
+    values: impl IntoIterator<Item = T>,
+    bucket_fn: impl Fn(&T) -> K,
+) -> String {
+    let mut counts = BTreeMap::new();
+    for value in values {
+        *counts.entry(bucket_fn(&value)).or_insert(0usize) += 1;
+    }
+    let total: usize = counts.values().sum();
+    let max = counts.values().copied().max().unwrap_or(0);
+
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("bucket");
+        b.push("count");
+        b.push("percent");
+        b.push("");
+    });
+    g.aggregate(1, Sum::new());
+    for (bucket, count) in counts {
+        let percent = count as f64 * 100.0 / total as f64;
+        let bar = (count * BAR_WIDTH).div_ceil(max);
+        g.push(|b| {
+            b.push(cell(bucket));
+            b.push(count);
+            b.push(cell(format!("{percent:.1}%")).right());
+            b.push("#".repeat(bar));
+        });
+    }
+    g.push_totals();
+    g.to_string()
+}
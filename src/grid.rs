@@ -1,8 +1,13 @@
 use std::borrow::Borrow;
 use std::fmt::{Debug, Display, Formatter};
 
-use crate::cells_csv_writer::write_csv;
-use crate::{grid_builder::*, Cells, CellsSchema, CellsSchemaExt, DefaultCellsSchema};
+use std::io::Write;
+
+use crate::cells_csv_writer::CsvOptions;
+use crate::{
+    grid_builder::*, Aggregations, Cells, CellsSchema, CellsSchemaExt, DefaultCellsSchema,
+    GridRenderOptions,
+};
 /// Generate a table using the columns defined by [`Cells`](crate::Cells).
 ///
 /// # Examples
@@ -44,6 +49,207 @@ pub fn to_grid_with_schema<T>(
     GridBuilder::from_iter_with_schema(rows, schema).to_string()
 }
 
+/// Generate a table showing the differences between `old_rows` and `new_rows`.
+///
+/// See [`GridBuilder::from_diff_with_schema`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let old = [
+///     RowData { name: "a", value: 1 },
+///     RowData { name: "b", value: 2 },
+///     RowData { name: "c", value: 3 },
+/// ];
+/// let new = [
+///     RowData { name: "a", value: 1 },
+///     RowData { name: "b", value: 20 },
+///     RowData { name: "d", value: 4 },
+/// ];
+/// let g = to_diff_grid(&old, &new, DefaultCellsSchema::default(), |x: &RowData| x.name);
+/// assert_eq!(format!("\n{g}"), "
+///   name | value |
+///  ------|-------|
+///   a    |     1 |
+/// * b    |    \x1b[33m20\x1b[0m |
+/// +\x1b[32m d    |     4 |\x1b[0m
+/// -\x1b[31m c    |     3 |\x1b[0m
+/// ");
+/// ```
+pub fn to_diff_grid<T, K: Ord>(
+    old_rows: impl IntoIterator<Item = impl Borrow<T>>,
+    new_rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+    key_fn: impl Fn(&T) -> K,
+) -> String {
+    GridBuilder::from_diff_with_schema(old_rows, new_rows, schema, key_fn).to_string()
+}
+
+/// Generate a table using the columns defined by [`Cells`](crate::Cells) and the specified options.
+///
+/// See [`GridRenderOptions`] for details.
+pub fn to_grid_with_options(
+    rows: impl IntoIterator<Item = impl Cells>,
+    options: GridRenderOptions,
+) -> String {
+    let mut g = GridBuilder::from_iter_with_schema(rows, DefaultCellsSchema::default());
+    g.options = options;
+    g.to_string()
+}
+
+/// Generate a table using the columns defined by [`Cells`](crate::Cells), followed by a totals row.
+///
+/// The aggregators are specified by `f`. See [`Aggregations`] for details.
+pub fn to_grid_with_totals<T: Cells>(
+    rows: impl IntoIterator<Item = T>,
+    f: impl FnOnce(Aggregations) -> Aggregations,
+) -> String {
+    let schema = DefaultCellsSchema::<T>::default();
+    let mut g = GridBuilder::new();
+    g.extend_header_with_schema(&schema);
+    f(Aggregations::new()).register(&mut g, &schema_headers(&schema));
+    g.extend_body_with_schema(rows, &schema);
+    g.push_totals();
+    g.to_string()
+}
+
+/// Generate a table with rows and columns swapped using the columns defined by [`Cells`](crate::Cells).
+///
+/// Each column becomes a row with its header on the left, and each item becomes a column.
+/// See [`GridBuilder::transpose`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "a", value: 300 },
+///     RowData { name: "bb", value: 2 },
+/// ];
+/// let g = to_grid_transposed(rows);
+/// assert_eq!(format!("\n{g}"), r#"
+///  name  | a   | bb |
+///  value | 300 |  2 |
+/// "#);
+/// ```
+pub fn to_grid_transposed(rows: impl IntoIterator<Item = impl Cells>) -> String {
+    GridBuilder::from_iter_with_schema(rows, DefaultCellsSchema::default())
+        .transpose()
+        .to_string()
+}
+
+/// Generate a two-column table of the keys and values of a single value using the columns defined by [`Cells`](crate::Cells).
+///
+/// Each column becomes a row with its headers joined with `.` on the left and its value on the right.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct Size {
+///     w: u32,
+///     h: u32,
+/// }
+/// impl Cells for Size {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("w", |s| s.w);
+///         f.column("h", |s| s.h);
+///     }
+/// }
+/// struct Window {
+///     title: &'static str,
+///     size: Size,
+/// }
+/// impl Cells for Window {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("title", |s| s.title);
+///         f.column("size", |s| &s.size);
+///     }
+/// }
+///
+/// let value = Window { title: "main", size: Size { w: 640, h: 480 } };
+/// let g = to_record(&value);
+/// assert_eq!(format!("\n{g}"), r#"
+///  title  | main |
+///  size.w | 640  |
+///  size.h | 480  |
+/// "#);
+/// ```
+pub fn to_record<T: Cells>(value: &T) -> String {
+    to_record_with_schema(value, DefaultCellsSchema::default())
+}
+
+/// Generate a two-column table of the keys and values of a single value using the columns defined by [`CellsSchema`](crate::CellsSchema).
+///
+/// See [`to_record`] for details.
+pub fn to_record_with_schema<T>(value: &T, schema: impl CellsSchema<Source = T>) -> String {
+    let mut g = GridBuilder::new();
+    g.extend_body_with_schema([value], &schema);
+    let values = g.matrix_of(false, false).pop().unwrap_or_default();
+    let mut record = GridBuilder::new();
+    for (column, path) in schema_headers(&schema).paths().iter().enumerate() {
+        record.push(|b| {
+            b.push(path.join("."));
+            b.push(values.get(column).map_or("", |s| s.as_str()));
+        });
+    }
+    record.to_string()
+}
+
+/// Generate a table without header rows using the columns defined by [`Cells`](crate::Cells).
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "a", value: 300 },
+///     RowData { name: "bb", value: 2 },
+/// ];
+/// let g = to_grid_headerless(rows);
+/// assert_eq!(format!("\n{g}"), r#"
+///  a  | 300 |
+///  bb |   2 |
+/// "#);
+/// ```
+pub fn to_grid_headerless(rows: impl IntoIterator<Item = impl Cells>) -> String {
+    to_grid_with_options(rows, GridRenderOptions::new().hide_header(true))
+}
+
 /// Generate csv using the columns defined by [`Cells`](crate::Cells).
 pub fn to_csv(rows: impl IntoIterator<Item = impl Cells>) -> String {
     to_csv_with_schema(rows, DefaultCellsSchema::default())
@@ -54,13 +260,85 @@ pub fn to_csv_with_schema<T>(
     rows: impl IntoIterator<Item = impl Borrow<T>>,
     schema: impl CellsSchema<Source = T>,
 ) -> String {
-    let mut bytes = Vec::new();
-    {
-        let mut csv_writer = csv::Writer::from_writer(&mut bytes);
-        write_csv(&mut csv_writer, rows, schema, ".").unwrap();
-        csv_writer.flush().unwrap();
-    }
-    String::from_utf8(bytes).unwrap()
+    CsvOptions::new().to_csv_with_schema(rows, schema)
+}
+
+/// Generate csv using the columns defined by [`Cells`](crate::Cells) and the specified options.
+///
+/// See [`CsvOptions`] for details.
+pub fn to_csv_with_options(
+    rows: impl IntoIterator<Item = impl Cells>,
+    options: CsvOptions,
+) -> String {
+    options.to_csv(rows)
+}
+
+/// Generate tab-separated values using the columns defined by [`Cells`](crate::Cells).
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     a: u32,
+///     b: &'static str,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("a", |s| s.a);
+///         f.column("b", |s| s.b);
+///     }
+/// }
+///
+/// let rows = [RowData { a: 300, b: "x y" }, RowData { a: 2, b: "z" }];
+/// assert_eq!(to_tsv(rows), "a\tb\n300\tx y\n2\tz\n");
+/// ```
+pub fn to_tsv(rows: impl IntoIterator<Item = impl Cells>) -> String {
+    to_csv_with_options(rows, CsvOptions::tsv())
+}
+
+/// Write csv using the columns defined by [`Cells`](crate::Cells) to `writer`.
+///
+/// Each row is written as soon as it is formatted, so that the whole csv is not held in memory.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     a: u32,
+///     b: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("a", |s| s.a);
+///         f.column("b", |s| s.b);
+///     }
+/// }
+///
+/// let rows = [RowData { a: 300, b: 1 }, RowData { a: 2, b: 200 }];
+/// let mut out = Vec::new();
+/// write_csv(&mut out, rows)?;
+/// assert_eq!(String::from_utf8(out).unwrap(), "a,b\n300,1\n2,200\n");
+/// # Ok::<(), csv::Error>(())
+/// ```
+pub fn write_csv(
+    writer: impl Write,
+    rows: impl IntoIterator<Item = impl Cells>,
+) -> csv::Result<()> {
+    write_csv_with_schema(writer, rows, DefaultCellsSchema::default())
+}
+
+/// Write csv using the columns defined by [`CellsSchema`](crate::CellsSchema) to `writer`.
+///
+/// See [`write_csv`] for details.
+/// Use [`CsvOptions::write_csv_with_schema`] to specify the options.
+pub fn write_csv_with_schema<T>(
+    writer: impl Write,
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> csv::Result<()> {
+    CsvOptions::new().write_csv_with_schema(writer, rows, schema)
 }
 
 /// A builder used to create plain-text table.
@@ -114,13 +392,7 @@ impl<T, S: CellsSchema<Source = T>> Grid<T, S> {
     }
 
     pub fn to_csv(&self) -> String {
-        let mut bytes = Vec::new();
-        {
-            let mut csv_writer = csv::Writer::from_writer(&mut bytes);
-            write_csv(&mut csv_writer, &self.source, self.schema.as_ref(), ".").unwrap();
-            csv_writer.flush().unwrap();
-        }
-        String::from_utf8(bytes).unwrap()
+        to_csv_with_schema(&self.source, self.schema.as_ref())
     }
 
     fn build(&self) -> GridBuilder {
@@ -1,28 +1,45 @@
 use self::HorizontalAlignment::*;
+use crate::aggregator::{aggregator_fn, Aggregator, AggregatorFn};
+use crate::ansi::*;
 use crate::cell::*;
+use crate::numeric::NumberKind;
 use crate::Cells;
 use crate::CellsFormatter;
 use crate::CellsSchema;
 use crate::CellsWrite;
 use crate::DefaultCellsSchema;
+use crate::DiffOptions;
+use crate::GridRenderOptions;
+use crate::GridTheme;
+use crate::HeaderResolver;
+use crate::SharedLayout;
+use crate::{GridRenderer, RenderCell};
 use derive_ex::derive_ex;
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
 use std::cmp::*;
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::*;
-use std::ops::Deref;
-use unicode_width::UnicodeWidthStr;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, Range};
+use std::rc::Rc;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 struct GridLayout {
     depth: usize,
     depth_max: usize,
     styles: Vec<ColumnStyle>,
+    has_unit: bool,
+    columns: Vec<(usize, Option<ColumnStyle>)>,
+    fixed_column_ends: Vec<usize>,
+    path: Vec<String>,
+    paths: Vec<Vec<String>>,
 }
 impl GridLayout {
     pub fn from_schema<T: ?Sized>(schema: &dyn CellsSchema<Source = T>) -> Self {
         let mut this = GridLayout::new();
         schema.fmt(&mut CellsFormatter::new(&mut this, None));
-        this.styles.pop();
         this
     }
     fn new() -> Self {
@@ -30,11 +47,19 @@ impl GridLayout {
             depth: 0,
             depth_max: 0,
             styles: Vec::new(),
+            has_unit: false,
+            columns: Vec::new(),
+            fixed_column_ends: Vec::new(),
+            path: Vec::new(),
+            paths: Vec::new(),
         }
     }
     fn set_column_end_style(&mut self) {
+        let len = self.styles.len();
         if let Some(last) = self.styles.last_mut() {
-            last.column_end = true;
+            if !self.fixed_column_ends.contains(&(len - 1)) {
+                last.column_end = true;
+            }
         }
     }
 }
@@ -43,19 +68,56 @@ impl CellsWrite for GridLayout {
         self.styles.push(ColumnStyle {
             column_end: false,
             stretch,
+            ..ColumnStyle::default()
         });
+        self.paths.push(self.path.clone());
     }
     fn merged_body_start(&mut self, _cell: &dyn RawCell) {}
     fn merged_body_end(&mut self, _cell: &dyn RawCell) {}
-    fn column_start(&mut self, _header: &dyn RawCell) {
+    fn column_start(&mut self, header: &dyn RawCell) {
+        let mut s = String::new();
+        header.fmt(&mut s);
+        self.path.push(s);
         self.set_column_end_style();
         self.depth += 1;
         self.depth_max = max(self.depth_max, self.depth);
+        self.columns.push((self.styles.len(), None));
     }
 
     fn column_end(&mut self, _header: &dyn RawCell) {
+        self.path.pop();
         self.depth -= 1;
-        self.set_column_end_style()
+        self.set_column_end_style();
+        let (start, style) = self.columns.pop().unwrap();
+        if let Some(style) = style {
+            for s in &mut self.styles[start..] {
+                s.stretch |= style.stretch;
+                s.hide_if_empty |= style.hide_if_empty;
+                s.min_width = max(s.min_width, style.min_width);
+                s.align_h = style.align_h.or(s.align_h);
+                s.header_align = style.header_align.or(s.header_align);
+                if style.pad_char != ' ' {
+                    s.pad_char = style.pad_char;
+                }
+                s.max_width = min_some(s.max_width, style.max_width);
+                s.truncate = min_some(s.truncate, style.truncate);
+            }
+            if let Some(last) = self.styles.len().checked_sub(1).filter(|&i| i >= start) {
+                self.styles[last].column_end = style.column_end;
+                if style.rule != VerticalRule::Single {
+                    self.styles[last].rule = style.rule;
+                }
+                self.fixed_column_ends.push(last);
+            }
+        }
+    }
+    fn column_unit(&mut self, _unit: &dyn RawCell) {
+        self.has_unit = true;
+    }
+    fn column_style(&mut self, style: &ColumnStyle) {
+        if let Some(column) = self.columns.last_mut() {
+            column.1 = Some(style.clone());
+        }
     }
 }
 
@@ -98,10 +160,6 @@ impl CellsWrite for HeaderWriter<'_, '_> {
     fn column_end(&mut self, header: &dyn RawCell) {
         self.depth -= 1;
         if self.depth == self.target {
-            let style = CellStyle {
-                align_h: Some(HorizontalAlignment::Center),
-            };
-            let header = Cell::new(header).with_base_style(style);
             self.push_cell(header);
         }
     }
@@ -111,6 +169,55 @@ impl Drop for HeaderWriter<'_, '_> {
         self.push_cell("");
     }
 }
+struct UnitWriter<'a, 'b> {
+    b: &'a mut RowBuilder<'b>,
+    starts: Vec<usize>,
+    column: usize,
+    column_last: usize,
+}
+impl<'a, 'b> UnitWriter<'a, 'b> {
+    fn new(b: &'a mut RowBuilder<'b>) -> Self {
+        Self {
+            b,
+            starts: Vec::new(),
+            column: 0,
+            column_last: 0,
+        }
+    }
+    fn push_empty_cells(&mut self, end: usize) {
+        while self.column_last < end {
+            self.b.push("");
+            self.column_last += 1;
+        }
+    }
+}
+impl CellsWrite for UnitWriter<'_, '_> {
+    fn content(&mut self, _cell: Option<&dyn RawCell>, _stretch: bool) {
+        self.column += 1;
+    }
+    fn merged_body_start(&mut self, _cell: &dyn RawCell) {}
+    fn merged_body_end(&mut self, _cell: &dyn RawCell) {}
+    fn column_start(&mut self, _header: &dyn RawCell) {
+        self.starts.push(self.column);
+    }
+    fn column_end(&mut self, _header: &dyn RawCell) {
+        self.starts.pop();
+    }
+    fn column_unit(&mut self, unit: &dyn RawCell) {
+        let start = *self.starts.last().unwrap();
+        if self.column_last <= start {
+            self.push_empty_cells(start);
+            self.b.push_with_colspan(unit, self.column - start);
+            self.column_last = self.column;
+        }
+    }
+}
+impl Drop for UnitWriter<'_, '_> {
+    fn drop(&mut self) {
+        self.push_empty_cells(self.column);
+    }
+}
+
 struct BodyWriter<'a, 'b> {
     b: &'a mut RowBuilder<'b>,
     colspan: Option<usize>,
@@ -143,6 +250,41 @@ impl CellsWrite for BodyWriter<'_, '_> {
     fn column_end(&mut self, _header: &dyn RawCell) {}
 }
 
+/// Detects whether a schema defines any footer cells, without calling the functions that obtain the cells.
+struct FooterDetector<'a> {
+    empty_footer_depth: &'a std::cell::Cell<usize>,
+    has_footer: bool,
+}
+impl FooterDetector<'_> {
+    fn has_footer<T: ?Sized>(schema: &impl CellsSchema<Source = T>) -> bool {
+        let depth = std::cell::Cell::new(0);
+        let mut w = FooterDetector {
+            empty_footer_depth: &depth,
+            has_footer: false,
+        };
+        schema.fmt_footer(&mut CellsFormatter::with_empty_footer_depth(
+            &mut w, None, &depth,
+        ));
+        w.has_footer
+    }
+    fn found(&mut self) {
+        self.has_footer |= self.empty_footer_depth.get() == 0;
+    }
+}
+impl CellsWrite for FooterDetector<'_> {
+    fn content(&mut self, _cell: Option<&dyn RawCell>, _stretch: bool) {
+        self.found();
+    }
+    fn merged_body_start(&mut self, _cell: &dyn RawCell) {
+        self.found();
+    }
+    fn merged_body_end(&mut self, _cell: &dyn RawCell) {}
+    fn column_start(&mut self, _header: &dyn RawCell) {
+        self.found();
+    }
+    fn column_end(&mut self, _header: &dyn RawCell) {}
+}
+
 /// A builder used to create plain-text table.
 ///
 /// # Examples
@@ -179,17 +321,82 @@ pub struct GridBuilder {
     rows: Vec<RowEntry>,
     columns: usize,
     pub column_styles: Vec<ColumnStyle>,
+    pub options: GridRenderOptions,
+    aggregators: Vec<(usize, AggregatorFn, Box<dyn Aggregator>)>,
+    footnotes: Vec<String>,
+    legend: Vec<(String, String)>,
+    width_cache: Vec<Option<(usize, usize, usize)>>,
+    header_resolver: Option<Rc<dyn HeaderResolver>>,
+    rowspans: Vec<(usize, usize)>,
 }
 
+#[derive(Clone)]
 struct CellEntry {
     s_idx: usize,
     width: usize,
     colspan: usize,
     style: CellStyle,
+    default_align: bool,
+    merged: bool,
+    error: bool,
+    header_key: Option<HeaderKey>,
+}
+
+/// Key used to resolve the text of a header cell with [`HeaderResolver`] when the grid is displayed.
+#[derive(Clone)]
+struct HeaderKey {
+    /// The key specified by [`RawCell::header_key`], or `None` to use the text.
+    key: Option<String>,
+    /// The length of the text replaced by the resolved string, excluding the footnote mark.
+    len: usize,
+}
+impl CellEntry {
+    fn pad_left(&self) -> usize {
+        self.style.pad_left.unwrap_or(0)
+    }
+    fn pad_right(&self) -> usize {
+        self.style.pad_right.unwrap_or(0)
+    }
+    fn content_width(&self) -> usize {
+        self.content_width_of(self.width)
+    }
+    fn content_width_of(&self, width: usize) -> usize {
+        width + self.pad_left() + self.pad_right()
+    }
+    fn layout_width_of(&self, width: usize) -> usize {
+        max(
+            self.content_width_of(width),
+            self.style.min_width.unwrap_or(0),
+        )
+    }
+    fn column_align_h(&self, style: &ColumnStyle, kind: RowKind) -> Option<HorizontalAlignment> {
+        if !self.default_align || self.colspan != 1 {
+            return None;
+        }
+        match kind {
+            RowKind::Header => style.header_align,
+            RowKind::Body | RowKind::Footer => style.align_h,
+        }
+    }
 }
 struct RowEntry {
     cells_idx: usize,
-    has_separator: bool,
+    separator: Option<SeparatorKind>,
+    style: RowStyle,
+    kind: RowKind,
+}
+
+/// Kind of row.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RowKind {
+    /// Header row.
+    Header,
+    /// Body row.
+    Body,
+    /// Footer row.
+    Footer,
 }
 
 impl GridBuilder {
@@ -201,7 +408,97 @@ impl GridBuilder {
             rows: Vec::new(),
             columns: 0,
             column_styles: Vec::new(),
+            options: GridRenderOptions::new(),
+            aggregators: Vec::new(),
+            footnotes: Vec::new(),
+            legend: Vec::new(),
+            width_cache: Vec::new(),
+            header_resolver: None,
+            rowspans: Vec::new(),
+        }
+    }
+
+    /// Create a grid showing the differences between `old` and `new`.
+    ///
+    /// Rows are matched by the key returned by `key`.
+    /// The rows of `new` are displayed in order, followed by the rows only in `old`.
+    ///
+    /// - Rows only in `new` are marked with `+` and displayed in green.
+    /// - Rows only in `old` are marked with `-` and displayed in red.
+    /// - Changed rows are marked with `*`, and changed cells are displayed in yellow.
+    ///
+    /// Use [`DiffOptions`] to display the differences without colors.
+    pub fn from_diff_with_schema<T, K: Ord>(
+        old: impl IntoIterator<Item = impl Borrow<T>>,
+        new: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+        key: impl Fn(&T) -> K,
+    ) -> Self {
+        Self::diff_with_options(old, new, schema, key, &DiffOptions::new())
+    }
+    pub(crate) fn diff_with_options<T, K: Ord>(
+        old: impl IntoIterator<Item = impl Borrow<T>>,
+        new: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+        key: impl Fn(&T) -> K,
+        options: &DiffOptions,
+    ) -> Self {
+        let style = |marker: &str, color: Color| {
+            let style = RowStyle::new().marker(marker);
+            if options.colors {
+                style.color(color)
+            } else {
+                style
+            }
+        };
+        let mut this = Self::new();
+        this.extend_header_with_schema(&schema);
+        let mut old_grid = Self::new();
+        let mut old_rows = BTreeMap::<K, VecDeque<usize>>::new();
+        for (old_row, source) in old.into_iter().enumerate() {
+            old_grid.push_body_with_schema(source.borrow(), &schema);
+            old_rows
+                .entry(key(source.borrow()))
+                .or_default()
+                .push_back(old_row);
+        }
+        let mut matched = vec![false; old_grid.rows.len()];
+        for source in new {
+            let source = source.borrow();
+            this.push_body_with_schema(source, &schema);
+            let row = this.rows.len() - 1;
+            let old_row = old_rows.get_mut(&key(source)).and_then(|x| x.pop_front());
+            let Some(old_row) = old_row else {
+                this.rows[row].style = style("+", Color::Green);
+                continue;
+            };
+            matched[old_row] = true;
+            let cells_idx = this.cells_idx(row);
+            let old_cells_idx = old_grid.cells_idx(old_row);
+            let old_len = old_grid.cells_idx(old_row + 1) - old_cells_idx;
+            let mut changed = false;
+            for i in 0..this.cells_idx(row + 1) - cells_idx {
+                if i >= old_len
+                    || this.cell_str(cells_idx + i) != old_grid.cell_str(old_cells_idx + i)
+                {
+                    if options.colors {
+                        this.cells[cells_idx + i].style.color = Some(Color::Yellow);
+                    }
+                    changed = true;
+                }
+            }
+            if changed || this.cells_idx(row + 1) - cells_idx != old_len {
+                this.rows[row].style = RowStyle::new().marker("*");
+            }
+        }
+        for (old_row, matched) in matched.into_iter().enumerate() {
+            if !matched {
+                this.push_row_from(&old_grid, old_row);
+                let row = this.rows.len() - 1;
+                this.rows[row].style = style("-", Color::Red);
+            }
         }
+        this
     }
 
     pub fn from_iter_with_schema<T>(
@@ -210,379 +507,3018 @@ impl GridBuilder {
     ) -> Self {
         let mut this = Self::new();
         this.extend_header_with_schema(&schema);
-        this.extend_body_with_schema(source, &schema);
+        if !FooterDetector::has_footer(&schema) {
+            this.extend_body_with_schema(source, &schema);
+            return this;
+        }
+        let source: Vec<_> = source.into_iter().collect();
+        let source: Vec<&T> = source.iter().map(|x| x.borrow()).collect();
+        this.extend_body_with_schema(source.iter().copied(), &schema);
+        this.push_footer_with_schema(&source, &schema);
         this
     }
 
     /// Append a row to the bottom of the grid.
     pub fn push(&mut self, f: impl FnOnce(&mut RowBuilder)) {
+        f(&mut self.push_row())
+    }
+    /// Append a row with the specified style to the bottom of the grid.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(1);
+    /// });
+    /// g.push_styled(RowStyle::new().marker(">"), |b| {
+    ///     b.push("b");
+    ///     b.push(2);
+    /// });
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///   a | 1 |
+    /// > b | 2 |
+    /// "#);
+    /// ```
+    pub fn push_styled(&mut self, style: RowStyle, f: impl FnOnce(&mut RowBuilder)) {
+        self.push(f);
+        self.set_row_style(self.rows.len() - 1, style);
+    }
+
+    /// Set the style of the row at `row`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row >= self.row_count()`.
+    pub fn set_row_style(&mut self, row: usize, style: RowStyle) {
+        self.rows[row].style = style;
+    }
+
+    /// Returns the style of the row at `row`.
+    pub fn row_style(&self, row: usize) -> Option<&RowStyle> {
+        self.rows.get(row).map(|r| &r.style)
+    }
+
+    pub(crate) fn push_row(&mut self) -> RowBuilder<'_> {
+        self.push_row_with_kind(RowKind::Body)
+    }
+    fn push_row_with_kind(&mut self, kind: RowKind) -> RowBuilder<'_> {
         let cells_idx = self.cells.len();
-        f(&mut RowBuilder {
+        let mut rowspans = std::mem::take(&mut self.rowspans);
+        if kind != RowKind::Body {
+            rowspans.clear();
+        }
+        RowBuilder {
             grid: self,
             cells_idx,
-        })
+            kind,
+            rowspans: rowspans.into(),
+        }
     }
 
     /// Append a row separator to the bottom of the grid.
     pub fn push_separator(&mut self) {
-        if let Some(row) = self.rows.last_mut() {
-            row.has_separator = true;
-        }
+        self.push_separator_with(SeparatorKind::Single);
     }
 
-    pub fn extend_header<T: ?Sized + Cells>(&mut self) {
-        self.extend_header_with_schema::<T>(&DefaultCellsSchema::default());
+    /// Append a row separator of the specified kind to the bottom of the grid.
+    pub fn push_separator_with(&mut self, kind: SeparatorKind) {
+        if let Some(row) = self.rows.last_mut() {
+            row.separator = Some(kind);
+        }
     }
 
-    pub fn extend_header_with_schema<T: ?Sized>(&mut self, schema: impl CellsSchema<Source = T>) {
-        let layout = GridLayout::from_schema(&schema);
-        self.column_styles = layout.styles;
-        for target in 0..layout.depth_max {
-            self.push(|b| {
-                schema.fmt(&mut CellsFormatter::new(
-                    &mut HeaderWriter::new(b, target),
-                    None,
-                ))
-            });
-            self.push_separator();
+    /// Insert a row separator just before the row at `row`.
+    ///
+    /// If `row == 0`, this method will do nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row > self.row_count()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| b.push("a"));
+    /// g.push(|b| b.push("b"));
+    /// g.push(|b| b.push("c"));
+    /// g.push_separator_before(2);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  a |
+    ///  b |
+    /// ---|
+    ///  c |
+    /// "#);
+    /// ```
+    pub fn push_separator_before(&mut self, row: usize) {
+        assert!(row <= self.rows.len(), "row index out of range");
+        if row != 0 {
+            self.set_separator(row - 1, Some(SeparatorKind::Single));
         }
     }
 
-    pub fn push_body(&mut self, source: &impl Cells) {
-        self.push_body_with_schema(source, DefaultCellsSchema::default());
+    /// Set the separator displayed below the row at `row`.
+    ///
+    /// If `kind` is `None`, the separator is removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row >= self.row_count()`.
+    pub fn set_separator(&mut self, row: usize, kind: Option<SeparatorKind>) {
+        self.rows[row].separator = kind;
     }
-    pub fn push_body_with_schema<T: ?Sized>(
-        &mut self,
-        source: &T,
-        schema: impl CellsSchema<Source = T>,
-    ) {
-        self.push(|b| {
-            b.extend_with_schema(source, &schema);
-        });
+
+    /// Returns the separator displayed below the row at `row`.
+    pub fn separator(&self, row: usize) -> Option<SeparatorKind> {
+        self.rows.get(row).and_then(|r| r.separator)
     }
-    pub fn extend_body(&mut self, source: impl IntoIterator<Item = impl Cells>) {
-        self.extend_body_with_schema(source, DefaultCellsSchema::default());
+
+    /// Set the key displayed below the table.
+    ///
+    /// Each entry is a pair of a symbol and its meaning, displayed as a line with the symbols aligned.
+    /// Meanings that do not fit within the width of the table are wrapped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("test");
+    ///     b.push("status");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("parse");
+    ///     b.push("✔");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("render");
+    ///     b.push("~");
+    /// });
+    /// g.set_legend([
+    ///     ("✔", "passed"),
+    ///     ("~", "skipped on this platform"),
+    /// ]);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///   test  | status |
+    /// --------|--------|
+    ///  parse  | ✔      |
+    ///  render | ~      |
+    ///  ✔  passed
+    ///  ~  skipped on
+    ///     this platform
+    /// "#);
+    /// ```
+    pub fn set_legend(&mut self, entries: impl IntoIterator<Item = (impl Display, impl Display)>) {
+        self.legend = entries
+            .into_iter()
+            .map(|(symbol, meaning)| (symbol.to_string(), meaning.to_string()))
+            .collect();
     }
-    pub fn extend_body_with_schema<T>(
-        &mut self,
-        source: impl IntoIterator<Item = impl Borrow<T>>,
-        schema: impl CellsSchema<Source = T>,
-    ) {
-        for source in source {
-            self.push(|b| {
-                b.extend_with_schema(source.borrow(), &schema);
-            });
-        }
+
+    /// Returns the number of rows.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
     }
 
-    fn push_cell<S: RawCell>(&mut self, cell: S, colspan: usize) {
-        let s_idx = self.s.len();
-        cell.fmt(&mut self.s);
-        self.cells.push(CellEntry {
-            s_idx,
-            width: self.s[s_idx..].width(),
-            colspan,
-            style: cell.style().or(cell.style_for_body()),
-        });
+    /// Append a header row to the bottom of the grid, followed by a row separator.
+    ///
+    /// If horizontal alignment of a cell is not specified, it is set to the center.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push_with_colspan("value", 2);
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(1);
+    ///     b.push(200);
+    /// });
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name |  value  |
+    /// ------|---------|
+    ///  a    | 1 | 200 |
+    /// "#);
+    /// ```
+    pub fn push_header(&mut self, f: impl FnOnce(&mut RowBuilder)) {
+        f(&mut self.push_row_with_kind(RowKind::Header));
+        self.push_separator();
     }
-    fn get_width(&self, widths: &[usize], column: usize, colspan: usize) -> usize {
-        assert!(colspan >= 1);
-        let mut result = widths[column];
-        for i in 1..colspan {
-            if self.has_border(column + i) {
-                result += 3;
+
+    /// Append a footer row to the bottom of the grid.
+    ///
+    /// A separator is displayed above the first of consecutive footer rows.
+    /// Footer cells are aligned like body cells and are used to calculate the column widths.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("price");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("apple");
+    ///     b.push(100);
+    /// });
+    /// g.push(|b| {
+    ///     b.push("orange");
+    ///     b.push(250);
+    /// });
+    /// g.push_footer(|b| {
+    ///     b.push("sum");
+    ///     b.push(350);
+    /// });
+    /// g.push_footer(|b| {
+    ///     b.push("average");
+    ///     b.push(175);
+    /// });
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///   name   | price |
+    /// ---------|-------|
+    ///  apple   |   100 |
+    ///  orange  |   250 |
+    /// ---------|-------|
+    ///  sum     |   350 |
+    ///  average |   175 |
+    /// "#);
+    /// ```
+    pub fn push_footer(&mut self, f: impl FnOnce(&mut RowBuilder)) {
+        let prev = self.rows.len().checked_sub(1);
+        f(&mut self.push_row_with_kind(RowKind::Footer));
+        if let Some(row) = prev {
+            let e = &mut self.rows[row];
+            if e.kind != RowKind::Footer && e.separator.is_none() {
+                e.separator = Some(SeparatorKind::Single);
             }
-            result += widths[column + i];
-        }
-        result
-    }
-    fn has_border(&self, n: usize) -> bool {
-        if n == 0 {
-            false
-        } else if n >= self.columns {
-            true
-        } else {
-            self.column_style(n - 1).column_end
         }
     }
-    fn has_left_padding(&self, n: usize) -> bool {
-        if n == 0 {
-            true
-        } else {
-            self.has_border(n)
+
+    /// Replace the header rows with the specified rows.
+    ///
+    /// The new header rows are placed at the top of the grid.
+    /// To specify multi-column header cells, use [`push_header`](Self::push_header).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// for row in [["a", "b"], ["ccc", "d"]] {
+    ///     g.push(|b| {
+    ///         b.push(row[0]);
+    ///         b.push(row[1]);
+    ///     });
+    /// }
+    /// g.set_headers(&[&["x", "y"]]);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///   x  | y |
+    /// -----|---|
+    ///  a   | b |
+    ///  ccc | d |
+    /// "#);
+    /// ```
+    pub fn set_headers<T: RawCell>(&mut self, rows: &[&[T]]) {
+        let mut g = self.with_same_settings();
+        for row in rows {
+            g.push_header(|b| {
+                for cell in *row {
+                    b.push(cell);
+                }
+            });
         }
-    }
-    fn has_right_padding(&self, n: usize) -> bool {
-        if n == self.columns {
-            true
-        } else {
-            self.has_border(n + 1)
+        for row in 0..self.rows.len() {
+            if self.rows[row].kind != RowKind::Header {
+                g.push_row_from(self, row);
+            }
         }
+        g.aggregators = std::mem::take(&mut self.aggregators);
+        *self = g;
     }
 
-    fn column_style(&self, column: usize) -> &ColumnStyle {
-        self.column_styles
-            .get(column)
-            .unwrap_or(&ColumnStyle::DEFAULT)
-    }
-    fn stretch_count(&self, column: usize, colspan: usize) -> usize {
-        let mut count = 0;
-        for i in 0..colspan {
-            if self.column_style(column + i).stretch {
-                count += 1;
+    /// Retain only the body rows for which `f` returns true.
+    ///
+    /// Header and footer rows are always retained.
+    /// Vertically merged cells whose row above is removed are displayed again.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("value");
+    /// });
+    /// for (name, value) in [("a", 10), ("b", 2), ("c", 30)] {
+    ///     g.push(|b| {
+    ///         b.push(name);
+    ///         b.push(value);
+    ///     });
+    /// }
+    /// g.retain_rows(|r| r.cell(1).unwrap().parse::<i32>().unwrap() >= 10);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | value |
+    /// ------|-------|
+    ///  a    |    10 |
+    ///  c    |    30 |
+    /// "#);
+    /// ```
+    pub fn retain_rows(&mut self, mut f: impl FnMut(RowView) -> bool) {
+        let mut g = self.with_same_settings();
+        g.aggregators = self.fresh_aggregators();
+        let mut prev_retained = true;
+        for row in 0..self.rows.len() {
+            let retain = self.rows[row].kind != RowKind::Body || f(RowView { grid: self, row });
+            if retain {
+                let cells_idx = g.cells.len();
+                g.push_row_from(self, row);
+                if !prev_retained {
+                    for cell in &mut g.cells[cells_idx..] {
+                        cell.merged = false;
+                    }
+                }
             }
+            prev_retained = retain;
         }
-        count
+        *self = g;
     }
 
-    fn get_widths(&self) -> Vec<usize> {
-        #[derive(PartialEq, Eq, Hash)]
-        struct ColRange {
-            colspan: usize,
-            column: usize,
+    /// Returns a grid that contains only the specified columns.
+    ///
+    /// Columns can be specified by index or by the text of a header cell.
+    /// A header cell spanning multiple columns selects all of them.
+    /// Unknown headers and indexes out of range are ignored.
+    ///
+    /// The selected columns keep their original order.
+    /// Cells spanning multiple columns are narrowed to the selected columns,
+    /// and cells without selected columns are removed.
+    /// Registered [`Aggregator`]s are not copied.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("size");
+    ///     b.push("path");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(10);
+    ///     b.push("/tmp/a");
+    /// });
+    /// g.push(|b| b.push_with_colspan("total", 3));
+    ///
+    /// assert_eq!(format!("\n{}", g.select_columns(["path", "name"])), r#"
+    ///  name |  path  |
+    /// ------|--------|
+    ///  a    | /tmp/a |
+    ///  total         |
+    /// "#);
+    /// assert_eq!(format!("\n{}", g.select_columns([1])), r#"
+    ///  size  |
+    /// -------|
+    ///     10 |
+    ///  total |
+    /// "#);
+    /// ```
+    pub fn select_columns<'a>(
+        &self,
+        columns: impl IntoIterator<Item = impl Into<ColumnRef<'a>>>,
+    ) -> GridBuilder {
+        let mut selected = vec![false; self.columns];
+        for column in columns {
+            match column.into() {
+                ColumnRef::Index(i) => {
+                    if let Some(s) = selected.get_mut(i) {
+                        *s = true;
+                    }
+                }
+                ColumnRef::Header(header) => {
+                    for (columns, _) in self.header_cells().filter(|c| c.1 == header) {
+                        for s in &mut selected[columns] {
+                            *s = true;
+                        }
+                    }
+                }
+            }
         }
-
-        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-        struct Block {
-            stretch: usize,
-            colspan: usize,
-            column: usize,
-            width: usize,
+        let mut g = self.with_same_settings();
+        g.column_styles.clear();
+        let indexes: Vec<usize> = (0..self.columns).filter(|&i| selected[i]).collect();
+        for (n, &i) in indexes.iter().enumerate() {
+            let mut style = self.column_style(i).clone();
+            let next = indexes.get(n + 1).copied().unwrap_or(self.columns);
+            if let Some(end) = (i..next).find(|&c| self.column_style(c).column_end) {
+                style.column_end = true;
+                style.rule = self.column_style(end).rule;
+            }
+            g.column_styles.push(style);
         }
-
-        let mut widths = vec![0; self.columns];
-        let mut blocks = HashMap::new();
-        for row in self.rows() {
-            for c in row {
-                let e = if c.colspan == 1 {
-                    &mut widths[c.column]
-                } else {
-                    let key = ColRange {
-                        colspan: c.colspan,
-                        column: c.column,
-                    };
-                    blocks.entry(key).or_insert(0)
-                };
-                *e = max(*e, c.width);
+        for row in 0..self.rows.len() {
+            let cells_idx = g.cells.len();
+            for c in self.row(row).unwrap() {
+                let colspan = selected[c.column..c.column + c.colspan]
+                    .iter()
+                    .filter(|&&s| s)
+                    .count();
+                if colspan == 0 {
+                    continue;
+                }
+                let s_idx = g.s.len();
+                g.s.push_str(c.s);
+                g.cells.push(CellEntry {
+                    s_idx,
+                    colspan,
+                    ..c.cell.clone()
+                });
             }
+            let source = &self.rows[row];
+            let e = g.push_row_entry(cells_idx, source.kind);
+            e.separator = source.separator;
+            e.style = source.style.clone();
         }
-        let mut blocks: Vec<_> = blocks
-            .into_iter()
-            .map(|c| Block {
-                stretch: self.stretch_count(c.0.column, c.0.colspan),
-                colspan: c.0.colspan,
-                column: c.0.column,
-                width: c.1,
-            })
-            .collect();
-        blocks.sort();
+        g
+    }
 
-        let mut expand_cols = Vec::new();
-        for b in blocks {
-            let mut width_sum = self.get_width(&widths, b.column, b.colspan);
-            let start = if b.stretch == 0 {
-                b.column
-            } else {
-                (b.column..b.column + b.colspan)
-                    .find(|&column| self.column_style(column).stretch)
-                    .unwrap()
-            };
+    /// Collapse each run of consecutive identical body rows into a single row.
+    ///
+    /// Rows are identical if they have the same cell values, column spans and row style.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.extend_body(["start", "retry", "retry", "retry", "done", "retry"]);
+    /// g.dedup_rows();
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  start |
+    ///  retry |
+    ///  done  |
+    ///  retry |
+    /// "#);
+    /// ```
+    pub fn dedup_rows(&mut self) {
+        *self = self.deduped(false);
+    }
 
-            while width_sum < b.width {
-                expand_cols.clear();
-                expand_cols.push(start);
-                let mut min_width = widths[start];
-                let mut next_width = usize::MAX;
-                #[allow(clippy::needless_range_loop)]
-                for column in start + 1..b.column + b.colspan {
-                    if b.stretch == 0 || self.column_style(column).stretch {
-                        let width = widths[column];
-                        if width < min_width {
-                            expand_cols.clear();
-                            next_width = min_width;
-                            min_width = width;
-                        }
-                        if width == min_width {
-                            expand_cols.push(column);
-                        }
-                    }
+    /// Collapse each run of consecutive identical body rows into a single row,
+    /// and add a column showing the number of rows in the run, such as `× 3`.
+    ///
+    /// The count is empty for rows that are not repeated.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| b.push("event"));
+    /// g.extend_body(["start", "retry", "retry", "retry", "done"]);
+    /// g.dedup_rows_with_count();
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  event |     |
+    /// -------|-----|
+    ///  start |     |
+    ///  retry | × 3 |
+    ///  done  |     |
+    /// "#);
+    /// ```
+    pub fn dedup_rows_with_count(&mut self) {
+        *self = self.deduped(true);
+    }
+
+    fn deduped(&self, count: bool) -> GridBuilder {
+        let mut g = self.with_same_settings();
+        g.aggregators = self.fresh_aggregators();
+        let mut row = 0;
+        while row < self.rows.len() {
+            let mut end = row + 1;
+            if self.rows[row].kind == RowKind::Body {
+                while end < self.rows.len()
+                    && self.rows[end].kind == RowKind::Body
+                    && self.is_same_row(row, end)
+                {
+                    end += 1;
                 }
-                for i in 0..expand_cols.len() {
-                    let count = expand_cols.len() - i;
-                    let expand_width_all = b.width - width_sum;
-                    let expand_width = expand_width_all.div_ceil(count);
-                    let expand_width = min(expand_width, next_width - min_width);
-                    width_sum += expand_width;
-                    widths[expand_cols[i]] += expand_width;
+            }
+            let cells_idx = g.cells.len();
+            g.push_cells_from(self, row, 0..usize::MAX);
+            let source = &self.rows[row];
+            if count {
+                let columns: usize = self.row(row).unwrap().map(|c| c.colspan).sum();
+                if columns < self.columns {
+                    g.push_cell(Cell::empty(), self.columns - columns, source.kind);
                 }
+                let n = end - row;
+                let text = if n == 1 || source.kind != RowKind::Body {
+                    String::new()
+                } else {
+                    format!("× {n}")
+                };
+                g.push_cell(cell(text).right(), 1, source.kind);
             }
+            let e = g.push_row_entry(cells_idx, source.kind);
+            e.separator = self.rows[end - 1].separator;
+            e.style = source.style.clone();
+            row = end;
         }
-        widths
-    }
-    fn row(&self, row: usize) -> Option<Cursor> {
-        if row < self.rows.len() {
-            Some(Cursor {
-                grid: self,
-                column: 0,
-                idx: self.cells_idx(row),
-                end: self.cells_idx(row + 1),
-            })
-        } else {
-            None
-        }
+        g
     }
-    fn rows(&self) -> impl Iterator<Item = Cursor> {
-        (0..self.rows.len()).map(|row| self.row(row).unwrap())
+    fn is_same_row(&self, row0: usize, row1: usize) -> bool {
+        self.rows[row0].style == self.rows[row1].style
+            && self
+                .row(row0)
+                .unwrap()
+                .map(|c| (c.s, c.colspan))
+                .eq(self.row(row1).unwrap().map(|c| (c.s, c.colspan)))
     }
 
-    fn cells_idx(&self, row: usize) -> usize {
-        if let Some(row) = self.rows.get(row) {
-            row.cells_idx
-        } else {
-            self.cells.len()
+    /// Returns a grid containing only the specified range of rows and columns.
+    ///
+    /// The column widths are recalculated within the range.
+    /// Cells partially included in the column range are cropped to the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows.end > self.row_count()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("aaaaaa");
+    ///     b.push("b");
+    ///     b.push("c");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("d");
+    ///     b.push("e");
+    ///     b.push("f");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("g");
+    ///     b.push_with_colspan("hhhhh", 2);
+    /// });
+    /// assert_eq!(format!("\n{}", g.view(1..3, 1..2)), r#"
+    ///  e     |
+    ///  hhhhh |
+    /// "#);
+    /// ```
+    pub fn view(&self, rows: Range<usize>, columns: Range<usize>) -> GridBuilder {
+        assert!(rows.end <= self.rows.len(), "row index out of range");
+        let mut g = self.with_same_settings();
+        g.column_styles = self
+            .column_styles
+            .iter()
+            .skip(columns.start)
+            .take(columns.len())
+            .cloned()
+            .collect();
+        for row in rows {
+            g.push_row_range_from(self, row, columns.clone());
         }
-    }
-    fn s_idx(&self, cells_idx: usize) -> usize {
-        if let Some(cell) = self.cells.get(cells_idx) {
-            cell.s_idx
-        } else {
-            self.s.len()
+        let end = g.cells_idx(1);
+        for cell in &mut g.cells[..end] {
+            cell.merged = false;
         }
+        g
     }
-}
 
-impl Display for GridBuilder {
-    fn fmt(&self, f: &mut Formatter) -> Result {
-        let widths = self.get_widths();
-        for row in 0..self.rows.len() {
-            if self.has_border(0) {
-                write!(f, "|")?;
+    /// Returns a grid in which the body rows flow into multiple side-by-side groups of columns.
+    ///
+    /// As many groups as fit within `width` are placed, like the output of `ls`.
+    /// The body rows are arranged from top to bottom, then from left to right,
+    /// and the header rows are repeated for each group.
+    /// Groups are separated by [`VerticalRule::Double`], and footer rows are placed in the first group.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| b.push("name"));
+    /// g.extend_body(["apple", "banana", "cherry", "durian", "elderberry"]);
+    /// assert_eq!(format!("\n{}", g.flow(30)), r#"
+    ///   name  ||    name    |
+    /// --------||------------|
+    ///  apple  || durian     |
+    ///  banana || elderberry |
+    ///  cherry ||            |
+    /// "#);
+    /// ```
+    pub fn flow(&self, width: usize) -> GridBuilder {
+        let body: Vec<usize> = (0..self.rows.len())
+            .filter(|&row| self.rows[row].kind == RowKind::Body)
+            .collect();
+        let mut result = self.flowed(&body, 1);
+        for groups in 2..=body.len() {
+            let g = self.flowed(&body, groups);
+            if g.render_width() > width {
+                break;
             }
-            for c in self.row(row).unwrap() {
-                let width = self.get_width(&widths, c.column, c.colspan);
-                if self.has_left_padding(c.column) {
-                    write!(f, " ")?;
-                }
-                let p = width - c.width;
-                match c.style.align_h.unwrap_or(Left) {
-                    Left => write!(f, "{0}{1:<p$}", c.s, "", p = p),
-                    Right => write!(f, "{1:<p$}{0}", c.s, "", p = p),
-                    Center => {
-                        let lp = p / 2;
-                        let rp = p - lp;
-                        write!(f, "{1:<lp$}{0}{1:<rp$}", c.s, "", lp = lp, rp = rp)
-                    }
-                }?;
-                if self.has_right_padding(c.column + c.colspan - 1) {
-                    write!(f, " ")?;
+            result = g;
+        }
+        result
+    }
+    fn flowed(&self, body: &[usize], groups: usize) -> GridBuilder {
+        let columns = self.columns;
+        let mut g = self.with_same_settings();
+        g.column_styles.clear();
+        for i in 0..groups {
+            for column in 0..columns {
+                let mut style = self.column_style(column).clone();
+                if column + 1 == columns && i + 1 != groups {
+                    style.column_end = true;
+                    style.rule = VerticalRule::Double;
+                }
+                g.column_styles.push(style);
+            }
+        }
+        let height = body.len().div_ceil(groups).max(1);
+        let push_row = |g: &mut GridBuilder, rows: &[Option<usize>], kind: RowKind| {
+            let cells_idx = g.cells.len();
+            for (i, &row) in rows.iter().enumerate() {
+                if let Some(row) = row {
+                    g.push_cells_from(self, row, 0..columns);
                 }
-                if self.has_border(c.column + c.colspan) {
-                    write!(f, "|")?;
+                let filled: usize = g.cells[cells_idx..].iter().map(|c| c.colspan).sum();
+                for _ in filled..(i + 1) * columns {
+                    g.push_cell("", 1, kind);
                 }
             }
-            writeln!(f)?;
-            if self.rows[row].has_separator {
-                let mut cs = [self.row(row), self.row(row + 1)];
-                for (column, _) in widths.iter().enumerate() {
-                    if self.has_left_padding(column) {
-                        write!(f, "-")?;
-                    }
-                    write!(f, "{:-<f$}", "", f = widths[column])?;
-                    if self.has_right_padding(column) {
-                        write!(f, "-")?;
-                    }
-                    for c in cs.iter_mut().flatten() {
-                        while c.column <= column && c.next().is_some() {}
-                    }
-                    if self.has_border(column + 1) {
-                        if cs.iter().flatten().all(|x| x.column == column + 1) {
-                            write!(f, "|")?;
-                        } else {
-                            write!(f, "-")?;
+            g.push_row_entry(cells_idx, kind);
+        };
+        for row in 0..self.rows.len() {
+            match self.rows[row].kind {
+                RowKind::Header => {
+                    push_row(&mut g, &vec![Some(row); groups], RowKind::Header);
+                    g.rows.last_mut().unwrap().separator = self.rows[row].separator;
+                }
+                RowKind::Body => {}
+                RowKind::Footer => {
+                    let mut rows = vec![None; groups];
+                    rows[0] = Some(row);
+                    push_row(&mut g, &rows, RowKind::Footer);
+                    g.rows.last_mut().unwrap().separator = self.rows[row].separator;
+                }
+            }
+            if Some(&row) == body.last() {
+                for i in 0..height {
+                    let rows: Vec<_> = (0..groups)
+                        .map(|group| body.get(group * height + i).copied())
+                        .collect();
+                    let cells_idx = g.cells.len();
+                    push_row(&mut g, &rows, RowKind::Body);
+                    if i == 0 {
+                        for cell in &mut g.cells[cells_idx..] {
+                            cell.merged = false;
                         }
                     }
                 }
-                writeln!(f)?;
+                g.rows.last_mut().unwrap().separator = self.rows[row].separator;
             }
         }
-        Ok(())
-    }
-}
-impl Debug for GridBuilder {
-    fn fmt(&self, f: &mut Formatter) -> Result {
-        Display::fmt(self, f)
-    }
-}
-impl<T: Cells> FromIterator<T> for GridBuilder {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut b = Self::new();
-        b.extend_header::<T>();
-        b.extend_body(iter);
-        b
+        g
     }
-}
-
-/// A builder used to create row of [`GridBuilder`].
-///
-/// This structure is created by [`GridBuilder::push`].
-pub struct RowBuilder<'a> {
-    grid: &'a mut GridBuilder,
-    cells_idx: usize,
-}
 
-impl RowBuilder<'_> {
-    /// Append a cell to the right of row.
-    pub fn push(&mut self, cell: impl RawCell) {
-        self.grid.push_cell(cell, 1);
+    pub fn extend_header<T: ?Sized + Cells>(&mut self) -> HeaderLayout {
+        self.extend_header_with_schema::<T>(&DefaultCellsSchema::default())
     }
 
-    /// Append a multi-column cell to the right of row.
+    /// Append header rows for the columns defined by `schema`, and set [`column_styles`](Self::column_styles).
     ///
-    /// - `cell` : Contents of cell to be appended.
-    /// - `colspan` : Number of columns used by the cell to be appended.
+    /// Returns the layout of the columns, which can be used to find the index of a column by its headers.
     ///
-    /// if `colspan == 0`, this method will do nothing.
-    pub fn push_with_colspan(&mut self, cell: impl RawCell, colspan: usize) {
-        if colspan != 0 {
-            self.grid.push_cell(cell, colspan);
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct X {
+    ///     a: u32,
+    ///     b: [u32; 2],
+    /// }
+    /// impl Cells for X {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("a", |s| s.a);
+    ///         f.column("b", |s| s.b);
+    ///     }
+    /// }
+    /// let mut g = GridBuilder::new();
+    /// let layout = g.extend_header::<X>();
+    /// assert_eq!(layout.column_count(), 3);
+    /// assert_eq!(layout.depth(), 2);
+    /// assert_eq!(layout.paths()[2], ["b", "1"]);
+    ///
+    /// let column = layout.find(&["b", "1"]).unwrap();
+    /// g.column_styles[column].align_h = Some(HorizontalAlignment::Left);
+    /// g.extend_body([X { a: 1, b: [200, 3] }]);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  a |    b    |
+    /// ---|---------|
+    ///    |  0  | 1 |
+    /// ---|-----|---|
+    ///  1 | 200 | 3 |
+    /// "#);
+    /// ```
+    pub fn extend_header_with_schema<T: ?Sized>(
+        &mut self,
+        schema: impl CellsSchema<Source = T>,
+    ) -> HeaderLayout {
+        let layout = GridLayout::from_schema(&schema);
+        let header_layout = HeaderLayout::new(&layout);
+        self.column_styles = layout.styles;
+        for target in 0..layout.depth_max {
+            {
+                let mut b = self.push_row_with_kind(RowKind::Header);
+                schema.fmt(&mut CellsFormatter::new(
+                    &mut HeaderWriter::new(&mut b, target),
+                    None,
+                ));
+            }
+            self.push_separator();
+        }
+        if layout.has_unit {
+            if let Some(row) = self.rows.last_mut() {
+                row.separator = None;
+            }
+            {
+                let mut b = self.push_row_with_kind(RowKind::Header);
+                schema.fmt(&mut CellsFormatter::new(&mut UnitWriter::new(&mut b), None));
+            }
+            self.push_separator();
         }
+        header_layout
     }
 
-    pub fn extend<T: ?Sized + Cells>(&mut self, source: &T) {
-        T::fmt(&mut CellsFormatter::new(
-            &mut BodyWriter::new(self),
-            Some(source),
-        ))
+    pub fn push_body(&mut self, source: &impl Cells) {
+        self.push_body_with_schema(source, DefaultCellsSchema::default());
     }
-    pub fn extend_with_schema<T: ?Sized>(
+    pub fn push_body_with_schema<T: ?Sized>(
         &mut self,
         source: &T,
         schema: impl CellsSchema<Source = T>,
     ) {
-        schema.fmt(&mut CellsFormatter::new(
-            &mut BodyWriter::new(self),
-            Some(source),
-        ))
+        self.push(|b| {
+            b.extend_with_schema(source, &schema);
+        });
     }
-}
-impl Drop for RowBuilder<'_> {
-    fn drop(&mut self) {
-        let mut columns = 0;
-        for cell in &self.grid.cells[self.cells_idx..] {
-            columns += cell.colspan;
+    pub fn extend_body(&mut self, source: impl IntoIterator<Item = impl Cells>) {
+        self.extend_body_with_schema(source, DefaultCellsSchema::default());
+    }
+    pub fn extend_body_with_schema<T>(
+        &mut self,
+        source: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+    ) {
+        for source in source {
+            self.push(|b| {
+                b.extend_with_schema(source.borrow(), &schema);
+            });
         }
-        self.grid.columns = max(self.grid.columns, columns);
-        self.grid.rows.push(RowEntry {
-            cells_idx: self.cells_idx,
-            has_separator: false,
-        });
     }
-}
 
-struct Cursor<'a> {
-    grid: &'a GridBuilder,
-    column: usize,
-    idx: usize,
+    /// Append a body row for each item, where `Err` items are displayed as error rows.
+    ///
+    /// An error row consists of a single red cell spanning all columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     name: &'static str,
+    ///     value: i32,
+    /// }
+    /// impl Cells for RowData {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("name", |s| s.name);
+    ///         f.column("value", |s| s.value);
+    ///     }
+    /// }
+    /// let items = [("a", "10"), ("b", "x"), ("c", "-3")].map(|(name, value)| {
+    ///     value
+    ///         .parse()
+    ///         .map(|value| RowData { name, value })
+    ///         .map_err(|_| format!("{name}: bad value"))
+    /// });
+    /// let mut g = GridBuilder::new();
+    /// g.extend_header::<RowData>();
+    /// g.extend_body_results(items);
+    /// assert_eq!(format!("\n{g}"), "
+    ///  name | value |
+    /// ------|-------|
+    ///  a    |    10 |
+    /// \x1b[31m b: bad value |\x1b[0m
+    ///  c    |    -3 |
+    /// ");
+    /// ```
+    pub fn extend_body_results<T: Cells, E: Display>(
+        &mut self,
+        source: impl IntoIterator<Item = std::result::Result<T, E>>,
+    ) {
+        self.extend_body_results_with_schema(source, DefaultCellsSchema::default());
+    }
+
+    /// Append a body row for each item using the columns defined by `schema`, where `Err` items are displayed as error rows.
+    ///
+    /// See [`extend_body_results`](Self::extend_body_results) for details.
+    pub fn extend_body_results_with_schema<T, E: Display>(
+        &mut self,
+        source: impl IntoIterator<Item = std::result::Result<impl Borrow<T>, E>>,
+        schema: impl CellsSchema<Source = T>,
+    ) {
+        let columns = max(GridLayout::from_schema(&schema).styles.len(), 1);
+        for source in source {
+            match source {
+                Ok(source) => self.push(|b| {
+                    b.extend_with_schema(source.borrow(), &schema);
+                }),
+                Err(e) => self.push_styled(RowStyle::new().color(Color::Red), |b| {
+                    b.push_with_colspan(cell(e), columns);
+                }),
+            }
+        }
+    }
+
+    /// Append a footer row defined by [`CellsSchema::fmt_footer`] to the bottom of the grid.
+    ///
+    /// If all footer cells are empty, this method will do nothing.
+    pub fn push_footer_with_schema<T: ?Sized>(
+        &mut self,
+        source: &[&T],
+        schema: impl CellsSchema<Source = T>,
+    ) {
+        let cells_idx = self.cells.len();
+        let s_idx = self.s.len();
+        {
+            let mut b = self.push_row_with_kind(RowKind::Footer);
+            schema.fmt_footer(&mut CellsFormatter::new(
+                &mut BodyWriter::new(&mut b),
+                Some(source),
+            ));
+        }
+        if self.s.len() == s_idx {
+            self.rows.pop();
+            self.cells.truncate(cells_idx);
+        } else if let Some(row) = self.rows.len().checked_sub(2) {
+            self.rows[row].separator = Some(SeparatorKind::Single);
+        }
+    }
+
+    /// Set the horizontal alignment of the body and footer cells in the column at `column`.
+    ///
+    /// This is applied when the grid is displayed, so it also affects the rows already pushed.
+    /// Cells whose alignment is specified explicitly, cells spanning multiple columns,
+    /// and rows with [`RowStyle::align_h`] keep their own alignment.
+    ///
+    /// This is equivalent to setting [`ColumnStyle::align_h`] of [`column_styles`](Self::column_styles).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::from_markdown("
+    /// | name | size |
+    /// |------|------|
+    /// | a    | 10   |
+    /// | bb   | 2    |
+    /// ").unwrap();
+    /// g.set_column_align(1, HorizontalAlignment::Right);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | size |
+    /// ------|------|
+    ///  a    |   10 |
+    ///  bb   |    2 |
+    /// "#);
+    /// ```
+    pub fn set_column_align(&mut self, column: usize, align: HorizontalAlignment) {
+        if self.column_styles.len() <= column {
+            self.column_styles
+                .resize(column + 1, ColumnStyle::default());
+        }
+        self.column_styles[column].align_h = Some(align);
+    }
+
+    /// Set the [`HeaderResolver`] used to localize the cells of the header rows.
+    ///
+    /// The resolver is consulted for all header rows when the grid is displayed or exported,
+    /// regardless of whether they were pushed before or after this call.
+    /// See [`HeaderResolver`] for details.
+    pub fn set_header_resolver(&mut self, resolver: impl HeaderResolver + 'static) {
+        self.header_resolver = Some(Rc::new(resolver));
+    }
+    /// Returns a grid in which the header cells are resolved by the [`HeaderResolver`], or `None` if no resolver is set.
+    pub(crate) fn resolved(&self) -> Option<GridBuilder> {
+        let resolver = self.header_resolver.as_ref()?;
+        // `map_cells` visits the cells in the order they are stored.
+        let mut keys = self.cells.iter().map(|c| c.header_key.as_ref());
+        let mut g = self.map_cells(|is_header, _, s| {
+            let k = keys.next().unwrap()?;
+            let key = match &k.key {
+                Some(key) => key,
+                None => s.get(..k.len)?,
+            };
+            let text = resolver.resolve(key).filter(|_| is_header)?;
+            Some(text + s.get(k.len..)?)
+        });
+        g.header_resolver = None;
+        for c in &mut g.cells {
+            c.header_key = None;
+        }
+        Some(g)
+    }
+
+    /// Set the [`GridTheme`] used to draw the grid.
+    ///
+    /// This is equivalent to setting [`GridRenderOptions::theme`] to [`options`](Self::options).
+    pub fn set_theme(&mut self, theme: GridTheme) {
+        self.options.theme = theme;
+    }
+
+    /// Register an [`Aggregator`] for the specified column.
+    ///
+    /// The aggregator receives the strings of the cells in the column of the body rows already pushed,
+    /// and then of each body row as it is pushed, so that [`push_totals`](Self::push_totals) does not read the rows again.
+    /// Cells spanning multiple columns are not aggregated.
+    /// If an aggregator is already registered for the column, it is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// use text_grid::aggregator::{Mean, Sum};
+    /// let mut g = GridBuilder::new();
+    /// g.aggregate(1, Sum::new());
+    /// g.aggregate(2, Mean::new());
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(10);
+    ///     b.push(1);
+    /// });
+    /// g.push(|b| {
+    ///     b.push("b");
+    ///     b.push(20);
+    ///     b.push(2);
+    /// });
+    /// g.push_totals();
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  a | 10 |   1 |
+    ///  b | 20 |   2 |
+    /// ---|----|-----|
+    ///    | 30 | 1.5 |
+    /// "#);
+    /// ```
+    pub fn aggregate(&mut self, column: usize, aggregator: impl Aggregator + Clone + 'static) {
+        self.aggregate_with(column, aggregator_fn(aggregator));
+    }
+    pub(crate) fn aggregate_with(&mut self, column: usize, aggregator: AggregatorFn) {
+        let mut a = vec![(column, aggregator.clone(), aggregator())];
+        for row in 0..self.rows.len() {
+            self.accumulate_to(row, &mut a);
+        }
+        let a = a.pop().unwrap();
+        if let Some(e) = self.aggregators.iter_mut().find(|e| e.0 == column) {
+            *e = a;
+        } else {
+            self.aggregators.push(a);
+        }
+    }
+    fn fresh_aggregators(&self) -> Vec<(usize, AggregatorFn, Box<dyn Aggregator>)> {
+        self.aggregators
+            .iter()
+            .map(|(column, f, _)| (*column, f.clone(), f()))
+            .collect()
+    }
+    fn accumulate(&mut self, row: usize) {
+        if !self.aggregators.is_empty() {
+            let mut aggregators = std::mem::take(&mut self.aggregators);
+            self.accumulate_to(row, &mut aggregators);
+            self.aggregators = aggregators;
+        }
+    }
+    fn accumulate_to(
+        &self,
+        row: usize,
+        aggregators: &mut [(usize, AggregatorFn, Box<dyn Aggregator>)],
+    ) {
+        if self.rows[row].kind != RowKind::Body {
+            return;
+        }
+        for c in self.row(row).unwrap() {
+            if c.colspan == 1 {
+                for (_, _, a) in aggregators.iter_mut().filter(|a| a.0 == c.column) {
+                    a.push(c.s);
+                }
+            }
+        }
+    }
+
+    /// Append a footer row containing the results of the registered aggregators.
+    ///
+    /// The results are those of the body rows pushed before the call.
+    /// Aggregated values are right-aligned.
+    /// Columns without an aggregator are left empty.
+    pub fn push_totals(&mut self) {
+        let mut cells = vec![String::new(); self.columns];
+        for (column, _, a) in &self.aggregators {
+            if let Some(s) = cells.get_mut(*column) {
+                Aggregator::fmt(&**a, s);
+            }
+        }
+        {
+            let mut b = self.push_row_with_kind(RowKind::Footer);
+            for s in cells {
+                b.push(cell(s).right());
+            }
+        }
+        if let Some(row) = self.rows.len().checked_sub(2) {
+            self.rows[row].separator = Some(SeparatorKind::Single);
+        }
+    }
+
+    /// Merge vertically adjacent body cells with the same text in the specified columns.
+    ///
+    /// Merged cells are displayed as a single cell spanning multiple rows, and the text is displayed only in the top row.
+    /// A cell is merged only if the cells in the specified columns to its left are also merged,
+    /// so that nested groups are not merged across the boundaries of outer groups.
+    /// Empty cells and cells spanning multiple columns are not merged.
+    ///
+    /// Call this after all body rows have been pushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// for (a, b, c) in [("x", "p", 1), ("x", "p", 2), ("x", "q", 3), ("y", "q", 4)] {
+    ///     g.push(|r| {
+    ///         r.push(a);
+    ///         r.push(b);
+    ///         r.push(c);
+    ///     });
+    ///     g.push_separator();
+    /// }
+    /// g.merge_vertically(&[0, 1]);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  x | p | 1 |
+    ///    |   |---|
+    ///    |   | 2 |
+    ///    |---|---|
+    ///    | q | 3 |
+    /// ---|---|---|
+    ///  y | q | 4 |
+    /// ---|---|---|
+    /// "#);
+    /// ```
+    pub fn merge_vertically(&mut self, columns: &[usize]) {
+        let mut columns = columns.to_vec();
+        columns.sort_unstable();
+        columns.dedup();
+        let mut prev: Option<Vec<Option<usize>>> = None;
+        for row in 0..self.rows.len() {
+            if self.rows[row].kind != RowKind::Body {
+                prev = None;
+                continue;
+            }
+            let cells_idx = self.cells_idx(row);
+            let mut current = vec![None; columns.len()];
+            for (i, c) in self.row(row).unwrap().enumerate() {
+                if c.colspan == 1 && !c.s.is_empty() {
+                    if let Ok(k) = columns.binary_search(&c.column) {
+                        current[k] = Some(cells_idx + i);
+                    }
+                }
+            }
+            if let Some(prev) = &prev {
+                for k in 0..columns.len() {
+                    let (Some(p), Some(c)) = (prev[k], current[k]) else {
+                        break;
+                    };
+                    if self.cell_str(p) != self.cell_str(c) {
+                        break;
+                    }
+                    self.cells[c].merged = true;
+                }
+            }
+            prev = Some(current);
+        }
+    }
+    fn cell_str(&self, cells_idx: usize) -> &str {
+        &self.s[self.s_idx(cells_idx)..self.s_idx(cells_idx + 1)]
+    }
+
+    /// Color the numeric cells of the body rows in the specified column on a gradient
+    /// between the minimum and maximum values of the column.
+    ///
+    /// Cells that are not numbers or span multiple columns are left unchanged.
+    /// Call this after all body rows have been pushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.extend_body([1, 3, 5]);
+    /// g.heatmap(0, Heatmap::new((0, 0, 0), (200, 100, 0)));
+    /// assert_eq!(format!("\n{g}"), "
+    ///  \x1b[38;2;0;0;0m1\x1b[0m |
+    ///  \x1b[38;2;100;50;0m3\x1b[0m |
+    ///  \x1b[38;2;200;100;0m5\x1b[0m |
+    /// ");
+    /// ```
+    pub fn heatmap(&mut self, column: usize, heatmap: Heatmap) {
+        let mut values = Vec::new();
+        for row in 0..self.rows.len() {
+            if self.rows[row].kind != RowKind::Body {
+                continue;
+            }
+            let cells_idx = self.cells_idx(row);
+            for (i, c) in self.row(row).unwrap().enumerate() {
+                if c.column == column && c.colspan == 1 {
+                    if let Ok(value) = c.s.trim().parse::<f64>() {
+                        if value.is_finite() {
+                            values.push((cells_idx + i, value));
+                        }
+                    }
+                }
+            }
+        }
+        let min = values.iter().map(|x| x.1).fold(f64::INFINITY, f64::min);
+        let max = values.iter().map(|x| x.1).fold(f64::NEG_INFINITY, f64::max);
+        for (idx, value) in values {
+            let t = if max > min {
+                (value - min) / (max - min)
+            } else {
+                0.5
+            };
+            let color = Some(heatmap.color(t));
+            let style = &mut self.cells[idx].style;
+            if heatmap.is_background() {
+                style.background = color;
+            } else {
+                style.color = color;
+            }
+        }
+    }
+
+    /// Align the columns whose body cells are all numbers.
+    ///
+    /// Integer columns are right-aligned and decimal columns are aligned at the decimal point.
+    /// Empty cells and cells spanning multiple columns are ignored.
+    /// Numeric footer cells, such as totals, are aligned together with the body cells of the column.
+    /// Cells whose alignment is specified explicitly keep their own alignment.
+    ///
+    /// This is useful for grids built from untyped data such as [`from_markdown`](Self::from_markdown).
+    /// Call this after all body rows have been pushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::from_markdown("
+    /// | name | count | price |
+    /// |------|-------|-------|
+    /// | a    | 3     | 1.5   |
+    /// | bb   | 12    | 10.25 |
+    /// | ccc  |       | 0.125 |
+    /// ").unwrap();
+    /// g.auto_align_numeric();
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | count | price  |
+    /// ------|-------|--------|
+    ///  a    |     3 |  1.5   |
+    ///  bb   |    12 | 10.25  |
+    ///  ccc  |       |  0.125 |
+    /// "#);
+    /// ```
+    pub fn auto_align_numeric(&mut self) {
+        let mut columns: Vec<Vec<usize>> = vec![Vec::new(); self.columns];
+        let mut footers: Vec<Vec<usize>> = vec![Vec::new(); self.columns];
+        for row in 0..self.rows.len() {
+            let columns = match self.rows[row].kind {
+                RowKind::Header => continue,
+                RowKind::Body => &mut columns,
+                RowKind::Footer => &mut footers,
+            };
+            let cells_idx = self.cells_idx(row);
+            for (i, c) in self.row(row).unwrap().enumerate() {
+                if c.colspan == 1 && !c.s.trim().is_empty() {
+                    columns[c.column].push(cells_idx + i);
+                }
+            }
+        }
+        for (column, (mut cells, footers)) in columns.into_iter().zip(footers).enumerate() {
+            let kind = NumberKind::detect(cells.iter().map(|&idx| self.cell_str(idx)));
+            match kind {
+                None => continue,
+                Some(NumberKind::Integer) => {}
+                Some(NumberKind::Decimal) => {
+                    cells.extend(
+                        footers
+                            .into_iter()
+                            .filter(|&idx| NumberKind::detect([self.cell_str(idx)]).is_some()),
+                    );
+                    let fraction_width = |s: &str| {
+                        let s = s.trim_end();
+                        s.len() - s.find('.').unwrap_or(s.len())
+                    };
+                    let widths: Vec<usize> = cells
+                        .iter()
+                        .map(|&idx| fraction_width(self.cell_str(idx)))
+                        .collect();
+                    let max_width = widths.iter().copied().max().unwrap_or(0);
+                    for (&idx, width) in cells.iter().zip(widths) {
+                        self.cells[idx].style.pad_right = Some(max_width - width);
+                    }
+                }
+            }
+            self.set_column_align(column, HorizontalAlignment::Right);
+        }
+    }
+
+    fn push_cell<S: RawCell>(&mut self, cell: S, colspan: usize, kind: RowKind) {
+        let s_idx = self.s.len();
+        let error = cell.try_fmt(&mut self.s).is_err();
+        if error {
+            self.s.truncate(s_idx);
+            self.s.push_str(ERROR_TEXT);
+        }
+        if self.s[s_idx..].contains("\r\n") {
+            let text = self.s[s_idx..].replace("\r\n", "\n");
+            self.s.truncate(s_idx);
+            self.s.push_str(&text);
+        }
+        let header_key = (kind == RowKind::Header).then(|| HeaderKey {
+            key: cell.header_key().map(|key| key.to_string()),
+            len: self.s.len() - s_idx,
+        });
+        if let Some(footnote) = cell.footnote() {
+            let n = match self.footnotes.iter().position(|x| x == footnote) {
+                Some(i) => i + 1,
+                None => {
+                    self.footnotes.push(footnote.to_string());
+                    self.footnotes.len()
+                }
+            };
+            write!(self.s, "[{n}]").unwrap();
+        }
+        let base_style = match kind {
+            RowKind::Header => CellStyle::new().align_h(Center),
+            RowKind::Body | RowKind::Footer => cell.style_for_body(),
+        };
+        let style = cell.style();
+        let width = self.cached_text_width(s_idx);
+        self.cells.push(CellEntry {
+            s_idx,
+            width,
+            colspan,
+            default_align: style.align_h.is_none(),
+            style: style.or(base_style),
+            merged: false,
+            error,
+            header_key,
+        });
+    }
+    /// Returns the width of the text after `s_idx`, reusing the width of the same short text.
+    ///
+    /// The cache has a fixed number of slots, each of which holds the position, length and width of a text in `self.s`.
+    fn cached_text_width(&mut self, s_idx: usize) -> usize {
+        const MAX_CACHED_LEN: usize = 64;
+        const SLOTS: usize = 256;
+        let s = &self.s[s_idx..];
+        if s.len() > MAX_CACHED_LEN {
+            return block_width(s);
+        }
+        if self.width_cache.is_empty() {
+            self.width_cache = vec![None; SLOTS];
+        }
+        let mut hasher = DefaultHasher::new();
+        s.hash(&mut hasher);
+        let slot = &mut self.width_cache[hasher.finish() as usize % SLOTS];
+        if let Some((start, len, width)) = *slot {
+            if len == s.len() && self.s.get(start..start + len) == Some(s) {
+                return width;
+            }
+        }
+        let width = block_width(s);
+        *slot = Some((s_idx, s.len(), width));
+        width
+    }
+    fn push_row_entry(&mut self, cells_idx: usize, kind: RowKind) -> &mut RowEntry {
+        let mut columns = 0;
+        for cell in &self.cells[cells_idx..] {
+            columns += cell.colspan;
+        }
+        self.columns = max(self.columns, columns);
+        self.rows.push(RowEntry {
+            cells_idx,
+            separator: None,
+            style: RowStyle::default(),
+            kind,
+        });
+        self.accumulate(self.rows.len() - 1);
+        self.rows.last_mut().unwrap()
+    }
+    fn push_row_from(&mut self, source: &GridBuilder, row: usize) {
+        self.push_row_range_from(source, row, 0..usize::MAX);
+    }
+    fn push_row_range_from(&mut self, source: &GridBuilder, row: usize, columns: Range<usize>) {
+        let cells_idx = self.cells.len();
+        self.push_cells_from(source, row, columns);
+        let source = &source.rows[row];
+        let e = self.push_row_entry(cells_idx, source.kind);
+        e.separator = source.separator;
+        e.style = source.style.clone();
+    }
+    fn push_cells_from(&mut self, source: &GridBuilder, row: usize, columns: Range<usize>) {
+        for c in source.row(row).unwrap() {
+            let start = max(c.column, columns.start);
+            let end = min(c.column + c.colspan, columns.end);
+            if start >= end {
+                continue;
+            }
+            let s_idx = self.s.len();
+            self.s.push_str(c.s);
+            self.cells.push(CellEntry {
+                s_idx,
+                colspan: end - start,
+                ..c.cell.clone()
+            });
+        }
+    }
+    fn with_same_settings(&self) -> Self {
+        GridBuilder {
+            column_styles: self.column_styles.clone(),
+            options: self.options.clone(),
+            footnotes: self.footnotes.clone(),
+            legend: self.legend.clone(),
+            header_resolver: self.header_resolver.clone(),
+            ..Self::new()
+        }
+    }
+    /// Returns a grid in which the cells wider than `widths` are truncated with `…`.
+    pub(crate) fn truncated(&self, widths: &[usize]) -> GridBuilder {
+        let mut g = self.with_same_settings();
+        for row in 0..self.rows.len() {
+            let cells_idx = g.cells.len();
+            for c in self.row(row).unwrap() {
+                let s_idx = g.s.len();
+                let max_width = (c.column + c.colspan <= widths.len()).then(|| {
+                    self.get_width(widths, c.column, c.colspan)
+                        .saturating_sub(c.pad_left() + c.pad_right())
+                });
+                let width = match max_width {
+                    Some(max_width) if c.width > max_width => {
+                        write_truncated_lines(&mut g.s, c.s, max_width)
+                    }
+                    _ => {
+                        g.s.push_str(c.s);
+                        c.width
+                    }
+                };
+                g.cells.push(CellEntry {
+                    s_idx,
+                    width,
+                    ..c.cell.clone()
+                });
+            }
+            let source = &self.rows[row];
+            let e = g.push_row_entry(cells_idx, source.kind);
+            e.separator = source.separator;
+            e.style = source.style.clone();
+        }
+        g
+    }
+    /// Returns the columns spanned by each cell of the header rows and its text.
+    pub(crate) fn header_cells(&self) -> impl Iterator<Item = (Range<usize>, &str)> {
+        (0..self.rows.len())
+            .filter(|&row| self.rows[row].kind == RowKind::Header)
+            .flat_map(|row| self.row(row).unwrap())
+            .map(|c| (c.column..c.column + c.colspan, c.s))
+    }
+    /// Returns the kind of each row, with the columns spanned by each cell of the row and its text.
+    pub(crate) fn row_cells(&self) -> impl Iterator<Item = (RowKind, Vec<(Range<usize>, &str)>)> {
+        (0..self.rows.len()).map(|row| {
+            let cells = self.row(row).unwrap();
+            let cells = cells.map(|c| (c.column..c.column + c.colspan, c.s));
+            (self.rows[row].kind, cells.collect())
+        })
+    }
+    /// Returns a grid in which the text of each cell is replaced with the result of `f`.
+    ///
+    /// `f` receives whether the cell is in a header row, the columns spanned by the cell and the text.
+    /// If `f` returns `None`, the text is kept.
+    pub(crate) fn map_cells(
+        &self,
+        mut f: impl FnMut(bool, Range<usize>, &str) -> Option<String>,
+    ) -> GridBuilder {
+        let mut g = self.with_same_settings();
+        for row in 0..self.rows.len() {
+            let source = &self.rows[row];
+            let cells_idx = g.cells.len();
+            for c in self.row(row).unwrap() {
+                let s_idx = g.s.len();
+                let columns = c.column..c.column + c.colspan;
+                match f(source.kind == RowKind::Header, columns, c.s) {
+                    Some(s) => g.s.push_str(&s),
+                    None => g.s.push_str(c.s),
+                }
+                let width = g.cached_text_width(s_idx);
+                g.cells.push(CellEntry {
+                    s_idx,
+                    width,
+                    ..c.cell.clone()
+                });
+            }
+            let e = g.push_row_entry(cells_idx, source.kind);
+            e.separator = source.separator;
+            e.style = source.style.clone();
+        }
+        g
+    }
+    fn get_width(&self, widths: &[usize], column: usize, colspan: usize) -> usize {
+        assert!(colspan >= 1);
+        let mut result = widths[column];
+        for i in 1..colspan {
+            if self.has_border(column + i) {
+                result += self.options.theme.padding * 2 + self.rule(column + i).width();
+            }
+            result += widths[column + i];
+        }
+        result
+    }
+    fn has_border(&self, n: usize) -> bool {
+        if n == 0 {
+            self.options.frame || self.options.theme.left_border
+        } else if n >= self.columns {
+            true
+        } else {
+            self.column_style(n - 1).column_end
+        }
+    }
+    fn rule(&self, n: usize) -> Cow<'_, str> {
+        let rule = if n == 0 {
+            VerticalRule::Single
+        } else {
+            self.column_style(n - 1).rule
+        };
+        match rule {
+            VerticalRule::Single => Cow::Borrowed(&self.options.theme.rule),
+            VerticalRule::Double => Cow::Borrowed(&self.options.theme.double_rule),
+            VerticalRule::Char(ch) => Cow::Owned(ch.to_string()),
+        }
+    }
+    fn has_left_padding(&self, n: usize) -> bool {
+        if n == 0 {
+            true
+        } else {
+            self.has_border(n)
+        }
+    }
+    fn has_right_padding(&self, n: usize) -> bool {
+        if n == self.columns {
+            true
+        } else {
+            self.has_border(n + 1)
+        }
+    }
+
+    fn column_style(&self, column: usize) -> &ColumnStyle {
+        self.column_styles
+            .get(column)
+            .unwrap_or(&ColumnStyle::DEFAULT)
+    }
+    fn stretch_count(&self, column: usize, colspan: usize) -> usize {
+        let mut count = 0;
+        for i in 0..colspan {
+            if self.column_style(column + i).stretch {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    pub(crate) fn get_widths(&self) -> Vec<usize> {
+        #[derive(PartialEq, Eq, Hash)]
+        struct ColRange {
+            colspan: usize,
+            column: usize,
+        }
+
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Block {
+            stretch: usize,
+            colspan: usize,
+            column: usize,
+            width: usize,
+        }
+
+        let mut widths: Vec<_> = (0..self.columns)
+            .map(|column| self.column_style(column).min_width)
+            .collect();
+        let mut blocks = HashMap::new();
+        let placeholder_width = self.options.empty_placeholder.as_deref().map(text_width);
+        for row in (0..self.rows.len()).filter(|&row| self.is_row_visible(row)) {
+            let placeholders = self.placeholders(row);
+            for (i, c) in self.row(row).unwrap().enumerate() {
+                let width = match placeholder_width {
+                    Some(width) if placeholders[i] => width,
+                    _ => c.width,
+                };
+                let e = if c.colspan == 1 {
+                    &mut widths[c.column]
+                } else {
+                    let key = ColRange {
+                        colspan: c.colspan,
+                        column: c.column,
+                    };
+                    blocks.entry(key).or_insert(0)
+                };
+                *e = max(*e, c.layout_width_of(width));
+            }
+        }
+        let mut blocks: Vec<_> = blocks
+            .into_iter()
+            .map(|c| Block {
+                stretch: self.stretch_count(c.0.column, c.0.colspan),
+                colspan: c.0.colspan,
+                column: c.0.column,
+                width: c.1,
+            })
+            .collect();
+        blocks.sort();
+
+        let mut expand_cols = Vec::new();
+        for b in blocks {
+            let mut width_sum = self.get_width(&widths, b.column, b.colspan);
+            let start = if b.stretch == 0 {
+                b.column
+            } else {
+                (b.column..b.column + b.colspan)
+                    .find(|&column| self.column_style(column).stretch)
+                    .unwrap()
+            };
+
+            while width_sum < b.width {
+                expand_cols.clear();
+                expand_cols.push(start);
+                let mut min_width = widths[start];
+                let mut next_width = usize::MAX;
+                #[allow(clippy::needless_range_loop)]
+                for column in start + 1..b.column + b.colspan {
+                    if b.stretch == 0 || self.column_style(column).stretch {
+                        let width = widths[column];
+                        if width < min_width {
+                            expand_cols.clear();
+                            next_width = min_width;
+                            min_width = width;
+                        }
+                        if width == min_width {
+                            expand_cols.push(column);
+                        }
+                    }
+                }
+                for i in 0..expand_cols.len() {
+                    let count = expand_cols.len() - i;
+                    let expand_width_all = b.width - width_sum;
+                    let expand_width = expand_width_all.div_ceil(count);
+                    let expand_width = min(expand_width, next_width - min_width);
+                    width_sum += expand_width;
+                    widths[expand_cols[i]] += expand_width;
+                }
+            }
+        }
+        widths
+    }
+    /// Returns an iterator over the lines of the rendered table.
+    ///
+    /// Each line is rendered on demand and does not contain the trailing newline.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("name");
+    ///     b.push("value");
+    /// });
+    /// g.push_separator();
+    /// g.push(|b| {
+    ///     b.push("X");
+    ///     b.push(10);
+    /// });
+    /// for line in g.lines() {
+    ///     println!("> {line}");
+    /// }
+    /// assert_eq!(g.lines().collect::<Vec<_>>(), [
+    ///     " name | value |",
+    ///     "------|-------|",
+    ///     " X    |    10 |",
+    /// ]);
+    /// ```
+    pub fn lines(&self) -> Lines<'_> {
+        let transposed = self.display_grid().map(Box::new);
+        let grid = transposed.as_deref().unwrap_or(self);
+        Lines {
+            cx: grid.render_context(),
+            grid: self,
+            transposed,
+            item: 0,
+            lines: VecDeque::new(),
+        }
+    }
+
+    /// Render the grid with the specified [`GridRenderer`].
+    ///
+    /// See [`GridRenderer`] for details.
+    pub fn render(&self, renderer: &mut (impl GridRenderer + ?Sized)) -> Result {
+        let display = self.display_grid();
+        let g = display.as_ref().unwrap_or(self);
+        let cx = g.render_context();
+        renderer.begin(&cx.widths)?;
+        for item in &cx.items {
+            match *item {
+                RenderItem::Row(row) => {
+                    let r = &g.rows[row];
+                    let cells: Vec<_> = g
+                        .row(row)
+                        .unwrap()
+                        .map(|c| RenderCell {
+                            text: c.s,
+                            column: c.column,
+                            colspan: c.colspan,
+                            width: g.get_width(&cx.widths, c.column, c.colspan),
+                            align: g.cell_align_h(&c, &r.style, r.kind),
+                            style: c.style,
+                            merged: c.merged,
+                        })
+                        .collect();
+                    renderer.row(r.kind, &cells)?;
+                }
+                RenderItem::Separator(_, _, kind) => renderer.separator(kind)?,
+                RenderItem::Frame(..) => renderer.separator(SeparatorKind::Single)?,
+                RenderItem::Elision(count) => renderer.elision(count)?,
+                RenderItem::Footnote(i) => renderer.footnote(i + 1, &g.footnotes[i])?,
+                RenderItem::Legend(i) => {
+                    let (symbol, meaning) = &g.legend[i];
+                    renderer.legend(symbol, meaning)?
+                }
+            }
+        }
+        renderer.end()
+    }
+
+    /// Render the grid into a string, or return the position of the first cell that failed to format.
+    ///
+    /// [`Display`] never fails and displays such cells as `#ERROR`.
+    /// Use this method to detect the errors reported by [`try_cell_by`] and [`try_cell!`](crate::try_cell!).
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| b.push("a"));
+    /// g.push(|b| b.push(try_cell_by(|_| Err(std::fmt::Error))));
+    /// let e = g.try_to_string().unwrap_err();
+    /// assert_eq!((e.row(), e.column()), (1, 0));
+    /// assert_eq!(e.to_string(), "failed to format the cell at row 1, column 0");
+    /// ```
+    pub fn try_to_string(&self) -> std::result::Result<String, CellError> {
+        match self.cell_error() {
+            Some(e) => Err(e),
+            None => Ok(self.to_string()),
+        }
+    }
+    fn cell_error(&self) -> Option<CellError> {
+        for row in 0..self.rows.len() {
+            for c in self.row(row).unwrap() {
+                if c.cell.error {
+                    return Some(CellError {
+                        row,
+                        column: c.column,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the formatted values of the body and footer rows.
+    ///
+    /// Each row has one value per column.
+    /// The value of a cell spanning multiple columns is repeated in each column it spans,
+    /// and the columns missing at the end of a row are empty strings.
+    /// Vertically merged cells keep their value in every row.
+    ///
+    /// Use [`to_header_matrix`](Self::to_header_matrix) to get the values of the header rows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push_with_colspan("value", 2);
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(1);
+    ///     b.push(2);
+    /// });
+    /// g.push(|b| {
+    ///     b.push_with_colspan("b", 2);
+    /// });
+    /// assert_eq!(g.to_header_matrix(), [["name", "value", "value"]]);
+    /// assert_eq!(g.to_cells_matrix(), [["a", "1", "2"], ["b", "b", ""]]);
+    /// ```
+    pub fn to_cells_matrix(&self) -> Vec<Vec<String>> {
+        self.matrix_of(false, true)
+    }
+
+    /// Returns the formatted values of the header rows.
+    ///
+    /// The values are laid out in the same way as [`to_cells_matrix`](Self::to_cells_matrix).
+    /// Header rows are returned even if [`GridRenderOptions::hide_header`] is set.
+    pub fn to_header_matrix(&self) -> Vec<Vec<String>> {
+        self.matrix_of(true, true)
+    }
+
+    /// Returns the values of the header rows or the other rows.
+    ///
+    /// If `repeat_spanned` is false, the columns spanned by a cell other than the first are empty strings.
+    pub(crate) fn matrix_of(&self, header: bool, repeat_spanned: bool) -> Vec<Vec<String>> {
+        if let Some(g) = self.resolved() {
+            return g.matrix_of(header, repeat_spanned);
+        }
+        let mut matrix = Vec::new();
+        for row in 0..self.rows.len() {
+            if header != (self.rows[row].kind == RowKind::Header) {
+                continue;
+            }
+            let mut values = Vec::new();
+            for c in self.row(row).unwrap() {
+                values.push(c.s.to_string());
+                for _ in 1..c.colspan {
+                    values.push(if repeat_spanned {
+                        c.s.to_string()
+                    } else {
+                        String::new()
+                    });
+                }
+            }
+            values.resize(self.columns, String::new());
+            matrix.push(values);
+        }
+        matrix
+    }
+
+    /// Returns a value that displays the grid using the column widths of [`SharedLayout`].
+    ///
+    /// Each column is at least as wide as the corresponding column of the layout.
+    pub fn display_with_layout<'a>(&'a self, layout: &'a SharedLayout) -> impl Display + 'a {
+        DisplayWithLayout { grid: self, layout }
+    }
+
+    /// Returns the grid to be displayed instead of `self`, if it is transposed or mirrored.
+    fn display_grid(&self) -> Option<GridBuilder> {
+        if let Some(g) = self.resolved() {
+            return Some(g.display_grid().unwrap_or(g));
+        }
+        if self.column_styles.iter().any(|s| s.hide_if_empty) {
+            let hidden = self.hidden_columns();
+            let mut g = self.select_columns((0..self.columns).filter(|&c| !hidden[c]));
+            for style in &mut g.column_styles {
+                style.hide_if_empty = false;
+            }
+            return Some(g.display_grid().unwrap_or(g));
+        }
+        if self
+            .column_styles
+            .iter()
+            .any(|s| s.max_width.is_some() || s.truncate.is_some())
+        {
+            let mut g = self.fitted();
+            for style in &mut g.column_styles {
+                style.max_width = None;
+                style.truncate = None;
+            }
+            return Some(g.display_grid().unwrap_or(g));
+        }
+        let transposed = self.auto_transposed();
+        let g = transposed.as_ref().unwrap_or(self);
+        #[cfg(feature = "bidi")]
+        if g.options.bidi_visual {
+            let mut g = g.map_cells(|_, _, s| Some(bidi_visual(s)));
+            g.options.bidi_visual = false;
+            return Some(g.display_grid().unwrap_or(g));
+        }
+        if g.options.rtl {
+            Some(g.mirrored())
+        } else {
+            transposed
+        }
+    }
+    /// Returns whether each column is hidden by [`ColumnStyle::hide_if_empty`].
+    fn hidden_columns(&self) -> Vec<bool> {
+        let mut hidden: Vec<bool> = (0..self.columns)
+            .map(|column| self.column_style(column).hide_if_empty)
+            .collect();
+        for row in 0..self.rows.len() {
+            if self.rows[row].kind == RowKind::Header {
+                continue;
+            }
+            for c in self.row(row).unwrap() {
+                if c.colspan == 1 && !c.s.is_empty() {
+                    hidden[c.column] = false;
+                }
+            }
+        }
+        hidden
+    }
+    /// Returns a grid in which the cells are truncated and wrapped according to the column styles.
+    fn fitted(&self) -> GridBuilder {
+        self.map_cells(|_, columns, s| {
+            if columns.len() != 1 {
+                return None;
+            }
+            let style = self.column_style(columns.start);
+            let width = [style.max_width, style.truncate]
+                .into_iter()
+                .flatten()
+                .min()?;
+            if block_width(s) <= width {
+                return None;
+            }
+            let lines: Vec<_> = s
+                .split('\n')
+                .map(|line| {
+                    let mut line = line.to_string();
+                    if let Some(width) = style.truncate {
+                        let mut s = String::new();
+                        write_truncated_lines(&mut s, &line, width);
+                        line = s;
+                    }
+                    match style.max_width {
+                        Some(width) if text_width(&line) > width => {
+                            wrap_words(&line, width).join("\n")
+                        }
+                        _ => line,
+                    }
+                })
+                .collect();
+            Some(lines.join("\n"))
+        })
+    }
+    fn mirrored(&self) -> GridBuilder {
+        let n = self.columns;
+        let mut g = self.with_same_settings();
+        g.options.rtl = false;
+        g.column_styles = (0..n)
+            .map(|j| {
+                let mut style = self.column_style(n - 1 - j).clone();
+                let end = if j + 1 < n {
+                    self.column_style(n - 2 - j)
+                } else {
+                    &ColumnStyle::DEFAULT
+                };
+                style.column_end = end.column_end;
+                style.rule = end.rule;
+                style.align_h = style.align_h.map(|a| a.mirrored());
+                style.header_align = style.header_align.map(|a| a.mirrored());
+                style
+            })
+            .collect();
+        for row in 0..self.rows.len() {
+            let source = &self.rows[row];
+            let cells_idx = g.cells.len();
+            let cells: Vec<_> = self.row(row).unwrap().collect();
+            let columns = cells.last().map(|c| c.column + c.colspan).unwrap_or(0);
+            if columns < n {
+                g.push_cell("", n - columns, source.kind);
+            }
+            for c in cells.iter().rev() {
+                let s_idx = g.s.len();
+                g.s.push_str(c.s);
+                let mut style = c.style;
+                style.align_h = style.align_h.map(|a| a.mirrored());
+                g.cells.push(CellEntry {
+                    s_idx,
+                    style,
+                    ..c.cell.clone()
+                });
+            }
+            let e = g.push_row_entry(cells_idx, source.kind);
+            e.separator = source.separator;
+            e.style = source.style.clone();
+            e.style.align_h = e.style.align_h.map(|a| a.mirrored());
+        }
+        g
+    }
+    fn auto_transposed(&self) -> Option<GridBuilder> {
+        let (max_width, max_rows) = self.options.auto_transpose?;
+        let body_rows = self.rows.iter().filter(|r| r.kind == RowKind::Body).count();
+        if body_rows <= max_rows && self.render_width() > max_width {
+            Some(self.transposed())
+        } else {
+            None
+        }
+    }
+    fn render_width(&self) -> usize {
+        let cx = self.render_context();
+        self.table_width(&cx.widths, cx.marker_width)
+    }
+    fn table_width(&self, widths: &[usize], marker_width: usize) -> usize {
+        if self.columns == 0 {
+            return 0;
+        }
+        let mut width =
+            marker_width + self.options.theme.padding * 2 + self.get_width(widths, 0, self.columns);
+        if self.has_border(0) {
+            width += self.rule(0).width();
+        }
+        width + self.rule(self.columns).width()
+    }
+    /// Returns a grid with rows and columns swapped.
+    ///
+    /// Each column of this grid becomes a row, with the header cells in the leftmost columns,
+    /// and each row of this grid becomes a column.
+    /// Cells spanning multiple columns are placed in their leftmost column, and hidden header rows are omitted.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("size");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(10);
+    /// });
+    /// g.push(|b| {
+    ///     b.push("b");
+    ///     b.push(200);
+    /// });
+    /// assert_eq!(format!("\n{}", g.transpose()), r#"
+    ///  name | a  | b   |
+    ///  size | 10 | 200 |
+    /// "#);
+    /// ```
+    pub fn transpose(&self) -> GridBuilder {
+        self.transposed()
+    }
+    fn transposed(&self) -> GridBuilder {
+        if let Some(g) = self.resolved() {
+            return g.transposed();
+        }
+        let mut g = GridBuilder::new();
+        g.options = GridRenderOptions {
+            auto_transpose: None,
+            ..self.options.clone()
+        };
+        g.footnotes = self.footnotes.clone();
+        g.legend = self.legend.clone();
+        let rows: Vec<_> = (0..self.rows.len())
+            .filter(|&row| self.is_row_visible(row))
+            .collect();
+        let mut matrix = vec![vec![None; rows.len()]; self.columns];
+        for (i, &row) in rows.iter().enumerate() {
+            let cells_idx = self.cells_idx(row);
+            for (j, c) in self.row(row).unwrap().enumerate() {
+                matrix[c.column][i] = Some(cells_idx + j);
+            }
+        }
+        for column in matrix {
+            let cells_idx = g.cells.len();
+            for (i, idx) in column.into_iter().enumerate() {
+                if let Some(idx) = idx {
+                    let c = &self.cells[idx];
+                    let s_idx = g.s.len();
+                    g.s.push_str(self.cell_str(idx));
+                    let mut style = c.style;
+                    if self.rows[rows[i]].kind == RowKind::Header && c.default_align {
+                        style.align_h = None;
+                    }
+                    g.cells.push(CellEntry {
+                        s_idx,
+                        colspan: 1,
+                        style,
+                        merged: false,
+                        ..c.clone()
+                    });
+                } else {
+                    g.push_cell("", 1, RowKind::Body);
+                }
+            }
+            g.push_row_entry(cells_idx, RowKind::Body);
+        }
+        g
+    }
+    fn render_context(&self) -> RenderContext {
+        self.render_context_with(None)
+    }
+    fn render_context_with(&self, layout: Option<&SharedLayout>) -> RenderContext {
+        let mut widths = self.get_widths();
+        if let Some(layout) = layout {
+            for (w, &width) in widths.iter_mut().zip(layout.widths()) {
+                *w = max(*w, width);
+            }
+        }
+        let marker_width = self
+            .rows
+            .iter()
+            .filter_map(|r| r.style.marker.as_deref())
+            .map(|m| m.width())
+            .max()
+            .unwrap_or(0);
+        let indent = match self.options.block_align {
+            Some((width, align)) => {
+                let p = width.saturating_sub(self.table_width(&widths, marker_width));
+                match align {
+                    Left => 0,
+                    Center => p / 2,
+                    Right => p,
+                }
+            }
+            None => 0,
+        };
+        RenderContext {
+            widths,
+            marker_width,
+            indent,
+            items: self.render_items(),
+        }
+    }
+    fn write_with(&self, f: &mut Formatter, cx: RenderContext) -> Result {
+        for item in &cx.items {
+            self.write_item(f, &cx, item)?;
+        }
+        Ok(())
+    }
+    fn render_items(&self) -> Vec<RenderItem> {
+        let mut items = Vec::new();
+        let mut rows: Vec<_> = (0..self.rows.len())
+            .filter(|&row| self.is_row_visible(row))
+            .collect();
+        let mut elision = None;
+        if let Some((head, tail)) = self.options.max_rows {
+            let body: Vec<_> = (0..rows.len())
+                .filter(|&i| self.rows[rows[i]].kind == RowKind::Body)
+                .collect();
+            if body.len() > head + tail {
+                let hidden = &body[head..body.len() - tail];
+                let separator = self.rows[rows[*hidden.last().unwrap()]].separator;
+                elision = Some((hidden[0], hidden.len(), separator));
+                for &i in hidden.iter().rev() {
+                    rows.remove(i);
+                }
+            }
+        }
+        for i in 0..=rows.len() {
+            if let Some((at, count, separator)) = elision {
+                if at == i {
+                    items.push(RenderItem::Elision(count));
+                    if let Some(kind) = separator {
+                        items.push(RenderItem::Separator(None, rows.get(i).copied(), kind));
+                    }
+                }
+            }
+            let Some(&row) = rows.get(i) else {
+                break;
+            };
+            items.push(RenderItem::Row(row));
+            if let Some(kind) = self.rows[row].separator {
+                let lower = rows.get(i + 1).copied();
+                let lower = lower.filter(|_| !matches!(elision, Some((at, ..)) if at == i + 1));
+                items.push(RenderItem::Separator(Some(row), lower, kind));
+            }
+        }
+        if self.options.repeat_header && !self.options.hide_header {
+            self.push_repeated_header(&mut items);
+        }
+        if self.options.frame {
+            if let Some(&RenderItem::Row(first)) = items.first() {
+                items.insert(0, RenderItem::Frame(None, Some(first), 0));
+            }
+            match items.last() {
+                Some(&RenderItem::Row(last)) => {
+                    items.push(RenderItem::Frame(Some(last), None, 2));
+                }
+                Some(RenderItem::Elision(_)) => {
+                    items.push(RenderItem::Frame(None, None, 2));
+                }
+                _ => {}
+            }
+        }
+        items.extend((0..self.footnotes.len()).map(RenderItem::Footnote));
+        items.extend((0..self.legend.len()).map(RenderItem::Legend));
+        items
+    }
+    fn push_repeated_header(&self, items: &mut Vec<RenderItem>) {
+        let headers: Vec<_> = (0..self.rows.len())
+            .filter(|&row| self.rows[row].kind == RowKind::Header)
+            .collect();
+        let Some(&first) = headers.first() else {
+            return;
+        };
+        if !self.rows.iter().any(|r| r.kind == RowKind::Body) {
+            return;
+        }
+        match items.last_mut() {
+            Some(RenderItem::Separator(_, lower, _)) => *lower = Some(first),
+            Some(RenderItem::Elision(_)) => {
+                items.push(RenderItem::Separator(
+                    None,
+                    Some(first),
+                    SeparatorKind::Single,
+                ));
+            }
+            Some(RenderItem::Row(row)) => {
+                let row = *row;
+                items.push(RenderItem::Separator(
+                    Some(row),
+                    Some(first),
+                    SeparatorKind::Single,
+                ));
+            }
+            Some(RenderItem::Frame(..) | RenderItem::Footnote(_) | RenderItem::Legend(_))
+            | None => {}
+        }
+        for (i, &row) in headers.iter().enumerate() {
+            items.push(RenderItem::Row(row));
+            if let (Some(kind), Some(&next)) = (self.rows[row].separator, headers.get(i + 1)) {
+                items.push(RenderItem::Separator(Some(row), Some(next), kind));
+            }
+        }
+    }
+    fn write_item(&self, f: &mut impl Write, cx: &RenderContext, item: &RenderItem) -> Result {
+        if cx.indent != 0 {
+            let f = &mut IndentWriter {
+                w: f,
+                indent: cx.indent,
+                line_start: true,
+            };
+            return self.write_item_lines(f, cx, item);
+        }
+        self.write_item_lines(f, cx, item)
+    }
+    fn write_item_lines(
+        &self,
+        f: &mut impl Write,
+        cx: &RenderContext,
+        item: &RenderItem,
+    ) -> Result {
+        match *item {
+            RenderItem::Row(row) => self.write_row(f, cx, row),
+            RenderItem::Separator(upper, lower, kind) => {
+                self.write_separator(f, cx, [upper, lower], kind, 1)
+            }
+            RenderItem::Frame(upper, lower, position) => {
+                self.write_separator(f, cx, [upper, lower], SeparatorKind::Single, position)
+            }
+            RenderItem::Elision(count) => self.write_elision(f, cx, count),
+            RenderItem::Footnote(i) => writeln!(f, "[{}] {}", i + 1, self.footnotes[i]),
+            RenderItem::Legend(i) => self.write_legend(f, cx, i),
+        }
+    }
+    fn write_legend(&self, f: &mut impl Write, cx: &RenderContext, i: usize) -> Result {
+        let symbol_width = self.legend.iter().map(|(s, _)| s.width()).max().unwrap();
+        let (symbol, meaning) = &self.legend[i];
+        let indent = symbol_width + 3;
+        let width = match self.table_width(&cx.widths, cx.marker_width) {
+            0 => usize::MAX,
+            w => max(w.saturating_sub(indent), 1),
+        };
+        for (n, line) in wrap_words(meaning, width).iter().enumerate() {
+            if n == 0 {
+                let p = symbol_width - symbol.width();
+                writeln!(f, " {symbol}{0:<1$}  {line}", "", p)?;
+            } else {
+                writeln!(f, "{0:<1$}{line}", "", indent)?;
+            }
+        }
+        Ok(())
+    }
+    fn write_elision(&self, f: &mut impl Write, cx: &RenderContext, count: usize) -> Result {
+        if cx.widths.is_empty() {
+            return Ok(());
+        }
+        let mut text = String::from("… ");
+        write_thousands(&mut text, count)?;
+        text.push_str(if count == 1 {
+            " more row"
+        } else {
+            " more rows"
+        });
+        let width = self.get_width(&cx.widths, 0, cx.widths.len());
+        write!(f, "{0:<1$}", "", cx.marker_width)?;
+        if self.has_border(0) {
+            write!(f, "{}", self.rule(0))?;
+        }
+        let padding = self.options.theme.padding;
+        let p = width.saturating_sub(text.width());
+        write!(f, "{0:<1$}{text}{0:<2$}", "", padding, p + padding)?;
+        writeln!(f, "{}", self.rule(cx.widths.len()))
+    }
+
+    /// Write a row over as many lines as its tallest cell, repeating the padding and borders on each line.
+    fn write_row(&self, f: &mut impl Write, cx: &RenderContext, row: usize) -> Result {
+        let line_count = self
+            .row(row)
+            .unwrap()
+            .filter(|c| !c.merged)
+            .map(|c| c.s.split('\n').count())
+            .max()
+            .unwrap_or(1);
+        let (top, bottom) = self.vertical_padding(row);
+        if line_count == 1 && top == 0 && bottom == 0 {
+            return self.write_row_line(f, cx, row, RowLine::Single);
+        }
+        for _ in 0..top {
+            self.write_row_line(f, cx, row, RowLine::Blank)?;
+        }
+        for line in 0..line_count {
+            self.write_row_line(f, cx, row, RowLine::Content(line))?;
+        }
+        for _ in 0..bottom {
+            self.write_row_line(f, cx, row, RowLine::Blank)?;
+        }
+        Ok(())
+    }
+    fn vertical_padding(&self, row: usize) -> (usize, usize) {
+        let row = &self.rows[row];
+        let (top, bottom) = match row.kind {
+            RowKind::Body => self.options.vertical_padding,
+            RowKind::Header | RowKind::Footer => (0, 0),
+        };
+        (
+            row.style.pad_top.unwrap_or(top),
+            row.style.pad_bottom.unwrap_or(bottom),
+        )
+    }
+    fn write_row_line(
+        &self,
+        f: &mut impl Write,
+        cx: &RenderContext,
+        row: usize,
+        line: RowLine,
+    ) -> Result {
+        let style = &self.rows[row].style;
+        let kind = self.rows[row].kind;
+        let is_first = matches!(line, RowLine::Single | RowLine::Content(0));
+        let marker = style.marker.as_deref().filter(|_| is_first).unwrap_or("");
+        write!(f, "{marker}{0:<1$}", "", cx.marker_width - marker.width())?;
+        style.sgr.write_start(f)?;
+        if self.has_border(0) {
+            write!(f, "{}", self.rule(0))?;
+        }
+        let placeholders = self.placeholders(row);
+        for (i, c) in self.row(row).unwrap().enumerate() {
+            let width = self.get_width(&cx.widths, c.column, c.colspan);
+            if self.has_left_padding(c.column) {
+                write_repeat(f, ' ', self.options.theme.padding)?;
+            }
+            if c.merged {
+                self.write_padding(f, ' ', width)?;
+            } else {
+                let placeholder = self
+                    .options
+                    .empty_placeholder
+                    .as_deref()
+                    .filter(|_| placeholders[i] && is_first);
+                let text = match line {
+                    RowLine::Single => placeholder,
+                    RowLine::Content(line) => {
+                        placeholder.or(Some(c.s.split('\n').nth(line).unwrap_or("")))
+                    }
+                    RowLine::Blank => Some(""),
+                };
+                self.write_cell_content(f, &c, text, width, style, kind)?;
+            }
+            if self.has_right_padding(c.column + c.colspan - 1) {
+                write_repeat(f, ' ', self.options.theme.padding)?;
+            }
+            if self.has_border(c.column + c.colspan) {
+                write!(f, "{}", self.rule(c.column + c.colspan))?;
+            }
+        }
+        style.sgr.write_end(f)?;
+        writeln!(f)
+    }
+    fn write_cell_content(
+        &self,
+        f: &mut impl Write,
+        c: &CellRef,
+        text: Option<&str>,
+        width: usize,
+        style: &RowStyle,
+        kind: RowKind,
+    ) -> Result {
+        let (s, content_width) = match text {
+            Some(text) => (text, c.content_width_of(text_width(text))),
+            None => (c.s, c.content_width()),
+        };
+        let p = width - content_width;
+        write!(f, "{0:<1$}", "", c.pad_left())?;
+        let (lp, rp) = match self.cell_align_h(c, style, kind) {
+            Left => (0, p),
+            Right => (p, 0),
+            Center => (p / 2, p - p / 2),
+        };
+        let pad_char = self.column_style(c.column).pad_char;
+        self.write_padding(f, pad_char, lp)?;
+        let sgr = c.style.sgr();
+        if sgr.is_empty() {
+            write!(f, "{s}")?;
+        } else {
+            sgr.write_start(f)?;
+            write!(f, "{s}")?;
+            sgr.write_end(f)?;
+            style.sgr.write_start(f)?;
+        }
+        self.write_padding(f, pad_char, rp)?;
+        write!(f, "{0:<1$}", "", c.pad_right())
+    }
+    fn write_padding(&self, f: &mut impl Write, pad_char: char, n: usize) -> Result {
+        let pad_char = if self.options.full_width_padding && pad_char == ' ' {
+            '\u{3000}'
+        } else {
+            pad_char
+        };
+        match pad_char.width() {
+            Some(width) if width > 0 => {
+                write_repeat(f, pad_char, n / width)?;
+                write_repeat(f, ' ', n % width)
+            }
+            _ => write_repeat(f, ' ', n),
+        }
+    }
+    fn cell_align_h(&self, c: &CellRef, style: &RowStyle, kind: RowKind) -> HorizontalAlignment {
+        style
+            .align_h
+            .or(c.column_align_h(self.column_style(c.column), kind))
+            .or(c.style.align_h)
+            .unwrap_or(Left)
+    }
+
+    /// Returns the ranges of columns separated by vertical rules.
+    pub(crate) fn column_groups(&self) -> Vec<Range<usize>> {
+        let mut groups = Vec::new();
+        let mut start = 0;
+        for n in 1..=self.columns {
+            if self.has_border(n) {
+                groups.push(start..n);
+                start = n;
+            }
+        }
+        groups
+    }
+
+    /// Returns the texts of the body and footer rows, concatenated for each group of columns.
+    ///
+    /// A cell spanning multiple groups is placed in the group of its first column.
+    pub(crate) fn group_texts(&self, groups: &[Range<usize>]) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        for (row, r) in self.rows.iter().enumerate() {
+            if r.kind == RowKind::Header {
+                continue;
+            }
+            let mut values = vec![String::new(); groups.len()];
+            for c in self.row(row).unwrap() {
+                if let Some(i) = groups.iter().position(|g| g.contains(&c.column)) {
+                    values[i].push_str(c.s);
+                }
+            }
+            rows.push(values);
+        }
+        rows
+    }
+
+    /// Returns the most common alignment of the first non-empty body cells in the columns.
+    pub(crate) fn body_align(&self, columns: Range<usize>) -> HorizontalAlignment {
+        let mut counts = [(Left, 0), (Right, 0), (Center, 0)];
+        for (row, r) in self.rows.iter().enumerate() {
+            if r.kind != RowKind::Body {
+                continue;
+            }
+            let c = self
+                .row(row)
+                .unwrap()
+                .find(|c| columns.contains(&c.column) && c.colspan == 1 && !c.s.is_empty());
+            if let Some(c) = c {
+                let align_h = self.cell_align_h(&c, &r.style, r.kind);
+                counts.iter_mut().find(|x| x.0 == align_h).unwrap().1 += 1;
+            }
+        }
+        let mut result = counts[0];
+        for x in &counts[1..] {
+            if x.1 > result.1 {
+                result = *x;
+            }
+        }
+        result.0
+    }
+
+    fn write_separator(
+        &self,
+        f: &mut impl Write,
+        cx: &RenderContext,
+        rows: [Option<usize>; 2],
+        kind: SeparatorKind,
+        position: usize,
+    ) -> Result {
+        let theme = &self.options.theme;
+        let ch = match kind {
+            SeparatorKind::Single => theme.line,
+            SeparatorKind::Double => theme.double_line,
+        };
+        let widths = &cx.widths;
+        let mut cs = rows.map(|row| row.and_then(|row| self.row(row)));
+        let merged: Vec<_> = (0..widths.len())
+            .map(|column| self.is_merged_at(rows[1], column))
+            .collect();
+        let junctions = &theme.junctions[position];
+        write!(f, "{0:<1$}", "", cx.marker_width)?;
+        if self.has_border(0) {
+            if merged.first() == Some(&true) || !self.options.frame {
+                write!(f, "{}", self.rule(0))?;
+            } else {
+                write!(f, "{}", junctions[0])?;
+            }
+        }
+        for (column, _) in widths.iter().enumerate() {
+            let ch = if merged[column] { ' ' } else { ch };
+            if self.has_left_padding(column) {
+                write_repeat(f, ch, theme.padding)?;
+            }
+            write_repeat(f, ch, widths[column])?;
+            if self.has_right_padding(column) {
+                write_repeat(f, ch, theme.padding)?;
+            }
+            for c in cs.iter_mut().flatten() {
+                while c.column <= column && c.next().is_some() {}
+            }
+            if self.has_border(column + 1) {
+                let rule = self.rule(column + 1);
+                if cs.iter().flatten().all(|x| x.column == column + 1) {
+                    let merged_both = merged[column] && merged.get(column + 1) != Some(&false);
+                    if self.options.frame && !merged_both {
+                        let junction = if column + 1 == widths.len() {
+                            junctions[2]
+                        } else {
+                            junctions[1]
+                        };
+                        write_repeat(f, junction, rule.width())?;
+                    } else {
+                        write!(f, "{rule}")?;
+                    }
+                } else {
+                    write_repeat(f, ch, rule.width())?;
+                }
+            }
+        }
+        writeln!(f)
+    }
+
+    fn is_merged_at(&self, row: Option<usize>, column: usize) -> bool {
+        let Some(row) = row.and_then(|row| self.row(row)) else {
+            return false;
+        };
+        for c in row {
+            if c.column <= column && column < c.column + c.colspan {
+                return c.merged;
+            }
+        }
+        false
+    }
+
+    fn row(&self, row: usize) -> Option<Cursor<'_>> {
+        if row < self.rows.len() {
+            Some(Cursor {
+                grid: self,
+                column: 0,
+                idx: self.cells_idx(row),
+                end: self.cells_idx(row + 1),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether each cell of the row displays the empty placeholder.
+    ///
+    /// The placeholder is displayed in the first cell of a column whose cells in the body row are all empty.
+    fn placeholders(&self, row: usize) -> Vec<bool> {
+        let cells: Vec<_> = self.row(row).unwrap().collect();
+        let mut result = vec![false; cells.len()];
+        if self.options.empty_placeholder.is_none() || self.rows[row].kind != RowKind::Body {
+            return result;
+        }
+        let mut i = 0;
+        while i < cells.len() {
+            let mut end = self.column_end_of(cells[i].column + cells[i].colspan);
+            let mut j = i + 1;
+            while j < cells.len() && cells[j].column < end {
+                end = max(end, self.column_end_of(cells[j].column + cells[j].colspan));
+                j += 1;
+            }
+            result[i] = cells[i..j].iter().all(|c| c.s.is_empty() && !c.merged);
+            i = j;
+        }
+        result
+    }
+    fn column_end_of(&self, n: usize) -> usize {
+        (n..self.columns)
+            .find(|&n| self.has_border(n))
+            .unwrap_or(self.columns)
+    }
+    fn is_row_visible(&self, row: usize) -> bool {
+        !(self.options.hide_header && self.rows[row].kind == RowKind::Header)
+    }
+
+    fn cells_idx(&self, row: usize) -> usize {
+        if let Some(row) = self.rows.get(row) {
+            row.cells_idx
+        } else {
+            self.cells.len()
+        }
+    }
+    fn s_idx(&self, cells_idx: usize) -> usize {
+        if let Some(cell) = self.cells.get(cells_idx) {
+            cell.s_idx
+        } else {
+            self.s.len()
+        }
+    }
+}
+
+const ERROR_TEXT: &str = "#ERROR";
+
+/// Error returned by [`GridBuilder::try_to_string`] when a cell failed to format.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CellError {
+    row: usize,
+    column: usize,
+}
+impl CellError {
+    /// Index of the row containing the cell.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Index of the first column of the cell.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+impl Display for CellError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(
+            f,
+            "failed to format the cell at row {}, column {}",
+            self.row, self.column
+        )
+    }
+}
+impl std::error::Error for CellError {}
+
+impl Display for GridBuilder {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        if let Some(g) = self.display_grid() {
+            return Display::fmt(&g, f);
+        }
+        self.write_with(f, self.render_context())
+    }
+}
+
+/// Display the rendered grid as a multi-line cell.
+///
+/// All lines of the nested grid are padded to the same width,
+/// so the grid is aligned as a block in the outer grid.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let mut items = GridBuilder::new();
+/// items.push(|b| {
+///     b.push("bolt");
+///     b.push(12);
+/// });
+/// items.push(|b| {
+///     b.push("nut");
+///     b.push(8);
+/// });
+///
+/// let mut g = GridBuilder::new();
+/// g.push_header(|b| {
+///     b.push("order");
+///     b.push("items");
+/// });
+/// g.push(|b| {
+///     b.push("A-1");
+///     b.push(&items);
+/// });
+/// g.push(|b| {
+///     b.push("B-2");
+///     b.push("none");
+/// });
+/// assert_eq!(format!("\n{g}"), r#"
+///  order |    items     |
+/// -------|--------------|
+///  A-1   |  bolt | 12 | |
+///        |  nut  |  8 | |
+///  B-2   | none         |
+/// "#);
+/// ```
+impl RawCell for GridBuilder {
+    fn fmt(&self, s: &mut String) {
+        let _ = self.try_fmt(s);
+    }
+    fn try_fmt(&self, s: &mut String) -> Result {
+        let mut text = String::new();
+        write!(text, "{self}")?;
+        let width = block_width(&text);
+        for (i, line) in text.lines().enumerate() {
+            if i != 0 {
+                s.push('\n');
+            }
+            s.push_str(line);
+            write_repeat(s, ' ', width - text_width(line))?;
+        }
+        match self.cell_error() {
+            Some(_) => Err(Error),
+            None => Ok(()),
+        }
+    }
+}
+impl Cells for GridBuilder {
+    fn fmt(f: &mut CellsFormatter<Self>) {
+        f.content_cell(|x| x);
+    }
+}
+
+struct DisplayWithLayout<'a> {
+    grid: &'a GridBuilder,
+    layout: &'a SharedLayout,
+}
+impl Display for DisplayWithLayout<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let cx = self.grid.render_context_with(Some(self.layout));
+        self.grid.write_with(f, cx)
+    }
+}
+impl Debug for GridBuilder {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        Display::fmt(self, f)
+    }
+}
+impl<T: Cells> FromIterator<T> for GridBuilder {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut b = Self::new();
+        b.extend_header::<T>();
+        b.extend_body(iter);
+        b
+    }
+}
+
+impl<T: Cells> Extend<T> for GridBuilder {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_body(iter);
+    }
+}
+
+/// Layout of the columns defined by a schema.
+///
+/// This structure is returned by [`GridBuilder::extend_header_with_schema`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct HeaderLayout {
+    depth: usize,
+    paths: Vec<Vec<String>>,
+    stretches: Vec<bool>,
+}
+
+impl HeaderLayout {
+    fn new(layout: &GridLayout) -> Self {
+        Self {
+            depth: layout.depth_max,
+            paths: layout.paths.clone(),
+            stretches: layout.styles.iter().map(|s| s.stretch).collect(),
+        }
+    }
+
+    /// Returns the number of columns.
+    pub fn column_count(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns the number of header rows, excluding the row of units.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the headers from the outermost to the innermost for each column.
+    pub fn paths(&self) -> &[Vec<String>] {
+        &self.paths
+    }
+
+    /// Returns the index of the column whose headers are `path`.
+    pub fn find(&self, path: &[&str]) -> Option<usize> {
+        self.paths.iter().position(|p| p.iter().eq(path))
+    }
+
+    /// Returns the index of the first column whose innermost header is `name` or whose headers joined with `.` are `name`.
+    pub fn find_by_name(&self, name: &str) -> Option<usize> {
+        self.paths
+            .iter()
+            .position(|p| p.last().is_some_and(|s| s == name) || p.join(".") == name)
+    }
+
+    /// Returns whether the column at `column` is stretched in preference to other columns.
+    ///
+    /// See [`ColumnStyle::stretch`] for details.
+    pub fn stretch(&self, column: usize) -> bool {
+        self.stretches.get(column).copied().unwrap_or(false)
+    }
+}
+
+/// Returns the layout of the columns defined by `schema`.
+///
+/// The columns are traversed in the same way as [`GridBuilder::extend_header_with_schema`],
+/// so this can be used to validate column names specified by users or to list the available columns.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct Item {
+///     name: &'static str,
+///     width: u32,
+///     height: u32,
+/// }
+/// impl Cells for Item {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |x| x.name);
+///         f.column_with("size", |f| {
+///             f.column("w", |x| x.width);
+///             f.column("h", |x| x.height);
+///         });
+///     }
+/// }
+///
+/// let headers = schema_headers(&DefaultCellsSchema::<Item>::default());
+/// let paths: Vec<_> = headers.paths().iter().map(|p| p.join(".")).collect();
+/// assert_eq!(paths, ["name", "size.w", "size.h"]);
+/// assert_eq!(headers.find_by_name("h"), Some(2));
+/// assert_eq!(headers.find_by_name("size.w"), Some(1));
+/// ```
+pub fn schema_headers<T: ?Sized>(schema: &dyn CellsSchema<Source = T>) -> HeaderLayout {
+    HeaderLayout::new(&GridLayout::from_schema(schema))
+}
+
+/// A column specified by index or by header text.
+///
+/// Used by [`GridBuilder::select_columns`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ColumnRef<'a> {
+    /// The index of the column.
+    Index(usize),
+    /// The text of a header cell.
+    Header(&'a str),
+}
+impl From<usize> for ColumnRef<'_> {
+    fn from(value: usize) -> Self {
+        ColumnRef::Index(value)
+    }
+}
+impl<'a> From<&'a str> for ColumnRef<'a> {
+    fn from(value: &'a str) -> Self {
+        ColumnRef::Header(value)
+    }
+}
+impl<'a> From<&'a String> for ColumnRef<'a> {
+    fn from(value: &'a String) -> Self {
+        ColumnRef::Header(value)
+    }
+}
+
+/// A view of a row of [`GridBuilder`].
+///
+/// This structure is created by [`GridBuilder::retain_rows`].
+pub struct RowView<'a> {
+    grid: &'a GridBuilder,
+    row: usize,
+}
+
+impl<'a> RowView<'a> {
+    /// Returns the index of the row.
+    pub fn index(&self) -> usize {
+        self.row
+    }
+
+    /// Returns the formatted value of the cell spanning `column`.
+    ///
+    /// Returns `None` if the row has no cell at `column`.
+    pub fn cell(&self, column: usize) -> Option<&'a str> {
+        self.grid
+            .row(self.row)
+            .unwrap()
+            .find(|c| c.column <= column && column < c.column + c.colspan)
+            .map(|c| c.s)
+    }
+
+    /// Returns the formatted values of the cells in the row.
+    pub fn cells(&self) -> impl Iterator<Item = &'a str> + 'a {
+        self.grid.row(self.row).unwrap().map(|c| c.s)
+    }
+}
+
+/// A builder used to create row of [`GridBuilder`].
+///
+/// This structure is created by [`GridBuilder::push`].
+pub struct RowBuilder<'a> {
+    grid: &'a mut GridBuilder,
+    cells_idx: usize,
+    kind: RowKind,
+    rowspans: VecDeque<(usize, usize)>,
+}
+
+impl RowBuilder<'_> {
+    /// Append a cell to the right of row.
+    pub fn push(&mut self, cell: impl RawCell) {
+        self.push_spanned();
+        self.grid.push_cell(cell, 1, self.kind);
+    }
+
+    /// Append a multi-column cell to the right of row.
+    ///
+    /// - `cell` : Contents of cell to be appended.
+    /// - `colspan` : Number of columns used by the cell to be appended.
+    ///
+    /// if `colspan == 0`, this method will do nothing.
+    pub fn push_with_colspan(&mut self, cell: impl RawCell, colspan: usize) {
+        if colspan != 0 {
+            self.push_spanned();
+            self.grid.push_cell(cell, colspan, self.kind);
+        }
+    }
+
+    /// Append a cell that spans downward across multiple rows.
+    ///
+    /// - `cell` : Contents of cell to be appended.
+    /// - `rowspan` : Number of rows used by the cell to be appended.
+    ///
+    /// The text is displayed in the top row, and the cells of the following rows in the same column are skipped.
+    /// Cells pushed to the following rows are placed to the right of the spanned cell.
+    /// A cell spanning multiple columns that covers the column ends the span.
+    /// The span also ends at the last body row, so it never extends into header or footer rows such as [`push_totals`](GridBuilder::push_totals).
+    ///
+    /// if `rowspan == 0`, this method will do nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push_with_rowspan("x", 2);
+    ///     b.push("p");
+    /// });
+    /// g.push_separator();
+    /// g.push(|b| b.push("q"));
+    /// g.push_separator();
+    /// g.push(|b| {
+    ///     b.push("y");
+    ///     b.push("r");
+    /// });
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  x | p |
+    ///    |---|
+    ///    | q |
+    /// ---|---|
+    ///  y | r |
+    /// "#);
+    /// ```
+    pub fn push_with_rowspan(&mut self, cell: impl RawCell, rowspan: usize) {
+        if rowspan != 0 {
+            self.push_spanned();
+            let column = self.column();
+            self.grid.push_cell(cell, 1, self.kind);
+            if rowspan > 1 {
+                self.grid.rowspans.push((column, rowspan - 1));
+            }
+        }
+    }
+
+    pub fn extend<T: ?Sized + Cells>(&mut self, source: &T) {
+        T::fmt(&mut CellsFormatter::new(
+            &mut BodyWriter::new(self),
+            Some(source),
+        ))
+    }
+    pub fn extend_with_schema<T: ?Sized>(
+        &mut self,
+        source: &T,
+        schema: impl CellsSchema<Source = T>,
+    ) {
+        schema.fmt(&mut CellsFormatter::new(
+            &mut BodyWriter::new(self),
+            Some(source),
+        ))
+    }
+}
+impl RowBuilder<'_> {
+    fn column(&self) -> usize {
+        self.grid.cells[self.cells_idx..]
+            .iter()
+            .map(|c| c.colspan)
+            .sum()
+    }
+
+    /// Push the cells covered by the cells spanning from the rows above at the current column.
+    fn push_spanned(&mut self) {
+        while let Some(&(column, rest)) = self.rowspans.front() {
+            let current = self.column();
+            if column > current {
+                break;
+            }
+            self.rowspans.pop_front();
+            if column == current {
+                self.grid.push_cell("", 1, self.kind);
+                self.grid.cells.last_mut().unwrap().merged = true;
+                if rest > 1 {
+                    self.grid.rowspans.push((column, rest - 1));
+                }
+            }
+        }
+    }
+}
+impl Drop for RowBuilder<'_> {
+    fn drop(&mut self) {
+        while let Some(&(column, _)) = self.rowspans.front() {
+            while self.column() < column {
+                self.grid.push_cell("", 1, self.kind);
+            }
+            self.push_spanned();
+        }
+        self.grid.rowspans.sort_unstable();
+        self.grid.push_row_entry(self.cells_idx, self.kind);
+    }
+}
+
+/// An iterator over the lines of a rendered [`GridBuilder`].
+///
+/// This structure is created by [`GridBuilder::lines`].
+pub struct Lines<'a> {
+    grid: &'a GridBuilder,
+    transposed: Option<Box<GridBuilder>>,
+    cx: RenderContext,
+    item: usize,
+    lines: VecDeque<String>,
+}
+impl Iterator for Lines<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.lines.is_empty() && self.item < self.cx.items.len() {
+            let mut s = String::new();
+            let item = &self.cx.items[self.item];
+            let grid = self.transposed.as_deref().unwrap_or(self.grid);
+            grid.write_item(&mut s, &self.cx, item).unwrap();
+            self.lines.extend(s.lines().map(|l| l.to_string()));
+            self.item += 1;
+        }
+        self.lines.pop_front()
+    }
+}
+
+struct RenderContext {
+    widths: Vec<usize>,
+    marker_width: usize,
+    indent: usize,
+    items: Vec<RenderItem>,
+}
+
+enum RenderItem {
+    Row(usize),
+    Separator(Option<usize>, Option<usize>, SeparatorKind),
+    /// Top (`0`) or bottom (`2`) line of the frame.
+    Frame(Option<usize>, Option<usize>, usize),
+    Elision(usize),
+    Footnote(usize),
+    Legend(usize),
+}
+
+struct Cursor<'a> {
+    grid: &'a GridBuilder,
+    column: usize,
+    idx: usize,
     end: usize,
 }
 impl<'a> Iterator for Cursor<'a> {
@@ -605,6 +3541,17 @@ impl<'a> Iterator for Cursor<'a> {
     }
 }
 
+/// A physical line of a row.
+#[derive(Clone, Copy)]
+enum RowLine {
+    /// The only line of a row that fits in a line.
+    Single,
+    /// The specified line of the cell contents.
+    Content(usize),
+    /// A line of vertical padding.
+    Blank,
+}
+
 struct CellRef<'a> {
     cell: &'a CellEntry,
     s: &'a str,
@@ -617,82 +3564,841 @@ impl<'a> Deref for CellRef<'a> {
     }
 }
 
-/// Column's style.
-#[derive(Debug, Clone, Eq, PartialEq)]
-#[derive_ex(Default)]
-#[default(Self::DEFAULT)]
-pub struct ColumnStyle {
-    /// If true, display a separator on the right side of this column.
+/// Column's style.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive_ex(Default)]
+#[default(Self::DEFAULT)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct ColumnStyle {
+    /// If true, display a separator on the right side of this column.
+    ///
+    /// This setting is ignored for the rightmost column, and the border is always displayed.
+    ///
+    /// The default for this is `true`.
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("A");
+    ///     b.push("B");
+    ///     b.push("C");
+    /// });
+    /// assert_eq!(format!("\n{g}"), E0);
+    ///
+    /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[0].column_end = false;
+    ///
+    /// assert_eq!(format!("\n{g}"), E1);
+    ///
+    /// const E0: &str = r"
+    ///  A | B | C |
+    /// ";
+    ///
+    /// const E1: &str = r"
+    ///  AB | C |
+    /// ";
+    /// ```
+    pub column_end: bool,
+
+    /// If true, prioritize this column width expansion over others.
+    ///
+    /// When stretching a multi-column layout,
+    /// if any column has `stretch` set to true, only those columns will be stretched,
+    /// while columns with `stretch` set to false will not be stretched.
+    ///
+    /// The default for this is `false`.
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push_with_colspan("............", 2);
+    /// });
+    /// g.push(|b| {
+    ///     b.push("A");
+    ///     b.push("B");
+    /// });
+    /// assert_eq!(format!("\n{g}"), E0);
+    ///
+    /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[0].stretch = true;
+    ///
+    /// assert_eq!(format!("\n{g}"), E1);
+    ///
+    /// const E0: &str = r"
+    ///  ............ |
+    ///  A     | B    |
+    /// ";
+    ///
+    /// const E1: &str = r"
+    ///  ............ |
+    ///  A        | B |
+    /// ";
+    /// ```
+    pub stretch: bool,
+
+    /// Minimum width of this column.
+    ///
+    /// The default for this is `0`.
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("A");
+    ///     b.push("B");
+    /// });
+    /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[0].min_width = 3;
+    ///
+    /// assert_eq!(format!("\n{g}"), r"
+    ///  A   | B |
+    /// ");
+    /// ```
+    pub min_width: usize,
+
+    /// Vertical rule displayed on the right side of this column.
+    ///
+    /// This setting is ignored if [`column_end`](Self::column_end) is `false`.
+    ///
+    /// The default for this is [`VerticalRule::Single`].
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("A");
+    ///     b.push("B");
+    ///     b.push("C");
+    /// });
+    /// g.push_separator();
+    /// g.push(|b| {
+    ///     b.push("1");
+    ///     b.push_with_colspan("2", 2);
+    /// });
+    /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[0].rule = VerticalRule::Double;
+    /// g.column_styles[1].rule = VerticalRule::Char('│');
+    ///
+    /// assert_eq!(format!("\n{g}"), r"
+    ///  A || B │ C |
+    /// ---||-------|
+    ///  1 || 2     |
+    /// ");
+    /// ```
+    pub rule: VerticalRule,
+
+    /// Horizontal alignment of the body cells in this column.
+    ///
+    /// Applied to cells that do not specify their own alignment,
+    /// overriding the default alignment of the type such as right-aligned numbers.
+    /// Cells spanning multiple columns are not affected.
+    ///
+    /// The default for this is `None`.
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("n");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(1);
+    /// });
+    /// g.push(|b| {
+    ///     b.push("b");
+    ///     b.push(cell(2).right());
+    /// });
+    /// g.push(|b| {
+    ///     b.push("c");
+    ///     b.push(333);
+    /// });
+    /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[0].align_h = Some(HorizontalAlignment::Right);
+    /// g.column_styles[1].align_h = Some(HorizontalAlignment::Left);
+    ///
+    /// assert_eq!(format!("\n{g}"), r"
+    ///  name |  n  |
+    /// ------|-----|
+    ///     a | 1   |
+    ///     b |   2 |
+    ///     c | 333 |
+    /// ");
+    /// ```
+    pub align_h: Option<HorizontalAlignment>,
+
+    /// Horizontal alignment of the header cells in this column.
     ///
-    /// This setting is ignored for the rightmost column, and the border is always displayed.
+    /// Applied to cells that do not specify their own alignment instead of the default center alignment.
+    /// Cells spanning multiple columns are not affected.
     ///
-    /// The default for this is `true`.
+    /// The default for this is `None`.
     ///
     /// ```
     /// use text_grid::*;
     /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("a");
+    ///     b.push("b");
+    /// });
     /// g.push(|b| {
-    ///     b.push("A");
-    ///     b.push("B");
-    ///     b.push("C");
+    ///     b.push("xxxx");
+    ///     b.push("yyyy");
     /// });
-    /// assert_eq!(format!("\n{g}"), E0);
+    /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[0].header_align = Some(HorizontalAlignment::Left);
     ///
+    /// assert_eq!(format!("\n{g}"), r"
+    ///  a    |  b   |
+    /// ------|------|
+    ///  xxxx | yyyy |
+    /// ");
+    /// ```
+    pub header_align: Option<HorizontalAlignment>,
+
+    /// Character used to fill the space around the text of the cells in this column when aligning them.
+    ///
+    /// The padding specified by the cells and the spaces next to the vertical rules are not affected.
+    /// For cells spanning multiple columns, the setting of the leftmost column is used.
+    /// Double-width characters are repeated as far as they fit, and the rest is filled with spaces.
+    /// Characters without a display width, such as control characters, are replaced by spaces.
+    ///
+    /// The default for this is `' '`.
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("name");
+    ///     b.push(cell("a").pad_left(1));
+    /// });
+    /// g.push(|b| {
+    ///     b.push("version");
+    ///     b.push(cell("1.0").pad_left(1));
+    /// });
     /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[0].pad_char = '.';
     /// g.column_styles[0].column_end = false;
     ///
-    /// assert_eq!(format!("\n{g}"), E1);
+    /// assert_eq!(format!("\n{g}"), r"
+    ///  name... a   |
+    ///  version 1.0 |
+    /// ");
+    /// ```
+    pub pad_char: char,
+
+    /// Maximum display width of the cells in this column.
     ///
-    /// const E0: &str = r"
-    ///  A | B | C |
-    /// ";
+    /// Lines of the cells wider than this are wrapped at whitespace onto multiple lines within the row.
+    /// Words wider than this are broken at character boundaries.
+    /// Cells spanning multiple columns are not affected.
+    ///
+    /// The default for this is `None`.
     ///
-    /// const E1: &str = r"
-    ///  AB | C |
-    /// ";
     /// ```
-    pub column_end: bool,
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("description");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("text-grid");
+    ///     b.push("A library to create formatted plain-text tables.");
+    /// });
+    /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[1].max_width = Some(20);
+    ///
+    /// assert_eq!(format!("\n{g}"), r"
+    ///    name    |     description      |
+    /// -----------|----------------------|
+    ///  text-grid | A library to create  |
+    ///            | formatted plain-text |
+    ///            | tables.              |
+    /// ");
+    /// ```
+    pub max_width: Option<usize>,
 
-    /// If true, prioritize this column width expansion over others.
+    /// Maximum display width of the lines of the cells in this column.
     ///
-    /// When stretching a multi-column layout,
-    /// if any column has `stretch` set to true, only those columns will be stretched,
-    /// while columns with `stretch` set to false will not be stretched.
+    /// Lines wider than this are cut at a character boundary and end with `…`.
+    /// Escape sequences are kept so that styles are reset correctly.
+    /// Cells spanning multiple columns are not affected.
+    /// If both this and [`max_width`](Self::max_width) are set, the lines are truncated before wrapping.
     ///
-    /// The default for this is `false`.
+    /// The default for this is `None`.
     ///
     /// ```
     /// use text_grid::*;
     /// let mut g = GridBuilder::new();
-    /// g.push(|b| {
-    ///     b.push_with_colspan("............", 2);
+    /// g.push_header(|b| {
+    ///     b.push("level");
+    ///     b.push("path");
     /// });
     /// g.push(|b| {
-    ///     b.push("A");
-    ///     b.push("B");
+    ///     b.push("info");
+    ///     b.push("/usr/local/share/text-grid/config.toml");
     /// });
-    /// assert_eq!(format!("\n{g}"), E0);
-    ///
     /// g.column_styles = vec![ColumnStyle::default(); 2];
-    /// g.column_styles[0].stretch = true;
+    /// g.column_styles[1].truncate = Some(12);
     ///
-    /// assert_eq!(format!("\n{g}"), E1);
+    /// assert_eq!(format!("\n{g}"), r"
+    ///  level |     path     |
+    /// -------|--------------|
+    ///  info  | /usr/local/… |
+    /// ");
+    /// ```
+    pub truncate: Option<usize>,
+
+    /// If true, this column is not displayed when all of its body and footer cells are empty.
     ///
-    /// const E0: &str = r"
-    ///  ............ |
-    ///  A     | B    |
-    /// ";
+    /// Cells spanning multiple columns are not taken into account.
+    /// Header cells that span only hidden columns are also removed.
+    ///
+    /// The default for this is `false`.
     ///
-    /// const E1: &str = r"
-    ///  ............ |
-    ///  A        | B |
-    /// ";
     /// ```
-    pub stretch: bool,
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("note");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push("");
+    /// });
+    /// g.column_styles = vec![ColumnStyle::default(); 2];
+    /// g.column_styles[1].hide_if_empty = true;
+    ///
+    /// assert_eq!(format!("\n{g}"), r"
+    ///  name |
+    /// ------|
+    ///  a    |
+    /// ");
+    /// ```
+    pub hide_if_empty: bool,
 }
 impl ColumnStyle {
     const DEFAULT: Self = Self {
         column_end: true,
         stretch: false,
+        min_width: 0,
+        rule: VerticalRule::Single,
+        align_h: None,
+        header_align: None,
+        pad_char: ' ',
+        max_width: None,
+        truncate: None,
+        hide_if_empty: false,
     };
+
+    /// Create a new `ColumnStyle` with default settings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let style = ColumnStyle::new().min_width(5).align_h(HorizontalAlignment::Right);
+    /// assert_eq!(style.min_width, 5);
+    /// ```
+    pub fn new() -> Self {
+        Self::DEFAULT
+    }
+
+    /// Return the style with [`column_end`](Self::column_end) set.
+    pub fn column_end(self, value: bool) -> Self {
+        Self {
+            column_end: value,
+            ..self
+        }
+    }
+
+    /// Return the style with [`stretch`](Self::stretch) set.
+    pub fn stretch(self, value: bool) -> Self {
+        Self {
+            stretch: value,
+            ..self
+        }
+    }
+
+    /// Return the style with [`min_width`](Self::min_width) set.
+    pub fn min_width(self, value: usize) -> Self {
+        Self {
+            min_width: value,
+            ..self
+        }
+    }
+
+    /// Return the style with [`rule`](Self::rule) set.
+    pub fn rule(self, value: VerticalRule) -> Self {
+        Self {
+            rule: value,
+            ..self
+        }
+    }
+
+    /// Return the style with [`align_h`](Self::align_h) set.
+    pub fn align_h(self, value: HorizontalAlignment) -> Self {
+        Self {
+            align_h: Some(value),
+            ..self
+        }
+    }
+
+    /// Return the style with [`header_align`](Self::header_align) set.
+    pub fn header_align(self, value: HorizontalAlignment) -> Self {
+        Self {
+            header_align: Some(value),
+            ..self
+        }
+    }
+
+    /// Return the style with [`pad_char`](Self::pad_char) set.
+    pub fn pad_char(self, value: char) -> Self {
+        Self {
+            pad_char: value,
+            ..self
+        }
+    }
+
+    /// Return the style with [`max_width`](Self::max_width) set.
+    pub fn max_width(self, value: usize) -> Self {
+        Self {
+            max_width: Some(value),
+            ..self
+        }
+    }
+
+    /// Return the style with [`truncate`](Self::truncate) set.
+    pub fn truncate(self, value: usize) -> Self {
+        Self {
+            truncate: Some(value),
+            ..self
+        }
+    }
+
+    /// Return the style with [`hide_if_empty`](Self::hide_if_empty) set.
+    pub fn hide_if_empty(self, value: bool) -> Self {
+        Self {
+            hide_if_empty: value,
+            ..self
+        }
+    }
+}
+
+/// Vertical rule displayed between columns.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerticalRule {
+    /// `|`
+    #[default]
+    Single,
+    /// `||`
+    Double,
+    /// The specified character, such as `│` or `‖`.
+    Char(char),
+}
+impl Display for VerticalRule {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            VerticalRule::Single => write!(f, "|"),
+            VerticalRule::Double => write!(f, "||"),
+            VerticalRule::Char(ch) => write!(f, "{ch}"),
+        }
+    }
+}
+
+/// Row's style.
+///
+/// Use [`GridBuilder::push_styled`] or [`GridBuilder::set_row_style`] to apply.
+///
+/// # Examples
+/// ```rust
+/// use text_grid::*;
+/// let mut g = GridBuilder::new();
+/// g.push(|b| {
+///     b.push("a");
+///     b.push(1);
+/// });
+/// g.push_styled(RowStyle::new().color(Color::Red), |b| {
+///     b.push("b");
+///     b.push(2);
+/// });
+/// assert_eq!(format!("\n{g}"), "\n a | 1 |\n\x1b[31m b | 2 |\x1b[0m\n");
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RowStyle {
+    align_h: Option<HorizontalAlignment>,
+    sgr: Sgr,
+    marker: Option<String>,
+    pad_top: Option<usize>,
+    pad_bottom: Option<usize>,
+}
+impl RowStyle {
+    /// Create a new `RowStyle` with no styles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the style with horizontal alignment of all cells in the row overridden.
+    pub fn align_h(self, value: HorizontalAlignment) -> Self {
+        Self {
+            align_h: Some(value),
+            ..self
+        }
+    }
+
+    /// Return the style with the text color set.
+    pub fn color(mut self, value: Color) -> Self {
+        self.sgr.fg = Some(value);
+        self
+    }
+
+    /// Return the style with the background color set.
+    pub fn highlight(mut self, value: Color) -> Self {
+        self.sgr.bg = Some(value);
+        self
+    }
+
+    /// Return the style with a marker displayed to the left of the row.
+    ///
+    /// Rows without a marker are indented by the width of the widest marker.
+    pub fn marker(self, value: impl Into<String>) -> Self {
+        Self {
+            marker: Some(value.into()),
+            ..self
+        }
+    }
+
+    /// Return the style with blank lines added above and below the cell contents.
+    ///
+    /// This overrides [`GridRenderOptions::vertical_padding`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(1);
+    /// });
+    /// g.push_styled(RowStyle::new().pad_v(1, 0), |b| {
+    ///     b.push("b");
+    ///     b.push(2);
+    /// });
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  a | 1 |
+    ///    |   |
+    ///  b | 2 |
+    /// "#);
+    /// ```
+    pub fn pad_v(self, top: usize, bottom: usize) -> Self {
+        Self {
+            pad_top: Some(top),
+            pad_bottom: Some(bottom),
+            ..self
+        }
+    }
+}
+
+fn min_some(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(min(a, b)),
+        _ => a.or(b),
+    }
+}
+
+fn write_thousands(f: &mut impl Write, n: usize) -> Result {
+    let s = n.to_string();
+    let mut i = (s.len() - 1) % 3 + 1;
+    f.write_str(&s[..i])?;
+    while i < s.len() {
+        f.write_char(',')?;
+        f.write_str(&s[i..i + 3])?;
+        i += 3;
+    }
+    Ok(())
+}
+/// A writer that inserts spaces at the start of each line.
+struct IndentWriter<'a, W> {
+    w: &'a mut W,
+    indent: usize,
+    line_start: bool,
+}
+impl<W: Write> Write for IndentWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> Result {
+        for line in s.split_inclusive('\n') {
+            if self.line_start {
+                write!(self.w, "{0:<1$}", "", self.indent)?;
+            }
+            self.w.write_str(line)?;
+            self.line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+/// Split `s` into lines no wider than `width`, breaking at whitespace where possible.
+fn wrap_words(s: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+    for word in s.split_whitespace() {
+        let word_width = text_width(word);
+        if line_width != 0 && line_width + 1 + word_width <= width {
+            line.push(' ');
+            line.push_str(word);
+            line_width += 1 + word_width;
+            continue;
+        }
+        if line_width != 0 {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0;
+        }
+        for ch in word.chars() {
+            let ch_width = ch.width().unwrap_or(0);
+            if line_width != 0 && line_width + ch_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            line.push(ch);
+            line_width += ch_width;
+        }
+    }
+    if line_width != 0 || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Reorder each line of `s` from logical order to visual order using the Unicode Bidirectional Algorithm.
+#[cfg(feature = "bidi")]
+fn bidi_visual(s: &str) -> String {
+    let mut result = String::new();
+    for (i, line) in s.split('\n').enumerate() {
+        if i != 0 {
+            result.push('\n');
+        }
+        let info = unicode_bidi::BidiInfo::new(line, None);
+        for para in &info.paragraphs {
+            result.push_str(&info.reorder_line(para, para.range.clone()));
+        }
+    }
+    result
+}
+
+/// Returns the width of the widest line of `s`.
+fn block_width(s: &str) -> usize {
+    s.split('\n').map(text_width).max().unwrap_or(0)
+}
+
+/// Write the start of `s` followed by `…` within `width`, and return the width written.
+///
+/// Escape sequences after the truncated position are kept so that the styles are reset.
+fn write_truncated(f: &mut String, s: &str, width: usize) -> usize {
+    if width == 0 {
+        return 0;
+    }
+    let mut written = 0;
+    let mut truncated = false;
+    let mut rest = s;
+    while let Some(ch) = rest.chars().next() {
+        if let Some(len) = escape_len(rest) {
+            f.push_str(&rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+        rest = &rest[ch.len_utf8()..];
+        let ch_width = ch.width().unwrap_or(0);
+        if !truncated && written + ch_width + 1 > width {
+            f.push('…');
+            truncated = true;
+        }
+        if !truncated {
+            f.push(ch);
+            written += ch_width;
+        }
+    }
+    if !truncated {
+        f.push('…');
+    }
+    written + 1
+}
+
+/// Write each line of `s`, truncating the lines wider than `width`, and return the width of the widest line.
+fn write_truncated_lines(f: &mut String, s: &str, width: usize) -> usize {
+    let mut result = 0;
+    for (i, line) in s.split('\n').enumerate() {
+        if i != 0 {
+            f.push('\n');
+        }
+        let line_width = text_width(line);
+        result = max(
+            result,
+            if line_width > width {
+                write_truncated(f, line, width)
+            } else {
+                f.push_str(line);
+                line_width
+            },
+        );
+    }
+    result
+}
+
+fn write_repeat(f: &mut impl Write, ch: char, n: usize) -> Result {
+    for _ in 0..n {
+        f.write_char(ch)?;
+    }
+    Ok(())
+}
+
+/// Kind of row separator.
+///
+/// The characters used are determined by [`GridTheme`](crate::GridTheme).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SeparatorKind {
+    /// Separator drawn with `-`.
+    #[default]
+    Single,
+    /// Separator drawn with `=`.
+    Double,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GridData {
+    rows: Vec<RowData>,
+    column_styles: Vec<ColumnStyle>,
+    options: GridRenderOptions,
+    footnotes: Vec<String>,
+    legend: Vec<(String, String)>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RowData {
+    kind: RowKind,
+    separator: Option<SeparatorKind>,
+    style: RowStyle,
+    cells: Vec<CellData>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CellData {
+    text: String,
+    colspan: usize,
+    style: CellStyle,
+    default_align: bool,
+    merged: bool,
+}
+
+/// Serialize the cells, row styles, column styles, options, footnotes and legend of the grid.
+///
+/// Registered [`Aggregator`]s are not serialized.
+/// Returns an error if a cell failed to format.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let mut g = GridBuilder::new();
+/// g.push_header(|b| b.push("name"));
+/// g.push(|b| b.push("apple"));
+///
+/// let json = serde_json::to_string(&g).unwrap();
+/// let g2: GridBuilder = serde_json::from_str(&json).unwrap();
+/// assert_eq!(g2.to_string(), g.to_string());
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for GridBuilder {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        if let Some(e) = self.cell_error() {
+            return Err(serde::ser::Error::custom(e));
+        }
+        if let Some(g) = self.resolved() {
+            return g.serialize(serializer);
+        }
+        let rows = (0..self.rows.len())
+            .map(|row| RowData {
+                kind: self.rows[row].kind,
+                separator: self.rows[row].separator,
+                style: self.rows[row].style.clone(),
+                cells: self
+                    .row(row)
+                    .unwrap()
+                    .map(|c| CellData {
+                        text: c.s.to_string(),
+                        colspan: c.colspan,
+                        style: c.style,
+                        default_align: c.default_align,
+                        merged: c.merged,
+                    })
+                    .collect(),
+            })
+            .collect();
+        GridData {
+            rows,
+            column_styles: self.column_styles.clone(),
+            options: self.options.clone(),
+            footnotes: self.footnotes.clone(),
+            legend: self.legend.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Reconstruct a grid serialized by [`Serialize`](serde::Serialize).
+///
+/// The reconstructed grid can be rendered or extended with more rows.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for GridBuilder {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let data = GridData::deserialize(deserializer)?;
+        let mut g = GridBuilder {
+            column_styles: data.column_styles,
+            options: data.options,
+            footnotes: data.footnotes,
+            legend: data.legend,
+            ..GridBuilder::new()
+        };
+        for row in data.rows {
+            let cells_idx = g.cells.len();
+            for cell in row.cells {
+                if cell.colspan == 0 {
+                    return Err(serde::de::Error::custom("colspan must be greater than 0"));
+                }
+                let s_idx = g.s.len();
+                g.s.push_str(&cell.text);
+                let width = g.cached_text_width(s_idx);
+                g.cells.push(CellEntry {
+                    s_idx,
+                    width,
+                    colspan: cell.colspan,
+                    style: cell.style,
+                    default_align: cell.default_align,
+                    merged: cell.merged,
+                    error: false,
+                    header_key: None,
+                });
+            }
+            let e = g.push_row_entry(cells_idx, row.kind);
+            e.separator = row.separator;
+            e.style = row.style;
+        }
+        Ok(g)
+    }
 }
@@ -0,0 +1,392 @@
+use crate::{GridTheme, HorizontalAlignment};
+
+/// Options used when rendering [`GridBuilder`](crate::GridBuilder).
+///
+/// # Examples
+/// ```rust
+/// use text_grid::*;
+/// struct RowData {
+///     a: u32,
+///     b: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("a", |s| s.a);
+///         f.column("b", |s| s.b);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { a: 300, b: 1 },
+///     RowData { a: 2, b: 200 },
+/// ];
+/// let g = to_grid_with_options(rows, GridRenderOptions::new().repeat_header(true));
+/// assert_eq!(format!("\n{g}"), r#"
+///   a  |  b  |
+/// -----|-----|
+///  300 |   1 |
+///    2 | 200 |
+/// -----|-----|
+///   a  |  b  |
+/// "#);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridRenderOptions {
+    pub(crate) repeat_header: bool,
+    pub(crate) hide_header: bool,
+    pub(crate) frame: bool,
+    pub(crate) max_rows: Option<(usize, usize)>,
+    pub(crate) auto_transpose: Option<(usize, usize)>,
+    pub(crate) empty_placeholder: Option<String>,
+    pub(crate) block_align: Option<(usize, HorizontalAlignment)>,
+    pub(crate) full_width_padding: bool,
+    pub(crate) vertical_padding: (usize, usize),
+    pub(crate) rtl: bool,
+    pub(crate) theme: GridTheme,
+    #[cfg(feature = "bidi")]
+    pub(crate) bidi_visual: bool,
+}
+
+impl GridRenderOptions {
+    /// Create a new `GridRenderOptions` with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// If true, the header rows are displayed again at the bottom of the table.
+    ///
+    /// The default for this is `false`.
+    pub fn repeat_header(mut self, value: bool) -> Self {
+        self.repeat_header = value;
+        self
+    }
+
+    /// If true, the header rows are not displayed.
+    ///
+    /// Column styles derived from the schema are still applied,
+    /// and the widths of the header cells are not used to calculate column widths.
+    ///
+    /// The default for this is `false`.
+    pub fn hide_header(mut self, value: bool) -> Self {
+        self.hide_header = value;
+        self
+    }
+
+    /// If true, the table is surrounded by a frame and `+` is used for the junctions of the rules.
+    ///
+    /// The default for this is `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("a");
+    ///     b.push("b");
+    /// });
+    /// g.push(|b| {
+    ///     b.push(1);
+    ///     b.push(20);
+    /// });
+    /// g.options = GridRenderOptions::new().frame(true);
+    /// assert_eq!(format!("\n{g}"), r#"
+    /// +---+----+
+    /// | a | b  |
+    /// +---+----+
+    /// | 1 | 20 |
+    /// +---+----+
+    /// "#);
+    /// ```
+    pub fn frame(mut self, value: bool) -> Self {
+        self.frame = value;
+        self
+    }
+
+    /// Set the characters and padding used to draw the grid.
+    ///
+    /// The default for this is [`GridTheme::ascii`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let rows = [[1, 20], [300, 4]];
+    /// let g = to_grid_with_options(rows, GridRenderOptions::new().theme(GridTheme::compact()));
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  0 |1 |
+    /// ---|--|
+    ///   1|20|
+    /// 300| 4|
+    /// "#);
+    /// ```
+    pub fn theme(mut self, theme: GridTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Limit the number of body rows displayed.
+    ///
+    /// If the number of body rows exceeds `n`, only the first `n` rows are displayed,
+    /// followed by a line with the number of omitted rows.
+    /// Header and footer rows are always displayed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.extend_body(0..2000);
+    /// g.options = GridRenderOptions::new().max_rows(2);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///     0 |
+    ///     1 |
+    ///  … 1,998 more rows |
+    /// "#);
+    /// ```
+    pub fn max_rows(self, n: usize) -> Self {
+        self.max_rows_with_tail(n, 0)
+    }
+
+    /// Limit the number of body rows displayed, keeping the last rows.
+    ///
+    /// If the number of body rows exceeds `head + tail`, only the first `head` rows and the last `tail` rows are displayed,
+    /// with a line with the number of omitted rows between them.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| b.push("value"));
+    /// g.extend_body(0..5);
+    /// g.options = GridRenderOptions::new().max_rows_with_tail(1, 1);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  value |
+    /// -------|
+    ///      0 |
+    ///  … 3 more rows |
+    ///      4 |
+    /// "#);
+    /// ```
+    pub fn max_rows_with_tail(mut self, head: usize, tail: usize) -> Self {
+        self.max_rows = Some((head, tail));
+        self
+    }
+
+    /// Display the grid transposed if it is wider than `max_width` and has at most `max_rows` body rows.
+    ///
+    /// In the transposed grid, each column of the original grid is displayed as a row,
+    /// with the header cells as the keys in the leftmost columns.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("description");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("text-grid");
+    ///     b.push("A library to create formatted plain-text tables.");
+    /// });
+    /// g.options = GridRenderOptions::new().auto_transpose(40, 3);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name        | text-grid                                        |
+    ///  description | A library to create formatted plain-text tables. |
+    /// "#);
+    /// ```
+    pub fn auto_transpose(mut self, max_width: usize, max_rows: usize) -> Self {
+        self.auto_transpose = Some((max_width, max_rows));
+        self
+    }
+
+    /// Display `value` in place of body cells that are empty.
+    ///
+    /// The placeholder is displayed when all cells of a column in a body row are empty,
+    /// such as cells for `None`.
+    /// Vertically merged cells are not affected.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// struct RowData {
+    ///     name: &'static str,
+    ///     value: Option<u32>,
+    /// }
+    /// impl Cells for RowData {
+    ///     fn fmt(f: &mut CellsFormatter<Self>) {
+    ///         f.column("name", |s| s.name);
+    ///         f.column("value", |s| s.value);
+    ///     }
+    /// }
+    ///
+    /// let rows = [
+    ///     RowData { name: "a", value: Some(300) },
+    ///     RowData { name: "", value: None },
+    /// ];
+    /// let g = to_grid_with_options(rows, GridRenderOptions::new().empty_placeholder("-"));
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | value |
+    /// ------|-------|
+    ///  a    |   300 |
+    ///  -    | -     |
+    /// "#);
+    /// ```
+    pub fn empty_placeholder(mut self, value: impl Into<String>) -> Self {
+        self.empty_placeholder = Some(value.into());
+        self
+    }
+
+    /// Indent every line so that the table is aligned within `width` columns.
+    ///
+    /// If the table is wider than `width`, it is not indented.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| b.push("result"));
+    /// g.push(|b| b.push("ok"));
+    /// g.options = GridRenderOptions::new().block_align(20, HorizontalAlignment::Center);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///       result |
+    ///      --------|
+    ///       ok     |
+    /// "#);
+    /// ```
+    pub fn block_align(mut self, width: usize, align: HorizontalAlignment) -> Self {
+        self.block_align = Some((width, align));
+        self
+    }
+
+    /// If true, the padding in cells is made of ideographic spaces (U+3000) as much as possible.
+    ///
+    /// Each ideographic space occupies two columns, and a half-width space is used for the remaining odd column.
+    /// This keeps columns aligned in editors and fonts where the width of half-width spaces does not match that of CJK characters.
+    /// Padding of columns with [`ColumnStyle::pad_char`](crate::ColumnStyle::pad_char) other than a space is not affected.
+    ///
+    /// The default for this is `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| b.push("名前"));
+    /// g.push(|b| b.push("ab"));
+    /// g.options = GridRenderOptions::new().full_width_padding(true);
+    /// assert_eq!(format!("\n{g}"), "\n 名前 |\n ab\u{3000} |\n");
+    /// ```
+    pub fn full_width_padding(mut self, value: bool) -> Self {
+        self.full_width_padding = value;
+        self
+    }
+
+    /// Add blank lines above and below the contents of each body row.
+    ///
+    /// Use [`RowStyle::pad_v`](crate::RowStyle::pad_v) to set the padding of individual rows.
+    ///
+    /// The default for this is `(0, 0)`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| b.push("name"));
+    /// g.push(|b| b.push("a"));
+    /// g.push(|b| b.push("b"));
+    /// g.options = GridRenderOptions::new().vertical_padding(0, 1);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name |
+    /// ------|
+    ///  a    |
+    ///       |
+    ///  b    |
+    ///       |
+    /// "#);
+    /// ```
+    pub fn vertical_padding(mut self, top: usize, bottom: usize) -> Self {
+        self.vertical_padding = (top, bottom);
+        self
+    }
+
+    /// If true, display the table for right-to-left text.
+    ///
+    /// The order of the columns is reversed, and left and right alignments are swapped.
+    /// This does not affect the output of CSV and Markdown, which keep the logical order.
+    ///
+    /// The default for this is `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("size");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(10);
+    /// });
+    /// g.options = GridRenderOptions::new().rtl(true);
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  size | name |
+    /// ------|------|
+    ///  10   |    a |
+    /// "#);
+    /// ```
+    pub fn rtl(mut self, value: bool) -> Self {
+        self.rtl = value;
+        self
+    }
+
+    /// If true, the text of each cell is reordered from logical order to visual order
+    /// using the Unicode Bidirectional Algorithm.
+    ///
+    /// Use this for terminals that do not reorder right-to-left text such as Hebrew and Arabic.
+    /// The paragraph direction of each line is determined by its first strong character.
+    ///
+    /// The default for this is `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| b.push("שלום abc"));
+    /// g.options = GridRenderOptions::new().bidi_visual(true);
+    /// assert_eq!(format!("\n{g}"), "\n abc םולש |\n");
+    /// ```
+    #[cfg(feature = "bidi")]
+    pub fn bidi_visual(mut self, value: bool) -> Self {
+        self.bidi_visual = value;
+        self
+    }
+
+    /// Copy the settings of `self` that differ from the default to `target`.
+    pub(crate) fn merge_into(&self, target: &mut Self) {
+        let d = Self::default();
+        macro_rules! merge {
+            ($($field:ident),*) => {
+                $(
+                    if self.$field != d.$field {
+                        target.$field = self.$field.clone();
+                    }
+                )*
+            };
+        }
+        merge!(
+            repeat_header,
+            hide_header,
+            frame,
+            max_rows,
+            auto_transpose,
+            empty_placeholder,
+            block_align,
+            full_width_padding,
+            vertical_padding,
+            rtl,
+            theme
+        );
+        #[cfg(feature = "bidi")]
+        merge!(bidi_visual);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::Result;
+
+use crate::{CellStyle, HorizontalAlignment, RowKind, SeparatorKind};
+
+/// Output format of [`GridBuilder`](crate::GridBuilder) that receives the resolved layout.
+///
+/// Use [`GridBuilder::render`](crate::GridBuilder::render) to render a grid with a `GridRenderer`.
+/// The column widths are computed in the same way as the plain-text table,
+/// after applying [`GridRenderOptions`](crate::GridRenderOptions) such as transposition and wrapping.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt::{Result, Write};
+/// use text_grid::*;
+///
+/// struct MediaWiki(String);
+/// impl GridRenderer for MediaWiki {
+///     fn begin(&mut self, _widths: &[usize]) -> Result {
+///         writeln!(self.0, "{{| class=\"wikitable\"")
+///     }
+///     fn row(&mut self, kind: RowKind, cells: &[RenderCell]) -> Result {
+///         writeln!(self.0, "|-")?;
+///         let mark = if kind == RowKind::Header { '!' } else { '|' };
+///         for c in cells {
+///             match c.colspan() {
+///                 1 => writeln!(self.0, "{mark} {}", c.text())?,
+///                 n => writeln!(self.0, "{mark} colspan=\"{n}\" | {}", c.text())?,
+///             }
+///         }
+///         Ok(())
+///     }
+///     fn end(&mut self) -> Result {
+///         writeln!(self.0, "|}}")
+///     }
+/// }
+///
+/// let mut g = GridBuilder::new();
+/// g.push_header(|b| b.push_with_colspan("items", 2));
+/// g.push(|b| {
+///     b.push("a");
+///     b.push(1);
+/// });
+/// let mut r = MediaWiki(String::new());
+/// g.render(&mut r)?;
+/// assert_eq!(r.0, r#"{| class="wikitable"
+/// |-
+/// ! colspan="2" | items
+/// |-
+/// | a
+/// | 1
+/// |}
+/// "#);
+/// # Ok::<(), std::fmt::Error>(())
+/// ```
+pub trait GridRenderer {
+    /// Called before the other methods with the content width of each column.
+    fn begin(&mut self, widths: &[usize]) -> Result {
+        let _ = widths;
+        Ok(())
+    }
+
+    /// Called for each row.
+    fn row(&mut self, kind: RowKind, cells: &[RenderCell]) -> Result;
+
+    /// Called for each row separator.
+    fn separator(&mut self, kind: SeparatorKind) -> Result {
+        let _ = kind;
+        Ok(())
+    }
+
+    /// Called in place of the body rows omitted by [`GridRenderOptions::max_rows`](crate::GridRenderOptions::max_rows).
+    fn elision(&mut self, count: usize) -> Result {
+        let _ = count;
+        Ok(())
+    }
+
+    /// Called for each footnote with its number starting from 1.
+    fn footnote(&mut self, number: usize, text: &str) -> Result {
+        let _ = (number, text);
+        Ok(())
+    }
+
+    /// Called for each legend entry.
+    fn legend(&mut self, symbol: &str, meaning: &str) -> Result {
+        let _ = (symbol, meaning);
+        Ok(())
+    }
+
+    /// Called after the other methods.
+    fn end(&mut self) -> Result {
+        Ok(())
+    }
+}
+
+/// A cell passed to [`GridRenderer::row`].
+#[derive(Debug, Clone)]
+pub struct RenderCell<'a> {
+    pub(crate) text: &'a str,
+    pub(crate) column: usize,
+    pub(crate) colspan: usize,
+    pub(crate) width: usize,
+    pub(crate) align: HorizontalAlignment,
+    pub(crate) style: CellStyle,
+    pub(crate) merged: bool,
+}
+
+impl RenderCell<'_> {
+    /// Returns the text of the cell, which may contain line breaks.
+    pub fn text(&self) -> &str {
+        self.text
+    }
+
+    /// Returns the index of the first column of the cell.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the number of columns spanned by the cell.
+    pub fn colspan(&self) -> usize {
+        self.colspan
+    }
+
+    /// Returns the content width of the cell, including the columns and rules it spans.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the resolved horizontal alignment of the cell.
+    pub fn align(&self) -> HorizontalAlignment {
+        self.align
+    }
+
+    /// Returns the style of the cell.
+    pub fn style(&self) -> &CellStyle {
+        &self.style
+    }
+
+    /// Returns true if the cell is merged with the cell above, and its text should not be displayed.
+    pub fn merged(&self) -> bool {
+        self.merged
+    }
+}
@@ -0,0 +1,235 @@
+/// Characters and spacing used to draw a grid.
+///
+/// Set with [`GridRenderOptions::theme`](crate::GridRenderOptions::theme) or [`GridBuilder::set_theme`](crate::GridBuilder::set_theme).
+///
+/// [`VerticalRule::Char`](crate::VerticalRule::Char) set in [`ColumnStyle`](crate::ColumnStyle) takes precedence over the theme.
+///
+/// # Examples
+/// ```rust
+/// use text_grid::*;
+/// let mut g = GridBuilder::new();
+/// g.push_header(|b| {
+///     b.push("a");
+///     b.push("b");
+/// });
+/// g.push(|b| {
+///     b.push(1);
+///     b.push(20);
+/// });
+/// g.options = GridRenderOptions::new().theme(GridTheme::unicode()).frame(true);
+/// assert_eq!(format!("\n{g}"), r#"
+/// ┌───┬────┐
+/// │ a │ b  │
+/// ├───┼────┤
+/// │ 1 │ 20 │
+/// └───┴────┘
+/// "#);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridTheme {
+    pub(crate) rule: String,
+    pub(crate) double_rule: String,
+    pub(crate) line: char,
+    pub(crate) double_line: char,
+    pub(crate) junctions: [[char; 3]; 3],
+    pub(crate) padding: usize,
+    pub(crate) left_border: bool,
+}
+
+impl Default for GridTheme {
+    fn default() -> Self {
+        Self::ascii()
+    }
+}
+
+impl GridTheme {
+    /// The default theme using `|`, `-`, `=` and `+`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| b.push("a"));
+    /// g.push(|b| b.push(1));
+    /// g.set_theme(GridTheme::ascii());
+    /// assert_eq!(format!("\n{g}"), "\n a |\n---|\n 1 |\n");
+    /// ```
+    pub fn ascii() -> Self {
+        Self {
+            rule: "|".into(),
+            double_rule: "||".into(),
+            line: '-',
+            double_line: '=',
+            junctions: [['+'; 3]; 3],
+            padding: 1,
+            left_border: false,
+        }
+    }
+
+    /// A theme using box-drawing characters.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| b.push("a"));
+    /// g.push(|b| b.push(1));
+    /// g.set_theme(GridTheme::unicode());
+    /// assert_eq!(format!("\n{g}"), "\n a │\n───│\n 1 │\n");
+    /// ```
+    pub fn unicode() -> Self {
+        Self {
+            rule: "│".into(),
+            double_rule: "║".into(),
+            line: '─',
+            double_line: '═',
+            junctions: [['┌', '┬', '┐'], ['├', '┼', '┤'], ['└', '┴', '┘']],
+            ..Self::ascii()
+        }
+    }
+
+    /// A theme that looks like a Markdown table, with a vertical rule on the left side.
+    ///
+    /// Unlike [`GridBuilder::to_markdown`](crate::GridBuilder::to_markdown), the cells are not escaped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("a");
+    ///     b.push("b");
+    /// });
+    /// g.push(|b| {
+    ///     b.push(1);
+    ///     b.push(20);
+    /// });
+    /// g.set_theme(GridTheme::markdown());
+    /// assert_eq!(format!("\n{g}"), r#"
+    /// | a | b  |
+    /// |---|----|
+    /// | 1 | 20 |
+    /// "#);
+    /// ```
+    pub fn markdown() -> Self {
+        Self {
+            left_border: true,
+            ..Self::ascii()
+        }
+    }
+
+    /// A theme without padding around the cells.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("a");
+    ///     b.push("b");
+    /// });
+    /// g.push(|b| {
+    ///     b.push(1);
+    ///     b.push(20);
+    /// });
+    /// g.set_theme(GridTheme::compact());
+    /// assert_eq!(format!("\n{g}"), r#"
+    /// a|b |
+    /// -|--|
+    /// 1|20|
+    /// "#);
+    /// ```
+    pub fn compact() -> Self {
+        Self {
+            padding: 0,
+            ..Self::ascii()
+        }
+    }
+
+    /// A theme without vertical rules.
+    ///
+    /// Columns are separated only by padding, and separators are drawn as continuous lines.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("a");
+    ///     b.push("b");
+    /// });
+    /// g.push(|b| {
+    ///     b.push(1);
+    ///     b.push(20);
+    /// });
+    /// g.set_theme(GridTheme::borderless());
+    /// assert_eq!(format!("\n{g}"), "\n a  b  \n-------\n 1  20 \n");
+    /// ```
+    pub fn borderless() -> Self {
+        Self {
+            rule: String::new(),
+            double_rule: String::new(),
+            ..Self::ascii()
+        }
+    }
+
+    /// Set the text of [`VerticalRule::Single`](crate::VerticalRule::Single) and [`VerticalRule::Double`](crate::VerticalRule::Double).
+    pub fn rules(mut self, single: impl Into<String>, double: impl Into<String>) -> Self {
+        self.rule = single.into();
+        self.double_rule = double.into();
+        self
+    }
+
+    /// Set the characters of [`SeparatorKind::Single`](crate::SeparatorKind::Single) and [`SeparatorKind::Double`](crate::SeparatorKind::Double).
+    pub fn lines(mut self, single: char, double: char) -> Self {
+        self.line = single;
+        self.double_line = double;
+        self
+    }
+
+    /// Set the character used for all the junctions of the rules when [`GridRenderOptions::frame`](crate::GridRenderOptions::frame) is enabled.
+    pub fn cross(mut self, value: char) -> Self {
+        self.junctions = [[value; 3]; 3];
+        self
+    }
+
+    /// Set the characters used for the junctions of the rules when [`GridRenderOptions::frame`](crate::GridRenderOptions::frame) is enabled.
+    ///
+    /// `top`, `middle` and `bottom` are the characters of the top line, the separators between rows and the bottom line.
+    /// Each of them contains the characters of the left edge, the junctions between columns and the right edge.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push("b");
+    /// });
+    /// g.options = GridRenderOptions::new()
+    ///     .theme(GridTheme::ascii().junctions(['.', '+', '.'], ['+'; 3], ['\'', '+', '\'']))
+    ///     .frame(true);
+    /// assert_eq!(format!("\n{g}"), r#"
+    /// .---+---.
+    /// | a | b |
+    /// '---+---'
+    /// "#);
+    /// ```
+    pub fn junctions(mut self, top: [char; 3], middle: [char; 3], bottom: [char; 3]) -> Self {
+        self.junctions = [top, middle, bottom];
+        self
+    }
+
+    /// Set the number of spaces on each side of the cells.
+    pub fn padding(mut self, value: usize) -> Self {
+        self.padding = value;
+        self
+    }
+
+    /// If true, a vertical rule is displayed on the left side of the table even if the frame is disabled.
+    pub fn left_border(mut self, value: bool) -> Self {
+        self.left_border = value;
+        self
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// Translate header keys into localized strings.
+///
+/// When a resolver is set by [`GridBuilder::set_header_resolver`](crate::GridBuilder::set_header_resolver),
+/// it is consulted for each cell of the header rows when the grid is displayed or exported.
+/// The key of a cell is the one specified by [`Cell::header_key`](crate::Cell::header_key)
+/// or `#[cells(header_key = "...")]`, or the text of the cell if no key is specified.
+/// If the resolver returns `None`, the text of the cell is displayed as is.
+///
+/// `HeaderResolver` is implemented for maps from keys to strings and for closures.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+///
+/// #[derive(Cells)]
+/// struct Item {
+///     #[cells(header_key = "col.name")]
+///     name: &'static str,
+///     #[cells(header_key = "col.price")]
+///     price: u32,
+/// }
+///
+/// fn report(resolver: impl HeaderResolver + 'static) -> String {
+///     let mut g = GridBuilder::new();
+///     g.set_header_resolver(resolver);
+///     g.extend_header::<Item>();
+///     g.extend_body([Item { name: "apple", price: 100 }]);
+///     g.to_string()
+/// }
+///
+/// assert_eq!(format!("\n{}", report(|_: &str| None)), r#"
+///  name  | price |
+/// -------|-------|
+///  apple |   100 |
+/// "#);
+///
+/// let fr = |key: &str| match key {
+///     "col.name" => Some("nom".to_string()),
+///     "col.price" => Some("prix".to_string()),
+///     _ => None,
+/// };
+/// assert_eq!(format!("\n{}", report(fr)), r#"
+///   nom  | prix |
+/// -------|------|
+///  apple |  100 |
+/// "#);
+/// ```
+pub trait HeaderResolver {
+    /// Returns the localized string for `key`, or `None` if `key` is unknown.
+    fn resolve(&self, key: &str) -> Option<String>;
+}
+
+impl<F: Fn(&str) -> Option<String>> HeaderResolver for F {
+    fn resolve(&self, key: &str) -> Option<String> {
+        self(key)
+    }
+}
+impl<S: std::hash::BuildHasher> HeaderResolver for HashMap<String, String, S> {
+    fn resolve(&self, key: &str) -> Option<String> {
+        self.get(key).cloned()
+    }
+}
+impl HeaderResolver for BTreeMap<String, String> {
+    fn resolve(&self, key: &str) -> Option<String> {
+        self.get(key).cloned()
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt::Write;
+
+use crate::GridBuilder;
+
+/// Generate a table showing bytes as a hex dump.
+///
+/// Each row shows the offset, the bytes in hexadecimal and the bytes as ASCII characters.
+/// Characters other than printable ASCII are displayed as `.`.
+///
+/// See [`HexdumpOptions`] to change the number of bytes per row and the grouping.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let g = to_hexdump(b"Hello, world!\n");
+/// assert_eq!(format!("\n{g}"), r#"
+///  00000000 | 48 65 6c 6c 6f 2c 20 77 | 6f 72 6c 64 21 0a | Hello, world!. |
+/// "#);
+/// ```
+pub fn to_hexdump(bytes: &[u8]) -> String {
+    HexdumpOptions::new().to_hexdump(bytes)
+}
+
+/// Options used by [`to_hexdump`].
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let g = HexdumpOptions::new().width(6).group(2).to_hexdump(b"\x00\x01abcdefgh");
+/// assert_eq!(format!("\n{g}"), r#"
+///  00000000 | 00 01 | 61 62 | 63 64 | ..abcd |
+///  00000006 | 65 66 | 67 68 |       | efgh   |
+/// "#);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HexdumpOptions {
+    width: usize,
+    group: usize,
+}
+
+impl Default for HexdumpOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HexdumpOptions {
+    /// Create a new `HexdumpOptions` with default settings.
+    pub fn new() -> Self {
+        Self {
+            width: 16,
+            group: 8,
+        }
+    }
+
+    /// Set the number of bytes displayed in a row.
+    ///
+    /// The default for this is `16`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `0`.
+    pub fn width(mut self, value: usize) -> Self {
+        assert!(value != 0, "width must be greater than 0");
+        self.width = value;
+        self
+    }
+
+    /// Set the number of bytes displayed in a column.
+    ///
+    /// The default for this is `8`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `0`.
+    pub fn group(mut self, value: usize) -> Self {
+        assert!(value != 0, "group must be greater than 0");
+        self.group = value;
+        self
+    }
+
+    /// Generate a table showing `bytes` as a hex dump.
+    pub fn to_hexdump(&self, bytes: &[u8]) -> String {
+        let groups = self.width.div_ceil(self.group);
+        let mut g = GridBuilder::new();
+        for (i, row) in bytes.chunks(self.width).enumerate() {
+            g.push(|b| {
+                b.push(format!("{:08x}", i * self.width));
+                for n in 0..groups {
+                    let start = (n * self.group).min(row.len());
+                    let end = ((n + 1) * self.group).min(self.width).min(row.len());
+                    let mut s = String::new();
+                    for (i, byte) in row[start..end].iter().enumerate() {
+                        if i != 0 {
+                            s.push(' ');
+                        }
+                        write!(s, "{byte:02x}").unwrap();
+                    }
+                    b.push(s);
+                }
+                let text: String = row
+                    .iter()
+                    .map(|&byte| {
+                        if byte.is_ascii_graphic() || byte == b' ' {
+                            byte as char
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect();
+                b.push(text);
+            });
+        }
+        g.to_string()
+    }
+}
@@ -0,0 +1,173 @@
+use std::{borrow::Borrow, fmt::Write, io};
+
+use csv::StringRecord;
+
+use crate::{
+    cells_csv_writer::{header_record, CsvBodyWriter},
+    Cells, CellsSchema, DefaultCellsSchema,
+};
+
+/// Generate a JSON array of objects using the columns defined by [`Cells`](crate::Cells).
+///
+/// Each row is converted to an object whose keys are the headers of the columns,
+/// with the headers of nested columns joined by `.` as in [`to_csv`](crate::to_csv).
+/// Columns without a header are named by their position, after the path of the parent columns if any.
+/// The values are the formatted strings of the cells.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     size: [u32; 2],
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("size", |s| s.size);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "a", size: [1, 2] },
+///     RowData { name: "\"b\"", size: [30, 40] },
+/// ];
+/// assert_eq!(
+///     to_json(rows),
+///     r#"[{"name":"a","size.0":"1","size.1":"2"},{"name":"\"b\"","size.0":"30","size.1":"40"}]"#
+/// );
+/// ```
+pub fn to_json(rows: impl IntoIterator<Item = impl Cells>) -> String {
+    to_json_with_schema(rows, DefaultCellsSchema::default())
+}
+
+/// Generate a JSON array of objects using the columns defined by [`CellsSchema`].
+///
+/// See [`to_json`] for details.
+pub fn to_json_with_schema<T>(
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> String {
+    let mut w = JsonRowWriter::new(&schema);
+    let mut s = String::from("[");
+    for (i, row) in rows.into_iter().enumerate() {
+        if i != 0 {
+            s.push(',');
+        }
+        w.write_object(&mut s, &schema, row.borrow());
+    }
+    s.push(']');
+    s
+}
+
+/// Write JSON Lines using the columns defined by [`Cells`](crate::Cells) to `writer`.
+///
+/// Each row is written as a JSON object followed by `\n` as soon as it is formatted.
+/// The objects are the same as the elements of the array generated by [`to_json`].
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "a", value: 1 },
+///     RowData { name: "b", value: 20 },
+/// ];
+/// let mut out = Vec::new();
+/// write_jsonl(&mut out, rows)?;
+/// assert_eq!(String::from_utf8(out).unwrap(), r#"{"name":"a","value":"1"}
+/// {"name":"b","value":"20"}
+/// "#);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn write_jsonl(
+    writer: impl io::Write,
+    rows: impl IntoIterator<Item = impl Cells>,
+) -> io::Result<()> {
+    write_jsonl_with_schema(writer, rows, DefaultCellsSchema::default())
+}
+
+/// Write JSON Lines using the columns defined by [`CellsSchema`] to `writer`.
+///
+/// See [`write_jsonl`] for details.
+pub fn write_jsonl_with_schema<T>(
+    mut writer: impl io::Write,
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> io::Result<()> {
+    let mut w = JsonRowWriter::new(&schema);
+    let mut s = String::new();
+    for row in rows {
+        s.clear();
+        w.write_object(&mut s, &schema, row.borrow());
+        s.push('\n');
+        writer.write_all(s.as_bytes())?;
+    }
+    writer.flush()
+}
+
+struct JsonRowWriter {
+    headers: StringRecord,
+    body: CsvBodyWriter,
+}
+
+impl JsonRowWriter {
+    fn new<T: ?Sized>(schema: &impl CellsSchema<Source = T>) -> Self {
+        Self {
+            headers: header_record(schema),
+            body: CsvBodyWriter::new(),
+        }
+    }
+
+    fn write_object<T: ?Sized>(
+        &mut self,
+        s: &mut String,
+        schema: &impl CellsSchema<Source = T>,
+        item: &T,
+    ) {
+        s.push('{');
+        let record = self.body.record(schema, item);
+        for (i, value) in record.iter().enumerate() {
+            if i != 0 {
+                s.push(',');
+            }
+            let key = self.headers.get(i).unwrap_or("");
+            if key.is_empty() || key.ends_with('.') {
+                write_json_str(s, &format!("{key}{i}"));
+            } else {
+                write_json_str(s, key);
+            }
+            s.push(':');
+            write_json_str(s, value);
+        }
+        s.push('}');
+    }
+}
+
+fn write_json_str(s: &mut String, value: &str) {
+    s.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            ch if ch < ' ' => write!(s, "\\u{:04x}", ch as u32).unwrap(),
+            ch => s.push(ch),
+        }
+    }
+    s.push('"');
+}
@@ -0,0 +1,191 @@
+use std::borrow::Borrow;
+
+use crate::{
+    ansi::strip_escapes, grid_builder::RowKind, Cells, CellsSchema, DefaultCellsSchema,
+    GridBuilder, HorizontalAlignment,
+};
+
+/// Generate a LaTeX `tabular` environment using the columns defined by [`Cells`](crate::Cells).
+///
+/// See [`GridBuilder::to_latex`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "a_1", value: 300 },
+///     RowData { name: "b", value: 2 },
+/// ];
+/// let s = to_latex(rows);
+/// assert_eq!(s, r#"\begin{tabular}{lr}
+/// \toprule
+/// name & value \\
+/// \midrule
+/// a\_1 & 300 \\
+/// b & 2 \\
+/// \bottomrule
+/// \end{tabular}
+/// "#);
+/// ```
+pub fn to_latex(rows: impl IntoIterator<Item = impl Cells>) -> String {
+    to_latex_with_schema(rows, DefaultCellsSchema::default())
+}
+
+/// Generate a LaTeX `tabular` environment using the columns defined by [`CellsSchema`].
+///
+/// See [`GridBuilder::to_latex`] for details.
+pub fn to_latex_with_schema<T>(
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> String {
+    GridBuilder::from_iter_with_schema(rows, schema).to_latex()
+}
+
+impl GridBuilder {
+    /// Render the grid as a LaTeX `tabular` environment with the rules of the `booktabs` package.
+    ///
+    /// Each column separated by vertical rules in the plain-text table becomes a column of the LaTeX table,
+    /// aligned in the same way as [`to_markdown`](Self::to_markdown).
+    /// Header cells spanning multiple columns are written with `\multicolumn` and underlined with `\cmidrule`.
+    /// Footer rows are separated from the body rows with `\midrule`.
+    /// Special characters of LaTeX in the cells are escaped, and ANSI escape sequences are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push_with_colspan("size", 2);
+    /// });
+    /// g.push_header(|b| {
+    ///     b.push("");
+    ///     b.push("w");
+    ///     b.push("h");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(1);
+    ///     b.push(20);
+    /// });
+    /// assert_eq!(g.to_latex(), r#"\begin{tabular}{lrr}
+    /// \toprule
+    /// name & \multicolumn{2}{c}{size} \\
+    /// \cmidrule(lr){2-3}
+    ///  & w & h \\
+    /// \midrule
+    /// a & 1 & 20 \\
+    /// \bottomrule
+    /// \end{tabular}
+    /// "#);
+    /// ```
+    pub fn to_latex(&self) -> String {
+        if let Some(g) = self.resolved() {
+            return g.to_latex();
+        }
+        let groups = self.column_groups();
+        if groups.is_empty() {
+            return String::new();
+        }
+        let aligns: Vec<char> = groups
+            .iter()
+            .map(|g| align_char(self.body_align(g.clone())))
+            .collect();
+        let mut s = String::from("\\begin{tabular}{");
+        s.extend(&aligns);
+        s.push_str("}\n\\toprule\n");
+        let mut prev: Option<RowKind> = None;
+        for (kind, cells) in self.row_cells() {
+            if prev.is_some_and(|prev| prev != kind) {
+                s.push_str("\\midrule\n");
+            }
+            prev = Some(kind);
+            let mut entries: Vec<(usize, usize, String)> = Vec::new();
+            for (columns, text) in cells {
+                let Some(first) = groups.iter().position(|g| g.contains(&columns.start)) else {
+                    continue;
+                };
+                let last = groups.iter().rposition(|g| g.start < columns.end).unwrap();
+                match entries.last_mut() {
+                    Some(e) if e.0 == first => e.2.push_str(&escape_latex(text)),
+                    _ => entries.push((first, last - first + 1, escape_latex(text))),
+                }
+            }
+            let mut column = 0;
+            let mut rules = Vec::new();
+            for (first, span, text) in entries {
+                while column < first {
+                    s.push_str(if column == 0 { "" } else { " & " });
+                    column += 1;
+                }
+                if column != 0 {
+                    s.push_str(" & ");
+                }
+                if span > 1 {
+                    let align = if kind == RowKind::Header {
+                        'c'
+                    } else {
+                        aligns[first]
+                    };
+                    s.push_str(&format!("\\multicolumn{{{span}}}{{{align}}}{{{text}}}"));
+                    if kind == RowKind::Header {
+                        rules.push(format!("\\cmidrule(lr){{{}-{}}}", first + 1, first + span));
+                    }
+                } else {
+                    s.push_str(&text);
+                }
+                column = first + span;
+            }
+            while column < groups.len() {
+                s.push_str(if column == 0 { "" } else { " & " });
+                column += 1;
+            }
+            s.push_str(" \\\\\n");
+            for rule in rules {
+                s.push_str(&rule);
+                s.push('\n');
+            }
+        }
+        s.push_str("\\bottomrule\n\\end{tabular}\n");
+        s
+    }
+}
+
+fn align_char(align: HorizontalAlignment) -> char {
+    match align {
+        HorizontalAlignment::Left => 'l',
+        HorizontalAlignment::Center => 'c',
+        HorizontalAlignment::Right => 'r',
+    }
+}
+
+fn escape_latex(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for ch in strip_escapes(s).chars() {
+        match ch {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                result.push('\\');
+                result.push(ch);
+            }
+            '\\' => result.push_str("\\textbackslash{}"),
+            '~' => result.push_str("\\textasciitilde{}"),
+            '^' => result.push_str("\\textasciicircum{}"),
+            '\n' => result.push(' '),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
@@ -2,18 +2,69 @@
 //!
 //! See [`to_grid`] (High Level API) or [`GridBuilder`] (Low Level API) for details.
 
+pub mod aggregator;
+mod ansi;
+#[cfg(feature = "arrow")]
+mod arrow_writer;
+#[cfg(feature = "tokio")]
+mod async_writer;
 mod cell;
 mod cells;
 mod cells_csv_writer;
 mod cells_formatter;
+mod columns_source;
+mod crosstab;
+mod diff;
+mod frequency;
 mod grid;
 mod grid_builder;
+mod grid_render_options;
+mod grid_renderer;
+mod grid_theme;
+mod header_resolver;
+mod hexdump;
+mod json;
+mod latex;
+mod markdown;
+mod matrix;
+mod numeric;
+mod shared_layout;
+mod stream_writer;
+mod table;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod text_grid;
 
+pub use self::aggregator::{AggregateFn, Aggregations, Aggregator};
+pub use self::ansi::{Color, Heatmap};
+#[cfg(feature = "arrow")]
+pub use self::arrow_writer::*;
+#[cfg(feature = "tokio")]
+pub use self::async_writer::*;
 pub use self::cell::*;
 pub use self::cells::*;
+pub use self::cells_csv_writer::{write_csv_records, CsvOptions, CsvQuoteStyle, CsvTerminator};
 pub use self::cells_formatter::*;
+pub use self::columns_source::*;
+pub use self::crosstab::*;
+pub use self::diff::DiffOptions;
+pub use self::frequency::*;
 pub use self::grid::*;
 pub use self::grid_builder::*;
+pub use self::grid_render_options::*;
+pub use self::grid_renderer::*;
+pub use self::grid_theme::*;
+pub use self::header_resolver::*;
+pub use self::hexdump::*;
+pub use self::json::*;
+pub use self::latex::*;
+pub use self::markdown::*;
+pub use self::matrix::*;
+pub use self::numeric::StrRowsSchema;
+pub use self::shared_layout::SharedLayout;
+pub use self::stream_writer::*;
+pub use self::table::*;
+pub use self::text_grid::*;
 
 #[cfg(doctest)]
 mod tests {
@@ -0,0 +1,410 @@
+use std::borrow::Borrow;
+
+use derive_ex::derive_ex;
+
+use crate::{
+    ansi::text_width, Cells, CellsSchema, ColumnStyle, DefaultCellsSchema, GridBuilder,
+    HorizontalAlignment,
+};
+
+/// Generate a Markdown table using the columns defined by [`Cells`](crate::Cells).
+///
+/// See [`GridBuilder::to_markdown`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "a", value: 300 },
+///     RowData { name: "bb", value: 2 },
+/// ];
+/// let s = to_markdown(rows);
+/// assert_eq!(format!("\n{s}"), r#"
+/// | name | value |
+/// |------|------:|
+/// | a    |   300 |
+/// | bb   |     2 |
+/// "#);
+/// ```
+pub fn to_markdown(rows: impl IntoIterator<Item = impl Cells>) -> String {
+    to_markdown_with_schema(rows, DefaultCellsSchema::default())
+}
+
+/// Generate a Markdown table using the columns defined by [`CellsSchema`].
+///
+/// See [`GridBuilder::to_markdown`] for details.
+pub fn to_markdown_with_schema<T>(
+    rows: impl IntoIterator<Item = impl Borrow<T>>,
+    schema: impl CellsSchema<Source = T>,
+) -> String {
+    GridBuilder::from_iter_with_schema(rows, schema).to_markdown()
+}
+
+/// Options used when rendering [`GridBuilder`] as a Markdown table.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let mut g = GridBuilder::new();
+/// g.push_header(|b| {
+///     b.push("name");
+///     b.push("value");
+/// });
+/// g.push(|b| {
+///     b.push("a");
+///     b.push(300);
+/// });
+/// let options = MarkdownOptions::new().align(1, HorizontalAlignment::Center);
+/// assert_eq!(format!("\n{}", g.to_markdown_with_options(&options)), r#"
+/// | name | value |
+/// |------|:-----:|
+/// | a    |  300  |
+/// "#);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive_ex(Default)]
+pub struct MarkdownOptions {
+    aligns: Vec<Option<HorizontalAlignment>>,
+    #[default(true)]
+    escape: bool,
+}
+
+impl MarkdownOptions {
+    /// Create a new `MarkdownOptions` with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// If true, characters that have special meaning in Markdown tables are escaped.
+    ///
+    /// `\`, `|` and `` ` `` are escaped with `\`, `#` at the start of a cell is escaped with `\`,
+    /// and line breaks are replaced with `<br>`.
+    ///
+    /// The default for this is `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| b.push("expr"));
+    /// g.push(|b| b.push("a|b"));
+    /// assert_eq!(format!("\n{}", g.to_markdown()), r#"
+    /// | expr |
+    /// |------|
+    /// | a\|b |
+    /// "#);
+    /// let options = MarkdownOptions::new().escape(false);
+    /// assert_eq!(format!("\n{}", g.to_markdown_with_options(&options)), r#"
+    /// | expr |
+    /// |------|
+    /// | a|b  |
+    /// "#);
+    /// ```
+    pub fn escape(mut self, value: bool) -> Self {
+        self.escape = value;
+        self
+    }
+
+    /// Set the alignment of the column at `column`.
+    ///
+    /// By default, the alignment of a column is the most common alignment of its body cells.
+    pub fn align(mut self, column: usize, value: HorizontalAlignment) -> Self {
+        if self.aligns.len() <= column {
+            self.aligns.resize(column + 1, None);
+        }
+        self.aligns[column] = Some(value);
+        self
+    }
+}
+
+impl GridBuilder {
+    /// Render the grid as a Markdown table.
+    ///
+    /// Each column separated by vertical rules in the plain-text table becomes a column of the Markdown table.
+    /// The header rows are combined into a single row by joining the headers of nested columns with `.`.
+    /// The alignment of each column is the most common alignment of its body cells,
+    /// so columns of numbers are right-aligned.
+    /// A cell spanning multiple columns is placed in the first column it spans.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push_header(|b| {
+    ///     b.push("name");
+    ///     b.push("value");
+    /// });
+    /// g.push(|b| {
+    ///     b.push("a");
+    ///     b.push(300);
+    /// });
+    /// g.push(|b| {
+    ///     b.push("bb");
+    ///     b.push(2);
+    /// });
+    /// assert_eq!(format!("\n{}", g.to_markdown()), r#"
+    /// | name | value |
+    /// |------|------:|
+    /// | a    |   300 |
+    /// | bb   |     2 |
+    /// "#);
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_with_options(&MarkdownOptions::new())
+    }
+
+    /// Create a `GridBuilder` from the first Markdown pipe table in `s`.
+    ///
+    /// The table consists of a header row, a delimiter row, and the following body rows,
+    /// which end at the first blank line.
+    /// The alignment markers of the delimiter row are set to [`ColumnStyle::align_h`](crate::ColumnStyle::align_h),
+    /// and backslash escapes are removed from the cells.
+    ///
+    /// Returns `None` if `s` does not contain a table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::*;
+    /// let md = r#"
+    /// Some text.
+    ///
+    /// | name | value |
+    /// |:-----|------:|
+    /// | a    | 300 |
+    /// | bb | 2 |
+    /// "#;
+    /// let g = GridBuilder::from_markdown(md).unwrap();
+    /// assert_eq!(format!("\n{g}"), r#"
+    ///  name | value |
+    /// ------|-------|
+    ///  a    |   300 |
+    ///  bb   |     2 |
+    /// "#);
+    /// ```
+    pub fn from_markdown(s: &str) -> Option<Self> {
+        let lines: Vec<&str> = s.lines().collect();
+        let (i, aligns) = lines.windows(2).enumerate().find_map(|(i, w)| {
+            let aligns = parse_delimiter_row(w[1])?;
+            let header = split_markdown_row(w[0]);
+            (w[0].contains('|') && header.len() == aligns.len()).then_some((i, aligns))
+        })?;
+        let mut g = GridBuilder::new();
+        for (column, align) in aligns.iter().enumerate() {
+            if let Some(align) = *align {
+                g.column_styles.resize(column + 1, ColumnStyle::default());
+                g.column_styles[column].align_h = Some(align);
+            }
+        }
+        let header = split_markdown_row(lines[i]);
+        g.push_header(|b| {
+            for value in &header {
+                b.push(unescape_markdown(value));
+            }
+        });
+        for line in &lines[i + 2..] {
+            if line.trim().is_empty() {
+                break;
+            }
+            let values = split_markdown_row(line);
+            g.push(|b| {
+                for column in 0..aligns.len() {
+                    b.push(unescape_markdown(values.get(column).copied().unwrap_or("")));
+                }
+            });
+        }
+        Some(g)
+    }
+
+    /// Render the grid as a Markdown table with the specified options.
+    pub fn to_markdown_with_options(&self, options: &MarkdownOptions) -> String {
+        let groups = self.column_groups();
+        if groups.is_empty() {
+            return String::new();
+        }
+        let headers = self.matrix_of(true, true);
+        let mut body = self.group_texts(&groups);
+        let mut header: Vec<String> = groups
+            .iter()
+            .map(|g| {
+                let mut names: Vec<&str> = Vec::new();
+                for row in &headers {
+                    let name = row[g.start].as_str();
+                    if !name.is_empty() && names.last() != Some(&name) {
+                        names.push(name);
+                    }
+                }
+                names.join(".")
+            })
+            .collect();
+        if options.escape {
+            for value in header.iter_mut().chain(body.iter_mut().flatten()) {
+                *value = escape_markdown(value);
+            }
+        }
+        let aligns: Vec<HorizontalAlignment> = groups
+            .iter()
+            .enumerate()
+            .map(|(i, g)| {
+                options
+                    .aligns
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .unwrap_or_else(|| self.body_align(g.clone()))
+            })
+            .collect();
+        let widths: Vec<usize> = (0..groups.len())
+            .map(|i| {
+                body.iter()
+                    .map(|row| text_width(&row[i]))
+                    .chain([text_width(&header[i]), 3])
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+
+        let mut s = String::new();
+        write_markdown_row(&mut s, &header, &widths, &aligns);
+        s.push('|');
+        for (&width, &align) in widths.iter().zip(&aligns) {
+            let (l, r) = match align {
+                HorizontalAlignment::Left => ("-", "-"),
+                HorizontalAlignment::Center => (":", ":"),
+                HorizontalAlignment::Right => ("-", ":"),
+            };
+            s.push_str(l);
+            s.push_str(&"-".repeat(width));
+            s.push_str(r);
+            s.push('|');
+        }
+        s.push('\n');
+        for row in &body {
+            write_markdown_row(&mut s, row, &widths, &aligns);
+        }
+        s
+    }
+}
+
+fn escape_markdown(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    if s.starts_with('#') {
+        result.push('\\');
+    }
+    for c in s.chars() {
+        match c {
+            '\\' | '|' | '`' => {
+                result.push('\\');
+                result.push(c);
+            }
+            '\n' => result.push_str("<br>"),
+            '\r' => {}
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn unescape_markdown(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some(c) if c.is_ascii_punctuation() => result.push(c),
+                Some(c) => {
+                    result.push('\\');
+                    result.push(c);
+                }
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Split a line of a Markdown table into cells.
+fn split_markdown_row(line: &str) -> Vec<&str> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '|' if !escaped => {
+                cells.push(line[start..i].trim());
+                start = i + 1;
+            }
+            _ => escaped = false,
+        }
+    }
+    if start != line.len() {
+        cells.push(line[start..].trim());
+    }
+    cells
+}
+
+fn parse_delimiter_row(line: &str) -> Option<Vec<Option<HorizontalAlignment>>> {
+    if !line.contains('|') {
+        return None;
+    }
+    split_markdown_row(line)
+        .into_iter()
+        .map(|cell| {
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => Some(HorizontalAlignment::Center),
+                (false, true) => Some(HorizontalAlignment::Right),
+                (true, false) => Some(HorizontalAlignment::Left),
+                (false, false) => None,
+            })
+        })
+        .collect()
+}
+
+fn write_markdown_row(
+    s: &mut String,
+    row: &[String],
+    widths: &[usize],
+    aligns: &[HorizontalAlignment],
+) {
+    s.push('|');
+    for ((value, &width), &align) in row.iter().zip(widths).zip(aligns) {
+        let p = width - text_width(value);
+        let (lp, rp) = match align {
+            HorizontalAlignment::Left => (0, p),
+            HorizontalAlignment::Center => (p / 2, p - p / 2),
+            HorizontalAlignment::Right => (p, 0),
+        };
+        s.push(' ');
+        s.push_str(&" ".repeat(lp));
+        s.push_str(value);
+        s.push_str(&" ".repeat(rp));
+        s.push_str(" |");
+    }
+    s.push('\n');
+}
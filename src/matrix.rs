@@ -0,0 +1,118 @@
+use std::fmt::Display;
+
+use crate::{cell, Cell, GridBuilder};
+
+/// Generate a table of a two-dimensional matrix.
+///
+/// All cells are right-aligned, and rows shorter than the others are padded with empty cells.
+/// See [`MatrixOptions`] for the index headers.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let m: [[f64; 3]; 2] = [[1.0, -0.5, 0.25], [10.0, 2.0, -3.0]];
+/// let g = to_matrix_grid(m, &MatrixOptions::new().row_index(true).column_index(true));
+/// assert_eq!(format!("\n{g}"), r#"
+///    |  0 |    1 |    2 |
+/// ---|----|------|------|
+///  0 |  1 | -0.5 | 0.25 |
+///  1 | 10 |    2 |   -3 |
+/// "#);
+/// ```
+pub fn to_matrix_grid(
+    rows: impl IntoIterator<Item = impl IntoIterator<Item = impl Display>>,
+    options: &MatrixOptions,
+) -> String {
+    options.to_grid_builder(rows).to_string()
+}
+
+/// Generate a table of [`ndarray::Array2`] or its views.
+///
+/// See [`to_matrix_grid`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let a = ndarray::array![[1, 2], [30, 4]];
+/// let g = to_array2_grid(&a, &MatrixOptions::new());
+/// assert_eq!(format!("\n{g}"), r#"
+///   1 | 2 |
+///  30 | 4 |
+/// "#);
+/// ```
+#[cfg(feature = "ndarray")]
+pub fn to_array2_grid<S, T>(
+    a: &ndarray::ArrayBase<S, ndarray::Ix2>,
+    options: &MatrixOptions,
+) -> String
+where
+    S: ndarray::Data<Elem = T>,
+    T: Display,
+{
+    to_matrix_grid(a.rows(), options)
+}
+
+/// Options used by [`to_matrix_grid`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct MatrixOptions {
+    row_index: bool,
+    column_index: bool,
+}
+
+impl MatrixOptions {
+    /// Create a new `MatrixOptions` with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// If true, display the index of each row in the first column.
+    ///
+    /// The default for this is `false`.
+    pub fn row_index(mut self, value: bool) -> Self {
+        self.row_index = value;
+        self
+    }
+
+    /// If true, display the index of each column in the header row.
+    ///
+    /// The default for this is `false`.
+    pub fn column_index(mut self, value: bool) -> Self {
+        self.column_index = value;
+        self
+    }
+
+    fn to_grid_builder(
+        &self,
+        rows: impl IntoIterator<Item = impl IntoIterator<Item = impl Display>>,
+    ) -> GridBuilder {
+        let rows: Vec<Vec<_>> = rows.into_iter().map(|r| r.into_iter().collect()).collect();
+        let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let mut g = GridBuilder::new();
+        if self.column_index {
+            g.push_header(|b| {
+                if self.row_index {
+                    b.push("");
+                }
+                for i in 0..columns {
+                    b.push(Cell::new(i).right());
+                }
+            });
+        }
+        for (i, row) in rows.iter().enumerate() {
+            g.push(|b| {
+                if self.row_index {
+                    b.push(Cell::new(i).right());
+                }
+                for value in row {
+                    b.push(cell(value).right());
+                }
+                for _ in row.len()..columns {
+                    b.push("");
+                }
+            });
+        }
+        g
+    }
+}
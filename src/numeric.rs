@@ -0,0 +1,128 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use crate::{cell, CellsFormatter, CellsSchema};
+
+/// Kind of number detected from string values.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum NumberKind {
+    Integer,
+    Decimal,
+}
+impl NumberKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+        if !digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+            return None;
+        }
+        if digits.parse::<u128>().is_ok() {
+            Some(NumberKind::Integer)
+        } else if digits.parse::<f64>().is_ok() {
+            Some(NumberKind::Decimal)
+        } else {
+            None
+        }
+    }
+
+    /// Detect the kind of number shared by all non-empty values.
+    ///
+    /// Returns `None` if any value is not a number or all values are empty.
+    pub fn detect<'a>(values: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let mut result = None;
+        for value in values {
+            if value.trim().is_empty() {
+                continue;
+            }
+            let kind = Self::parse(value)?;
+            result = Some(match (result, kind) {
+                (Some(NumberKind::Decimal), _) | (_, NumberKind::Decimal) => NumberKind::Decimal,
+                _ => NumberKind::Integer,
+            });
+        }
+        result
+    }
+}
+
+/// [`CellsSchema`] for rows of string values, such as string matrices or records read from CSV.
+///
+/// Each row is a `Vec` of strings.
+///
+/// By default, all columns are left-aligned.
+/// Use [`auto_align`](Self::auto_align) to right-align columns that contain numbers.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let rows = vec![
+///     vec!["apple", "3", "1.5"],
+///     vec!["orange", "12", "10.25"],
+///     vec!["banana", "", "0.125"],
+/// ];
+/// let schema = StrRowsSchema::new(["name", "count", "price"]).auto_align(&rows);
+/// let g = to_grid_with_schema(&rows, schema);
+/// assert_eq!(format!("\n{g}"), r#"
+///   name  | count | price  |
+/// --------|-------|--------|
+///  apple  |     3 |  1.5   |
+///  orange |    12 | 10.25  |
+///  banana |       |  0.125 |
+/// "#);
+/// ```
+pub struct StrRowsSchema<S> {
+    headers: Vec<String>,
+    kinds: Vec<Option<NumberKind>>,
+    _phantom: PhantomData<fn(&S)>,
+}
+
+impl<S: AsRef<str>> StrRowsSchema<S> {
+    /// Create a new `StrRowsSchema` with the specified headers.
+    ///
+    /// The number of columns is the number of headers.
+    pub fn new(headers: impl IntoIterator<Item = impl Display>) -> Self {
+        let headers: Vec<_> = headers.into_iter().map(|h| h.to_string()).collect();
+        Self {
+            kinds: vec![None; headers.len()],
+            headers,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sample the values of each column and align columns that contain only numbers.
+    ///
+    /// Integer columns are right-aligned and decimal columns are aligned at the decimal point.
+    /// Empty values are ignored.
+    ///
+    /// To sample only a part of large data, pass the part such as `rows.iter().take(100)`.
+    pub fn auto_align<R: AsRef<[S]>>(mut self, rows: impl IntoIterator<Item = R>) -> Self {
+        let rows: Vec<R> = rows.into_iter().collect();
+        for (i, kind) in self.kinds.iter_mut().enumerate() {
+            *kind = NumberKind::detect(
+                rows.iter()
+                    .filter_map(|r| r.as_ref().get(i))
+                    .map(|s| s.as_ref()),
+            );
+        }
+        self
+    }
+}
+
+impl<S: AsRef<str>> CellsSchema for StrRowsSchema<S> {
+    type Source = Vec<S>;
+    fn fmt(&self, f: &mut CellsFormatter<Vec<S>>) {
+        for (i, header) in self.headers.iter().enumerate() {
+            match self.kinds[i] {
+                None => f.column(header, |x| cell(value_at(x, i))),
+                Some(NumberKind::Integer) => f.column(header, |x| cell(value_at(x, i)).right()),
+                Some(NumberKind::Decimal) => {
+                    f.column(header, |x| cell(value_at(x, i)).baseline("."))
+                }
+            }
+        }
+    }
+}
+
+fn value_at<S: AsRef<str>>(values: &[S], index: usize) -> &str {
+    values.get(index).map(|s| s.as_ref()).unwrap_or("")
+}
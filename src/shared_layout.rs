@@ -0,0 +1,59 @@
+use crate::GridBuilder;
+
+/// Column widths shared by multiple [`GridBuilder`]s.
+///
+/// Add all grids with [`push`](Self::push), then display each grid with
+/// [`GridBuilder::display_with_layout`] so that their columns line up vertically.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// let mut g0 = GridBuilder::new();
+/// g0.push(|b| {
+///     b.push("a");
+///     b.push(100);
+/// });
+/// let mut g1 = GridBuilder::new();
+/// g1.push(|b| {
+///     b.push("bbb");
+///     b.push(1);
+/// });
+///
+/// let mut layout = SharedLayout::new();
+/// layout.push(&g0);
+/// layout.push(&g1);
+///
+/// let s = format!("{}{}", g0.display_with_layout(&layout), g1.display_with_layout(&layout));
+/// assert_eq!(format!("\n{s}"), r#"
+///  a   | 100 |
+///  bbb |   1 |
+/// "#);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SharedLayout {
+    widths: Vec<usize>,
+}
+
+impl SharedLayout {
+    /// Create a new empty `SharedLayout`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Widen the columns to fit the specified grid.
+    pub fn push(&mut self, g: &GridBuilder) {
+        let widths = g.get_widths();
+        if self.widths.len() < widths.len() {
+            self.widths.resize(widths.len(), 0);
+        }
+        for (w, width) in self.widths.iter_mut().zip(widths) {
+            *w = (*w).max(width);
+        }
+    }
+
+    /// Returns the widths of the columns, excluding the padding.
+    pub fn widths(&self) -> &[usize] {
+        &self.widths
+    }
+}
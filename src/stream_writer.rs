@@ -0,0 +1,129 @@
+use std::{borrow::Borrow, io::Write};
+
+use crate::{Cells, CellsSchema, DefaultCellsSchema, GridBuilder, SharedLayout};
+
+/// How to display cells wider than the column widths estimated from the sample rows.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum WidthOverflow {
+    /// Widen the cell, shifting the following columns of the row to the right.
+    #[default]
+    Overflow,
+    /// Truncate the cell with `…` to fit the column.
+    Truncate,
+}
+
+/// Options used when writing a table while the rows are still being produced.
+///
+/// The column widths are computed from the first rows only,
+/// so that output can start without waiting for the whole source.
+/// Each row after the sample is written as soon as it is produced.
+///
+/// Footer rows are not written, since they require all rows.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+/// struct RowData {
+///     name: &'static str,
+///     value: u32,
+/// }
+/// impl Cells for RowData {
+///     fn fmt(f: &mut CellsFormatter<Self>) {
+///         f.column("name", |s| s.name);
+///         f.column("value", |s| s.value);
+///     }
+/// }
+///
+/// let rows = [
+///     RowData { name: "a", value: 1 },
+///     RowData { name: "bb", value: 20 },
+///     RowData { name: "cccccccc", value: 300 },
+/// ];
+/// let mut out = Vec::new();
+/// StreamOptions::new()
+///     .sample_rows(2)
+///     .overflow(WidthOverflow::Truncate)
+///     .write_grid(&mut out, rows)?;
+/// assert_eq!(format!("\n{}", String::from_utf8(out).unwrap()), r#"
+///  name | value |
+/// ------|-------|
+///  a    |     1 |
+///  bb   |    20 |
+///  ccc… |   300 |
+/// "#);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StreamOptions {
+    sample_rows: usize,
+    overflow: WidthOverflow,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamOptions {
+    /// Create a new `StreamOptions` with default settings.
+    pub fn new() -> Self {
+        Self {
+            sample_rows: 100,
+            overflow: WidthOverflow::Overflow,
+        }
+    }
+
+    /// Set the number of rows used to compute the column widths.
+    ///
+    /// The default for this is `100`.
+    pub fn sample_rows(mut self, n: usize) -> Self {
+        self.sample_rows = n;
+        self
+    }
+
+    /// Set how to display the cells wider than the computed column widths.
+    ///
+    /// The default for this is [`WidthOverflow::Overflow`].
+    pub fn overflow(mut self, value: WidthOverflow) -> Self {
+        self.overflow = value;
+        self
+    }
+
+    /// Write a table using the columns defined by [`Cells`] to `writer`.
+    pub fn write_grid(
+        &self,
+        writer: impl Write,
+        rows: impl IntoIterator<Item = impl Cells>,
+    ) -> std::io::Result<()> {
+        self.write_grid_with_schema(writer, rows, DefaultCellsSchema::default())
+    }
+
+    /// Write a table using the columns defined by [`CellsSchema`] to `writer`.
+    pub fn write_grid_with_schema<T>(
+        &self,
+        mut writer: impl Write,
+        rows: impl IntoIterator<Item = impl Borrow<T>>,
+        schema: impl CellsSchema<Source = T>,
+    ) -> std::io::Result<()> {
+        let mut rows = rows.into_iter();
+        let mut g = GridBuilder::new();
+        g.extend_header_with_schema(&schema);
+        g.extend_body_with_schema(rows.by_ref().take(self.sample_rows), &schema);
+        let mut layout = SharedLayout::new();
+        layout.push(&g);
+        write!(writer, "{}", g.display_with_layout(&layout))?;
+        for row in rows {
+            let mut r = GridBuilder::new();
+            r.column_styles = g.column_styles.clone();
+            r.options = g.options.clone();
+            r.extend_body_with_schema([row], &schema);
+            if self.overflow == WidthOverflow::Truncate {
+                r = r.truncated(layout.widths());
+            }
+            write!(writer, "{}", r.display_with_layout(&layout))?;
+        }
+        writer.flush()
+    }
+}
@@ -0,0 +1,209 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result};
+
+use crate::{schema_headers, Cells, CellsSchema, DefaultCellsSchema, GridBuilder};
+
+/// A table that owns rows, a schema and rendering settings.
+///
+/// `Table` is a high-level API on top of [`GridBuilder`].
+/// Filtering, sorting and limiting are applied to the rows when the table is displayed.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::*;
+///
+/// #[derive(Cells)]
+/// struct File {
+///     name: &'static str,
+///     size: u64,
+/// }
+///
+/// let files = [
+///     File { name: "a.txt", size: 120 },
+///     File { name: "b.txt", size: 8 },
+///     File { name: "c.txt", size: 4500 },
+///     File { name: ".hidden", size: 1 },
+/// ];
+/// let t = Table::new(files)
+///     .filter(|x| !x.name.starts_with('.'))
+///     .sort_by_desc("size")
+///     .limit(2)
+///     .with(GridRenderOptions::new().frame(true));
+/// assert_eq!(format!("\n{t}"), r#"
+/// +-------+------+
+/// | name  | size |
+/// +-------+------+
+/// | c.txt | 4500 |
+/// | a.txt |  120 |
+/// +-------+------+
+/// "#);
+/// ```
+pub struct Table<T, S = DefaultCellsSchema<T>> {
+    rows: Vec<T>,
+    schema: S,
+    #[allow(clippy::type_complexity)]
+    filters: Vec<Box<dyn Fn(&T) -> bool>>,
+    sort_keys: Vec<(String, bool)>,
+    limit: Option<usize>,
+    modifiers: Vec<Box<dyn TableModifier>>,
+}
+
+impl<T: Cells> Table<T> {
+    /// Create a new `Table` with the columns defined by [`Cells`].
+    pub fn new(rows: impl IntoIterator<Item = T>) -> Self {
+        Self::with_schema(rows, DefaultCellsSchema::default())
+    }
+}
+
+impl<T, S: CellsSchema<Source = T>> Table<T, S> {
+    /// Create a new `Table` with the columns defined by [`CellsSchema`].
+    pub fn with_schema(rows: impl IntoIterator<Item = T>, schema: S) -> Self {
+        Self {
+            rows: rows.into_iter().collect(),
+            schema,
+            filters: Vec::new(),
+            sort_keys: Vec::new(),
+            limit: None,
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Apply a [`TableModifier`], such as [`GridRenderOptions`](crate::GridRenderOptions), when the table is displayed.
+    ///
+    /// Modifiers are applied in the order they are added.
+    pub fn with(mut self, modifier: impl TableModifier + 'static) -> Self {
+        self.modifiers.push(Box::new(modifier));
+        self
+    }
+
+    /// Display only the rows for which `f` returns true.
+    ///
+    /// If called multiple times, only the rows that satisfy all of the conditions are displayed.
+    pub fn filter(mut self, f: impl Fn(&T) -> bool + 'static) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    /// Sort the rows in ascending order of the column whose header is `header`.
+    ///
+    /// The column is specified by its innermost header or by the path of headers joined with `.`.
+    /// Values are compared as numbers if both of them are numbers, otherwise as strings.
+    /// If called multiple times, the first call specifies the primary key.
+    /// Unknown headers are ignored.
+    pub fn sort_by(mut self, header: &str) -> Self {
+        self.sort_keys.push((header.to_string(), false));
+        self
+    }
+
+    /// Sort the rows in descending order of the column whose header is `header`.
+    ///
+    /// See [`sort_by`](Self::sort_by) for details.
+    pub fn sort_by_desc(mut self, header: &str) -> Self {
+        self.sort_keys.push((header.to_string(), true));
+        self
+    }
+
+    /// Display at most `n` rows.
+    ///
+    /// The limit is applied after filtering and sorting.
+    pub fn limit(mut self, n: usize) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    /// Returns the rows of the table, before filtering and sorting.
+    pub fn rows(&self) -> &[T] {
+        &self.rows
+    }
+
+    /// Create a [`GridBuilder`] with the rows to be displayed.
+    pub fn to_grid_builder(&self) -> GridBuilder {
+        let mut rows: Vec<&T> = self
+            .rows
+            .iter()
+            .filter(|x| self.filters.iter().all(|f| f(x)))
+            .collect();
+        self.sort(&mut rows);
+        if let Some(limit) = self.limit {
+            rows.truncate(limit);
+        }
+        let mut g = GridBuilder::from_iter_with_schema(rows, &self.schema);
+        for m in &self.modifiers {
+            m.modify(&mut g);
+        }
+        g
+    }
+
+    fn sort(&self, rows: &mut Vec<&T>) {
+        if self.sort_keys.is_empty() {
+            return;
+        }
+        let headers = schema_headers(&self.schema);
+        let keys: Vec<(usize, bool)> = self
+            .sort_keys
+            .iter()
+            .filter_map(|(header, desc)| Some((headers.find_by_name(header)?, *desc)))
+            .collect();
+        let mut g = GridBuilder::new();
+        g.extend_body_with_schema(rows.iter().copied(), &self.schema);
+        let values = g.to_cells_matrix();
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        order.sort_by(|&a, &b| {
+            for &(column, desc) in &keys {
+                let o = compare_values(&values[a][column], &values[b][column]);
+                let o = if desc { o.reverse() } else { o };
+                if o != Ordering::Equal {
+                    return o;
+                }
+            }
+            Ordering::Equal
+        });
+        *rows = order.into_iter().map(|i| rows[i]).collect();
+    }
+}
+
+impl<T, S: CellsSchema<Source = T>> Display for Table<T, S> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        Display::fmt(&self.to_grid_builder(), f)
+    }
+}
+
+/// Compare the values of a column, ordering numbers before other values.
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Setting applied to the [`GridBuilder`] created by [`Table`].
+///
+/// This is implemented for [`GridRenderOptions`](crate::GridRenderOptions), which overrides the options of the grid that differ from the default,
+/// for [`GridTheme`](crate::GridTheme), which replaces the theme of the grid,
+/// and for closures that take `&mut GridBuilder`.
+pub trait TableModifier {
+    /// Apply the setting to `g`.
+    fn modify(&self, g: &mut GridBuilder);
+}
+
+/// Settings that differ from the default override the current settings, and the others are kept.
+impl TableModifier for crate::GridRenderOptions {
+    fn modify(&self, g: &mut GridBuilder) {
+        self.merge_into(&mut g.options);
+    }
+}
+
+impl TableModifier for crate::GridTheme {
+    fn modify(&self, g: &mut GridBuilder) {
+        g.set_theme(self.clone());
+    }
+}
+
+impl<F: Fn(&mut GridBuilder)> TableModifier for F {
+    fn modify(&self, g: &mut GridBuilder) {
+        self(g)
+    }
+}
@@ -0,0 +1,128 @@
+//! Helpers for testing the output of grids, enabled by the `testing` feature.
+
+use std::collections::HashMap;
+
+use crate::GridBuilder;
+
+/// Replace volatile values in a grid so that the output can be used in snapshot tests.
+///
+/// Use [`scrub`](Self::scrub) to get a grid with the values replaced.
+/// Header cells are not changed.
+///
+/// # Examples
+///
+/// ```
+/// use text_grid::testing::SnapshotScrubber;
+/// use text_grid::*;
+/// let mut g = GridBuilder::new();
+/// g.push_header(|b| {
+///     b.push("name");
+///     b.push("duration");
+///     b.push("id");
+/// });
+/// g.push(|b| {
+///     b.push("build");
+///     b.push("1.52s");
+///     b.push("job-8a3f");
+/// });
+///
+/// let s = SnapshotScrubber::new()
+///     .mask_column("duration", "<dur>")
+///     .replace_with(|s| s.strip_prefix("job-").map(|_| "job-<id>".to_string()));
+/// assert_eq!(format!("\n{}", s.scrub(&g)), r#"
+///  name  | duration |    id    |
+/// -------|----------|----------|
+///  build | <dur>    | job-<id> |
+/// "#);
+/// ```
+#[derive(Default)]
+pub struct SnapshotScrubber {
+    masks: HashMap<String, String>,
+    #[allow(clippy::type_complexity)]
+    replacers: Vec<Box<dyn Fn(&str) -> Option<String>>>,
+}
+
+impl SnapshotScrubber {
+    /// Create a new `SnapshotScrubber` that does not replace any values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the non-empty body cells of the columns whose header is `header` with `replacement`.
+    ///
+    /// If a header cell spans multiple columns, all of the columns are masked.
+    pub fn mask_column(mut self, header: &str, replacement: &str) -> Self {
+        self.masks
+            .insert(header.to_string(), replacement.to_string());
+        self
+    }
+
+    /// Replace the text of body cells with the result of `f`.
+    ///
+    /// If `f` returns `None`, the text is kept.
+    /// Replacements are applied in the order they are added.
+    pub fn replace_with(mut self, f: impl Fn(&str) -> Option<String> + 'static) -> Self {
+        self.replacers.push(Box::new(f));
+        self
+    }
+
+    /// Replace all matches of the regular expression `pattern` in body cells with `replacement`.
+    ///
+    /// `replacement` can refer to capture groups such as `$1`. See [`regex::Regex::replace_all`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regular expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use text_grid::testing::SnapshotScrubber;
+    /// use text_grid::*;
+    /// let mut g = GridBuilder::new();
+    /// g.push(|b| {
+    ///     b.push("started at 2024-05-01 12:34:56");
+    /// });
+    /// let s = SnapshotScrubber::new()
+    ///     .replace_regex(r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}", "<time>");
+    /// assert_eq!(format!("\n{}", s.scrub(&g)), r#"
+    ///  started at <time> |
+    /// "#);
+    /// ```
+    pub fn replace_regex(self, pattern: &str, replacement: &str) -> Self {
+        let re = regex::Regex::new(pattern).unwrap();
+        let replacement = replacement.to_string();
+        self.replace_with(move |s| match re.replace_all(s, &replacement) {
+            std::borrow::Cow::Borrowed(_) => None,
+            std::borrow::Cow::Owned(s) => Some(s),
+        })
+    }
+
+    /// Returns a grid in which the volatile values of `g` are replaced.
+    pub fn scrub(&self, g: &GridBuilder) -> GridBuilder {
+        let masks: Vec<_> = g
+            .header_cells()
+            .filter_map(|(columns, s)| Some((columns, self.masks.get(s)?.clone())))
+            .collect();
+        g.map_cells(|is_header, columns, s| {
+            if is_header {
+                return None;
+            }
+            if !s.is_empty() {
+                for (mask_columns, replacement) in &masks {
+                    if mask_columns.start <= columns.start && columns.end <= mask_columns.end {
+                        return Some(replacement.clone());
+                    }
+                }
+            }
+            let mut result = None;
+            for replacer in &self.replacers {
+                let s = result.as_deref().unwrap_or(s);
+                if let Some(s) = replacer(s) {
+                    result = Some(s);
+                }
+            }
+            result
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{cell, CellStyle, GridBuilder, HorizontalAlignment, RowBuilder};
+use std::fmt::*;
+
+/// A simple builder used to create plain-text table without defining [`Cells`](crate::Cells).
+///
+/// # Examples
+/// ```rust
+/// use text_grid::*;
+/// use text_grid::HorizontalAlignment::*;
+/// let mut g = TextGrid::new();
+/// g.push_row().push("name", Right).push("value", Left);
+/// g.push_separator();
+/// g.push_row().push("X", Right).push(10, Left);
+/// g.push_row().push("YY", Right).push(200, Left);
+/// assert_eq!(format!("\n{g}"), r#"
+///  name | value |
+/// ------|-------|
+///     X | 10    |
+///    YY | 200   |
+/// "#);
+/// ```
+#[derive(Default)]
+pub struct TextGrid {
+    b: GridBuilder,
+}
+
+impl TextGrid {
+    /// Create a new `TextGrid`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a row to the bottom of the grid.
+    ///
+    /// The row is added when the returned [`TextRow`] is dropped.
+    pub fn push_row(&mut self) -> TextRow<'_> {
+        TextRow(self.b.push_row())
+    }
+
+    /// Append a row separator to the bottom of the grid.
+    pub fn push_separator(&mut self) {
+        self.b.push_separator();
+    }
+
+    /// Returns the [`GridBuilder`] used to create this grid.
+    pub fn into_builder(self) -> GridBuilder {
+        self.b
+    }
+}
+impl Display for TextGrid {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        Display::fmt(&self.b, f)
+    }
+}
+impl Debug for TextGrid {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        Debug::fmt(&self.b, f)
+    }
+}
+
+/// A row of [`TextGrid`].
+///
+/// This structure is created by [`TextGrid::push_row`].
+pub struct TextRow<'a>(RowBuilder<'a>);
+
+impl TextRow<'_> {
+    /// Append a cell to the right of row.
+    pub fn push(&mut self, value: impl Display, align: HorizontalAlignment) -> &mut Self {
+        self.push_with_colspan(value, align, 1)
+    }
+
+    /// Append a multi-column cell to the right of row.
+    pub fn push_with_colspan(
+        &mut self,
+        value: impl Display,
+        align: HorizontalAlignment,
+        colspan: usize,
+    ) -> &mut Self {
+        let style = CellStyle::new().align_h(align);
+        self.0
+            .push_with_colspan(cell(value).with_base_style(style), colspan);
+        self
+    }
+}
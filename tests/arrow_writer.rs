@@ -0,0 +1,115 @@
+#![cfg(feature = "arrow")]
+use arrow_array::{Array, Float64Array, Int64Array, StringArray, UInt64Array};
+use arrow_schema::DataType;
+use text_grid::*;
+
+struct X {
+    name: &'static str,
+    count: Option<i32>,
+    price: f64,
+    y: [u32; 2],
+}
+impl Cells for X {
+    fn fmt(f: &mut CellsFormatter<Self>) {
+        f.column("name", |x| x.name);
+        f.column("count", |x| x.count);
+        f.column("price", |x| x.price);
+        f.column("y", |x| x.y);
+    }
+}
+
+fn rows() -> Vec<X> {
+    vec![
+        X {
+            name: "a",
+            count: Some(-3),
+            price: 1.5,
+            y: [1, 2],
+        },
+        X {
+            name: "12x",
+            count: None,
+            price: 2.0,
+            y: [3, 4],
+        },
+    ]
+}
+
+#[test]
+fn record_batch_types() {
+    let batch = to_record_batch(rows()).unwrap();
+    let schema = batch.schema();
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|f| (f.name().as_str(), f.data_type().clone()))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("name", DataType::Utf8),
+            ("count", DataType::Utf8),
+            ("price", DataType::Utf8),
+            ("y.0", DataType::Utf8),
+            ("y.1", DataType::Utf8),
+        ]
+    );
+    let name = batch.column(0).as_any().downcast_ref::<StringArray>();
+    assert_eq!(name.unwrap().value(1), "12x");
+}
+
+#[test]
+fn record_batch_column_types() {
+    let batch = ArrowOptions::new()
+        .column_type("count", DataType::Int64)
+        .column_type("price", DataType::Float64)
+        .column_type("y.1", DataType::UInt64)
+        .to_record_batch(rows())
+        .unwrap();
+    let count = batch.column(1).as_any().downcast_ref::<Int64Array>();
+    let count = count.unwrap();
+    assert_eq!(count.value(0), -3);
+    assert!(count.is_null(1));
+    let price = batch.column(2).as_any().downcast_ref::<Float64Array>();
+    assert_eq!(price.unwrap().values(), &[1.5, 2.0]);
+    assert_eq!(batch.schema().field(3).data_type(), &DataType::Utf8);
+    let y1 = batch.column(4).as_any().downcast_ref::<UInt64Array>();
+    assert_eq!(y1.unwrap().values(), &[2, 4]);
+}
+
+#[test]
+fn record_batch_column_type_parse_error() {
+    let e = ArrowOptions::new()
+        .column_type("name", DataType::Int64)
+        .to_record_batch(rows());
+    assert!(matches!(e, Err(arrow_schema::ArrowError::ParseError(_))));
+}
+
+#[test]
+fn record_batch_no_columns() {
+    struct Empty;
+    impl Cells for Empty {
+        fn fmt(_: &mut CellsFormatter<Self>) {}
+    }
+    let batch = to_record_batch([Empty, Empty]).unwrap();
+    assert_eq!(batch.num_columns(), 0);
+    assert_eq!(batch.num_rows(), 2);
+}
+
+#[test]
+fn arrow_ipc_round_trip() {
+    let mut out = Vec::new();
+    write_arrow_ipc(&mut out, rows()).unwrap();
+    let reader = arrow_ipc::reader::FileReader::try_new(std::io::Cursor::new(out), None).unwrap();
+    let batches: Vec<_> = reader.map(|b| b.unwrap()).collect();
+    assert_eq!(batches, [to_record_batch(rows()).unwrap()]);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_magic() {
+    let mut out = Vec::new();
+    write_parquet(&mut out, rows()).unwrap();
+    assert!(out.starts_with(b"PAR1"));
+    assert!(out.ends_with(b"PAR1"));
+}
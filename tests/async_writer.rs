@@ -0,0 +1,50 @@
+#![cfg(feature = "tokio")]
+use text_grid::*;
+
+struct X {
+    a: u32,
+    b: &'static str,
+}
+impl Cells for X {
+    fn fmt(f: &mut CellsFormatter<Self>) {
+        f.column("a", |x| x.a);
+        f.column("b", |x| x.b);
+    }
+}
+
+fn rows(n: u32) -> Vec<X> {
+    (0..n).map(|a| X { a, b: "=x" }).collect()
+}
+
+#[tokio::test]
+async fn write_grid_async_many_rows() {
+    let rows = rows(200);
+    let mut out = Vec::new();
+    write_grid_async(&mut out, &rows).await.unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), to_grid(&rows));
+}
+
+#[tokio::test]
+async fn write_csv_async_many_rows() {
+    let rows = rows(200);
+    let mut out = Vec::new();
+    write_csv_async(&mut out, &rows).await.unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), to_csv(&rows));
+}
+
+#[tokio::test]
+async fn write_csv_async_with_options() {
+    let options = CsvOptions::new()
+        .escape_formulas(true)
+        .exclude_columns(["a"]);
+    let mut out = Vec::new();
+    options.write_csv_async(&mut out, &rows(2)).await.unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "b\n'=x\n'=x\n");
+}
+
+#[test]
+fn write_async_future_is_send() {
+    fn assert_send(_: impl Send) {}
+    assert_send(write_grid_async(tokio::io::sink(), rows(1)));
+    assert_send(write_csv_async(tokio::io::sink(), rows(1)));
+}
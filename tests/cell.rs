@@ -2,6 +2,7 @@ use text_grid::{Cell, CellStyle, Cells, CellsFormatter, HorizontalAlignment, Raw
 
 #[test]
 fn impl_cell() {
+    #[allow(dead_code)]
     struct X(String);
 
     impl RawCell for X {
@@ -18,3 +19,41 @@ fn impl_cell() {
         }
     }
 }
+
+#[test]
+fn try_cell_error_is_reported_by_try_to_string() {
+    use text_grid::{cell, to_grid, try_cell, GridBuilder};
+
+    struct Fail;
+    impl std::fmt::Display for Fail {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "pa")?;
+            Err(std::fmt::Error)
+        }
+    }
+    struct X(u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |_| cell!("{}", Fail).right());
+            f.column("b", |x| Some(try_cell!("{}{}", x.0, Fail)));
+        }
+    }
+    struct Y;
+    impl Cells for Y {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |_| cell!("{}", Fail).right());
+        }
+    }
+    assert_eq!(to_grid([Y]), " a  |\n----|\n pa |\n");
+    let g = GridBuilder::from_iter([X(1)]);
+    assert_eq!(
+        g.to_string(),
+        " a  |   b    |\n----|--------|\n pa | #ERROR |\n"
+    );
+    let e = g.try_to_string().unwrap_err();
+    assert_eq!((e.row(), e.column()), (1, 1));
+    assert_eq!(format!("{}", g.view(0..1, 0..2)), " a | b |\n---|---|\n");
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push(cell(Fail)));
+    assert_eq!(g.try_to_string().unwrap(), " pa |\n");
+}
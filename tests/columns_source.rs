@@ -0,0 +1,47 @@
+use text_grid::{to_grid_with_schema, ColumnsSource};
+
+#[test]
+fn columns_source_empty_columns() {
+    let names: [&str; 0] = [];
+    let lens: [u32; 0] = [];
+    let s = ColumnsSource::new()
+        .column("name", &names)
+        .column("len", &lens);
+    assert!(s.is_empty());
+    assert_eq!(
+        format!("\n{}", s.to_grid()),
+        r#"
+ name | len |
+------|-----|
+"#
+    );
+}
+
+#[test]
+fn columns_source_as_schema() {
+    let names = ["a", "bb"];
+    let lens = [1, 2];
+    let s = ColumnsSource::new()
+        .column("name", &names)
+        .column("len", &lens);
+    let g = to_grid_with_schema([1, 0], &s);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ name | len |
+------|-----|
+ bb   |   2 |
+ a    |   1 |
+"#
+    );
+}
+
+#[test]
+#[should_panic(expected = "column `len` has 1 values, but the other columns have 2 values")]
+fn columns_source_length_mismatch() {
+    let names = ["a", "b"];
+    let lens = [1];
+    let _ = ColumnsSource::new()
+        .column("name", &names)
+        .column("len", &lens);
+}
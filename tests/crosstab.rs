@@ -0,0 +1,62 @@
+use text_grid::to_crosstab;
+
+#[test]
+fn crosstab_sum() {
+    struct Sale {
+        region: &'static str,
+        month: u32,
+        amount: u32,
+    }
+    let sales = [
+        Sale {
+            region: "west",
+            month: 2,
+            amount: 30,
+        },
+        Sale {
+            region: "east",
+            month: 1,
+            amount: 10,
+        },
+        Sale {
+            region: "east",
+            month: 1,
+            amount: 5,
+        },
+        Sale {
+            region: "east",
+            month: 2,
+            amount: 100,
+        },
+    ];
+    let g = to_crosstab(
+        sales,
+        |s| s.region,
+        |s| s.month,
+        |rows| rows.iter().map(|s| s.amount).sum::<u32>(),
+    );
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+       | 1  |  2  | total |
+-------|----|-----|-------|
+ east  | 15 | 100 |   115 |
+ west  |    |  30 |    30 |
+-------|----|-----|-------|
+ total | 15 | 130 |   145 |
+"#
+    );
+}
+
+#[test]
+fn crosstab_empty() {
+    let g = to_crosstab(Vec::<(u8, u8)>::new(), |x| x.0, |x| x.1, |rows| rows.len());
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+       | total |
+-------|-------|
+ total |       |
+"#
+    );
+}
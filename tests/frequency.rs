@@ -0,0 +1,30 @@
+use text_grid::to_frequency_grid;
+
+#[test]
+fn frequency_string_buckets() {
+    let g = to_frequency_grid(["ok", "error", "ok", "ok"], |s| s.to_string());
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ bucket | count | percent |                      |
+--------|-------|---------|----------------------|
+ error  |     1 |   25.0% | #######              |
+ ok     |     3 |   75.0% | #################### |
+--------|-------|---------|----------------------|
+        |     4 |         |                      |
+"#
+    );
+}
+
+#[test]
+fn frequency_empty() {
+    let g = to_frequency_grid(Vec::<u32>::new(), |x| *x);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ bucket | count | percent |  |
+--------|-------|---------|--|
+        |       |         |  |
+"#
+    );
+}
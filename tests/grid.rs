@@ -750,6 +750,450 @@ fn derive_cells_for_record_struct() {
     );
 }
 
+#[test]
+fn derive_cells_skip_generic_field() {
+    struct NoCells;
+
+    #[derive(Cells)]
+    struct X<T> {
+        a: u32,
+        #[cells(skip)]
+        _b: T,
+        c: u32,
+    }
+
+    do_test(
+        vec![X {
+            a: 1,
+            _b: NoCells,
+            c: 2,
+        }],
+        r"
+ a | c |
+---|---|
+ 1 | 2 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_skip_enum_fields() {
+    #[derive(Cells)]
+    enum X {
+        A(#[cells(skip)] u32, u32),
+        B {
+            x: u32,
+            #[cells(skip)]
+            y: u32,
+        },
+        C {
+            y: u32,
+        },
+    }
+
+    do_test(
+        vec![X::A(1, 2), X::B { x: 3, y: 4 }, X::C { y: 5 }],
+        r"
+   | 1 | x | y |
+---|---|---|---|
+ A | 2 |   |   |
+ B |   | 3 |   |
+ C |   |   | 5 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_rename_all() {
+    #[derive(Cells)]
+    #[cells(rename_all = "kebab-case")]
+    struct X {
+        file_name: u32,
+        #[cells(header = "Size")]
+        file_size: u32,
+        r#type: u32,
+    }
+
+    do_test(
+        vec![X {
+            file_name: 1,
+            file_size: 2,
+            r#type: 3,
+        }],
+        r"
+ file-name | Size | type |
+-----------|------|------|
+         1 |    2 |    3 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_rename_all_lowercase() {
+    #[derive(Cells)]
+    #[cells(rename_all = "lowercase")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["user_id"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_uppercase() {
+    #[derive(Cells)]
+    #[cells(rename_all = "UPPERCASE")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["USER_ID"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_pascal_case() {
+    #[derive(Cells)]
+    #[cells(rename_all = "PascalCase")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["UserId"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_camel_case() {
+    #[derive(Cells)]
+    #[cells(rename_all = "camelCase")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["userId"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_snake_case() {
+    #[derive(Cells)]
+    #[cells(rename_all = "snake_case")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["user_id"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_screaming_snake_case() {
+    #[derive(Cells)]
+    #[cells(rename_all = "SCREAMING_SNAKE_CASE")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["USER_ID"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_kebab_case() {
+    #[derive(Cells)]
+    #[cells(rename_all = "kebab-case")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["user-id"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_screaming_kebab_case() {
+    #[derive(Cells)]
+    #[cells(rename_all = "SCREAMING-KEBAB-CASE")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["USER-ID"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_title_case() {
+    #[derive(Cells)]
+    #[cells(rename_all = "Title Case")]
+    struct X {
+        user_id: u32,
+    }
+    let g = GridBuilder::from_iter([X { user_id: 1 }]);
+    assert_eq!(g.to_header_matrix(), [["User Id"]]);
+}
+
+#[test]
+fn derive_cells_rename_all_enum_fields() {
+    #[derive(Cells)]
+    #[cells(rename_all = "PascalCase")]
+    enum X {
+        A { first_value: u32 },
+        B { first_value: u32, second_value: u32 },
+    }
+
+    do_test(
+        vec![
+            X::A { first_value: 1 },
+            X::B {
+                first_value: 2,
+                second_value: 3,
+            },
+        ],
+        r"
+   | FirstValue | SecondValue |
+---|------------|-------------|
+ A |          1 |             |
+ B |          2 |           3 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_format() {
+    #[derive(Cells)]
+    struct X<T: std::fmt::Display>(
+        #[cells(format = "[{}]")] T,
+        #[cells(format = "{:>5.1}")] f64,
+    );
+
+    do_test(
+        vec![X("a", 1.25), X("bb", 10.0)],
+        r"
+ [a]   1.2 |
+ [bb] 10.0 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_flatten() {
+    #[derive(Cells)]
+    struct Meta {
+        id: u32,
+        tag: &'static str,
+    }
+    #[derive(Cells)]
+    struct Data {
+        value: u32,
+    }
+    #[derive(Cells)]
+    struct Row<T> {
+        #[cells(flatten)]
+        meta: Meta,
+        data: Data,
+        #[cells(flatten)]
+        extra: T,
+    }
+
+    do_test(
+        vec![Row {
+            meta: Meta { id: 1, tag: "x" },
+            data: Data { value: 20 },
+            extra: Data { value: 300 },
+        }],
+        r"
+ id | tag | data  | value |
+----|-----|-------|-------|
+    |     | value |       |
+----|-----|-------|-------|
+  1 | x   |    20 |   300 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_group() {
+    #[derive(Cells)]
+    struct Size {
+        w: u32,
+        h: u32,
+    }
+    #[derive(Cells)]
+    struct X {
+        #[cells(group = "g")]
+        a: u32,
+        #[cells(group = "g", flatten)]
+        size: Size,
+        b: u32,
+        #[cells(group = "g")]
+        c: u32,
+    }
+
+    do_test(
+        vec![X {
+            a: 1,
+            size: Size { w: 2, h: 3 },
+            b: 4,
+            c: 5,
+        }],
+        r"
+       g       | b |
+---------------|---|
+ a | w | h | c |   |
+---|---|---|---|---|
+ 1 | 2 | 3 | 5 | 4 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_with() {
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+    #[derive(Debug)]
+    struct Tag;
+    fn point(p: &Point) -> (i32, i32) {
+        (p.x, p.y)
+    }
+    fn upper(s: &&str) -> String {
+        s.to_uppercase()
+    }
+    fn debug<T: std::fmt::Debug>(value: &T) -> String {
+        format!("{value:?}")
+    }
+
+    #[derive(Cells)]
+    struct X<T: std::fmt::Debug> {
+        #[cells(with = "upper")]
+        name: &'static str,
+        #[cells(with = "point")]
+        pos: Point,
+        #[cells(with = "point", flatten)]
+        pos2: Point,
+        #[cells(with = "debug")]
+        tag: T,
+    }
+
+    do_test(
+        vec![X {
+            name: "a",
+            pos: Point { x: 1, y: 20 },
+            pos2: Point { x: 3, y: 4 },
+            tag: Tag,
+        }],
+        r"
+ name | pos |    | tag |
+------|-----|----|-----|
+ A    | 120 | 34 | Tag |
+",
+    );
+}
+
+#[test]
+fn derive_cells_hide_variant() {
+    #[derive(Cells)]
+    #[cells(hide_variant)]
+    enum X {
+        A { x: u32 },
+        B { x: u32, y: u32 },
+    }
+
+    do_test(
+        vec![X::A { x: 1 }, X::B { x: 2, y: 3 }],
+        r"
+ x | y |
+---|---|
+ 1 |   |
+ 2 | 3 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_order_with_group() {
+    #[derive(Cells)]
+    struct X {
+        #[cells(group = "g", order = 2)]
+        a: u32,
+        b: u32,
+        #[cells(group = "g", order = 2)]
+        c: u32,
+        #[cells(order = 1)]
+        d: u32,
+    }
+
+    do_test(
+        vec![X {
+            a: 1,
+            b: 2,
+            c: 3,
+            d: 4,
+        }],
+        r"
+ b | d |   g   |
+---|---|-------|
+   |   | a | c |
+---|---|---|---|
+ 2 | 4 | 1 | 3 |
+",
+    );
+}
+
+#[test]
+fn derive_cells_skip_if_none_in_group_and_enum() {
+    #[derive(Cells)]
+    struct Latency {
+        min: Option<u32>,
+        #[cells(skip_if_none)]
+        max: Option<u32>,
+    }
+    #[derive(Cells)]
+    struct X {
+        name: &'static str,
+        latency: Latency,
+        #[cells(skip_if_none, group = "g")]
+        a: Option<u32>,
+        #[cells(skip_if_none, group = "g")]
+        b: Option<u32>,
+    }
+
+    do_test(
+        vec![X {
+            name: "x",
+            latency: Latency {
+                min: Some(1),
+                max: None,
+            },
+            a: None,
+            b: None,
+        }],
+        r"
+ name | latency |
+------|---------|
+      |   min   |
+------|---------|
+ x    |       1 |
+",
+    );
+
+    #[derive(Cells)]
+    #[allow(dead_code)]
+    enum E {
+        A {
+            #[cells(skip_if_none)]
+            x: u32,
+        },
+        B {
+            y: u32,
+        },
+    }
+    do_test(
+        vec![E::B { y: 1 }],
+        r"
+   | y |
+---|---|
+ B | 1 |
+",
+    );
+}
+
 #[test]
 fn derive_cells_for_tuple_struct() {
     #[derive(Cells)]
@@ -884,14 +1328,1130 @@ fn derive_cells_generic() {
 }
 
 #[track_caller]
-fn do_test<T: Cells>(s: Vec<T>, e: &str) {
-    do_test_with_schema(s, DefaultCellsSchema::default(), e);
+#[test]
+fn repeat_header() {
+    struct Source {
+        a: u32,
+        b_1: u32,
+        b_2: u32,
+    }
+    impl Cells for Source {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |s| s.a);
+            f.column_with("b", |f| {
+                f.column("1", |s| s.b_1);
+                f.column("2", |s| s.b_2);
+            });
+        }
+    }
+
+    let rows = [Source {
+        a: 300,
+        b_1: 10,
+        b_2: 20,
+    }];
+    let a = to_grid_with_options(rows, GridRenderOptions::new().repeat_header(true));
+    let e = r"
+  a  |    b    |
+-----|---------|
+     | 1  | 2  |
+-----|----|----|
+ 300 | 10 | 20 |
+-----|---------|
+  a  |    b    |
+-----|---------|
+     | 1  | 2  |
+";
+    assert_eq!(a, e.trim_start_matches('\n'));
 }
 
-#[track_caller]
-fn do_test_with_schema<T>(s: Vec<T>, schema: impl CellsSchema<Source = T>, e: &str) {
-    let a = to_grid_with_schema(s, schema).to_string();
-    let e = e.trim_matches('\n');
-    let a = a.trim_matches('\n');
-    assert!(a == e, "\nexpected :\n{}\nactual :\n{}\n", e, a);
+#[test]
+fn repeat_header_zero_rows() {
+    #[derive(Cells)]
+    struct Source {
+        a: u32,
+    }
+    let a = to_grid_with_options(
+        Vec::<Source>::new(),
+        GridRenderOptions::new().repeat_header(true),
+    );
+    let e = r"
+ a |
+---|
+";
+    assert_eq!(a, e.trim_start_matches('\n'));
+}
+
+#[test]
+fn hide_header_keeps_column_styles() {
+    struct Source {
+        a: u32,
+        b_1: u32,
+        b_2: u32,
+    }
+    impl Cells for Source {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("long header", |s| s.a);
+            f.column_with("b", |f| {
+                f.content(|s| s.b_1);
+                f.content(|_| "/");
+                f.content(|s| s.b_2);
+            });
+        }
+    }
+
+    let rows = [
+        Source {
+            a: 1,
+            b_1: 10,
+            b_2: 2,
+        },
+        Source {
+            a: 300,
+            b_1: 1,
+            b_2: 20,
+        },
+    ];
+    let a = to_grid_headerless(rows);
+    let e = r"
+   1 | 10/ 2 |
+ 300 |  1/20 |
+";
+    assert_eq!(a, e.trim_start_matches('\n'));
+}
+
+#[test]
+fn footer_tuple_schema() {
+    struct Count;
+    impl CellsSchema for Count {
+        type Source = u32;
+        fn fmt(&self, f: &mut CellsFormatter<u32>) {
+            f.column("n", |x| *x);
+        }
+        fn fmt_footer(&self, f: &mut CellsFormatter<[&u32]>) {
+            f.column("n", |xs| xs.len());
+        }
+    }
+    let schema = (DefaultCellsSchema::<&str>::default(), Count);
+    do_test_with_schema(
+        vec![("a", 10), ("bb", 200)],
+        schema,
+        r"
+    |  n  |
+----|-----|
+ a  |  10 |
+ bb | 200 |
+----|-----|
+    |   2 |
+",
+    );
+}
+
+#[test]
+fn footer_none() {
+    let schema = cells_schema::<u32>(|f| f.column("n", |x| *x));
+    do_test_with_schema(
+        vec![1, 2],
+        schema,
+        r"
+ n |
+---|
+ 1 |
+ 2 |
+",
+    );
+}
+
+#[test]
+fn column_with_unit_group() {
+    struct Source {
+        a: f64,
+        b_1: u32,
+        b_2: u32,
+    }
+    impl Cells for Source {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column_with_unit("a", "sec", |s| s.a);
+            f.column_with("b", |f| {
+                f.column("1", |s| s.b_1);
+                f.column_with_unit("2", "B", |s| s.b_2);
+            });
+        }
+    }
+
+    do_test(
+        vec![
+            Source {
+                a: 1.5,
+                b_1: 10,
+                b_2: 20,
+            },
+            Source {
+                a: 10.25,
+                b_1: 1,
+                b_2: 500,
+            },
+        ],
+        r"
+   a   |    b     |
+-------|----------|
+       | 1  |  2  |
+  sec  |    |  B  |
+-------|----|-----|
+  1.5  | 10 |  20 |
+ 10.25 |  1 | 500 |
+",
+    );
+}
+
+#[test]
+fn str_rows_schema_auto_align() {
+    let rows = vec![
+        vec!["a", "-1", "x", "1e3"],
+        vec!["b", "+20", "1", "2.5"],
+        vec!["c", "300"],
+    ];
+    let schema = StrRowsSchema::new(["s", "i", "m", "f"]).auto_align(&rows);
+    do_test_with_schema(
+        rows,
+        schema,
+        r"
+ s |  i  | m |   f   |
+---|-----|---|-------|
+ a |  -1 | x | 1e3   |
+ b | +20 | 1 |   2.5 |
+ c | 300 |   |       |
+",
+    );
+}
+
+#[test]
+fn str_rows_schema_default() {
+    let rows = vec![vec!["1".to_string()], vec!["200".to_string()]];
+    let schema = StrRowsSchema::new(["n"]);
+    do_test_with_schema(
+        rows,
+        schema,
+        r"
+  n  |
+-----|
+ 1   |
+ 200 |
+",
+    );
+}
+
+#[test]
+fn column_styled_keeps_align() {
+    struct X(u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column_styled("n", |x| x.0, |&v| CellStyle::new().bold(v > 10));
+        }
+    }
+    do_test(
+        vec![X(1), X(200)],
+        "
+  n  |
+-----|
+   1 |
+ \x1b[1m200\x1b[0m |
+",
+    );
+}
+
+#[test]
+fn column_styled_background() {
+    struct X(&'static str);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column_styled(
+                "s",
+                |x| x.0,
+                |_| {
+                    CellStyle::new()
+                        .color(Color::White)
+                        .background(Color::Ansi256(196))
+                },
+            );
+        }
+    }
+    do_test(
+        vec![X("ab")],
+        "
+ s  |
+----|
+ \x1b[37;48;5;196mab\x1b[0m |
+",
+    );
+}
+
+#[test]
+fn content_merged_within_column() {
+    struct X(u32, u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column_with("range", |f| {
+                f.content_merged(
+                    |x| (x.0 == x.1).then_some(x.0),
+                    |f| {
+                        f.content(|x| x.0);
+                        f.content(|_| "-");
+                        f.content(|x| x.1);
+                    },
+                );
+            });
+        }
+    }
+    do_test(
+        vec![X(1, 100), X(5, 5)],
+        r"
+ range |
+-------|
+ 1-100 |
+     5 |
+",
+    );
+}
+
+#[test]
+fn column_with_style_no_column_end() {
+    struct X(u32, u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            let style = ColumnStyle::new().column_end(false);
+            f.column_with_style("a", style, |x| x.0);
+            f.column("b", |x| x.1);
+        }
+    }
+    do_test(
+        vec![X(1, 2), X(30, 40)],
+        r"
+ a b  |
+------|
+  1 2 |
+ 3040 |
+",
+    );
+}
+
+#[test]
+fn column_with_style_stretch_last() {
+    struct X(&'static str, &'static str);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column_with("long header", |f| {
+                f.column("a", |x| x.0);
+                let style = ColumnStyle::new().stretch(true);
+                f.column_with_style("b", style, |x| x.1);
+            });
+        }
+    }
+    do_test(
+        vec![X("x", "y")],
+        r"
+ long header |
+-------------|
+ a |    b    |
+---|---------|
+ x | y       |
+",
+    );
+}
+
+#[test]
+fn column_with_style_align_h() {
+    struct X(u32, &'static str);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            let style = ColumnStyle::new().align_h(HorizontalAlignment::Center);
+            f.column_with_style("number", style.clone(), |x| x.0);
+            f.column_with_style("text", style, |x| cell(x.1).left());
+        }
+    }
+    do_test(
+        vec![X(1, "a"), X(10, "b")],
+        r"
+ number | text |
+--------|------|
+   1    | a    |
+   10   | b    |
+",
+    );
+}
+
+#[test]
+fn column_with_style_pad_char_and_max_width() {
+    struct X(&'static str, &'static str);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column_with_style("a", ColumnStyle::new().pad_char('.'), |x| x.0);
+            f.column_with_style("b", ColumnStyle::new().max_width(5), |x| x.1);
+        }
+    }
+    do_test(
+        vec![X("xyz", "ab cd ef"), X("x", "g")],
+        r"
+ .a. |   b   |
+-----|-------|
+ xyz | ab cd |
+ ... | ef    |
+ x.. | g     |
+",
+    );
+}
+
+#[test]
+fn column_with_style_rule() {
+    struct X(u32, [u32; 2], u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            let style = ColumnStyle::new().rule(VerticalRule::Double);
+            f.column_with_style("a", style.clone(), |x| x.0);
+            f.column_with_style("b", style, |x| x.1);
+            f.column("c", |x| x.2);
+        }
+    }
+    do_test(
+        vec![X(1, [2, 3], 4)],
+        r"
+ a ||   b   || c |
+---||-------||---|
+   || 0 | 1 ||   |
+---||---|---||---|
+ 1 || 2 | 3 || 4 |
+",
+    );
+}
+
+#[test]
+fn column_with_style_truncate() {
+    struct X(&'static str, u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column_with_style("a", ColumnStyle::new().truncate(4), |x| x.0);
+            f.column("b", |x| x.1);
+        }
+    }
+    do_test(
+        vec![X("abcdefghij", 1), X("abc", 2)],
+        r"
+  a   | b |
+------|---|
+ abc… | 1 |
+ abc  | 2 |
+",
+    );
+}
+
+#[test]
+fn column_with_style_header_align() {
+    struct X(&'static str, u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            let left = ColumnStyle::new().header_align(HorizontalAlignment::Left);
+            let right = ColumnStyle::new().header_align(HorizontalAlignment::Right);
+            f.column_with_style("a", left, |x| x.0);
+            f.column_with_style("b", right, |x| x.1);
+            f.column(cell("c").left(), |x| x.1);
+        }
+    }
+    do_test(
+        vec![X("xxxx", 1000), X("y", 2)],
+        r"
+ a    |    b | c    |
+------|------|------|
+ xxxx | 1000 | 1000 |
+ y    |    2 |    2 |
+",
+    );
+}
+
+#[test]
+fn auto_transpose() {
+    struct X(&'static str, u32, u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("name", |x| x.0);
+            f.column("aaaaaaaaaa", |x| x.1);
+            f.column("bbbbbbbbbb", |x| x.2);
+        }
+    }
+    let rows = || vec![X("x", 1, 2), X("y", 10, 20)];
+    let e = r"
+ name       | x | y  |
+ aaaaaaaaaa | 1 | 10 |
+ bbbbbbbbbb | 2 | 20 |
+";
+    let g = to_grid_with_options(rows(), GridRenderOptions::new().auto_transpose(30, 2));
+    assert_eq!(format!("\n{g}"), e);
+
+    let g = to_grid_with_options(rows(), GridRenderOptions::new().auto_transpose(30, 1));
+    assert_eq!(g.lines().count(), 4);
+
+    let g = to_grid_with_options(rows(), GridRenderOptions::new().auto_transpose(40, 2));
+    assert_eq!(g.lines().count(), 4);
+}
+
+#[test]
+fn diff_grid_unchanged() {
+    let old = [(1, "a"), (2, "b")];
+    let new = [(2, "b"), (1, "a")];
+    let g = to_diff_grid(
+        old,
+        new,
+        DefaultCellsSchema::default(),
+        |x: &(i32, &str)| x.0,
+    );
+    let e = r"
+ 2b |
+ 1a |
+";
+    assert_eq!(format!("\n{g}"), e);
+}
+
+#[test]
+fn diff_grid_duplicate_keys() {
+    let old = [(1, "a"), (1, "b")];
+    let new = [(1, "a"), (1, "c")];
+    let g = to_diff_grid(
+        old,
+        new,
+        DefaultCellsSchema::default(),
+        |x: &(i32, &str)| x.0,
+    );
+    let e = "
+  1a |
+* 1\x1b[33mc\x1b[0m |
+";
+    assert_eq!(format!("\n{g}"), e);
+}
+
+#[test]
+fn diff_grid_without_colors() {
+    let old = [(1, "a"), (1, "b"), (2, "c")];
+    let new = [(1, "b"), (1, "a"), (3, "d")];
+    let g = DiffOptions::new().colors(false).to_grid_with_schema(
+        old,
+        new,
+        DefaultCellsSchema::default(),
+        |x: &(i32, &str)| x.0,
+    );
+    let e = "
+* 1b |
+* 1a |
++ 3d |
+- 2c |
+";
+    assert_eq!(format!("\n{g}"), e);
+}
+
+fn do_test<T: Cells>(s: Vec<T>, e: &str) {
+    do_test_with_schema(s, DefaultCellsSchema::default(), e);
+}
+
+#[track_caller]
+fn do_test_with_schema<T>(s: Vec<T>, schema: impl CellsSchema<Source = T>, e: &str) {
+    let a = to_grid_with_schema(s, schema).to_string();
+    let e = e.trim_matches('\n');
+    let a = a.trim_matches('\n');
+    assert!(a == e, "\nexpected :\n{}\nactual :\n{}\n", e, a);
+}
+
+#[test]
+fn column_float_option_exp() {
+    struct X(Option<f64>);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column_float("fixed", |x| x.0, FloatFormat::fixed(1));
+            f.column_float("exp", |x| x.0, FloatFormat::exp(2));
+        }
+    }
+    let g = to_grid([X(Some(1.25)), X(None), X(Some(-12345.0))]);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+  fixed   |    exp    |
+----------|-----------|
+      1.2 |  1.25 e 0 |
+          |           |
+ -12345.0 | -1.23 e 4 |
+"#
+    );
+}
+
+#[test]
+fn dyn_cells_rows() {
+    struct A(u32);
+    impl Cells for A {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.0);
+        }
+    }
+    struct B;
+    impl Cells for B {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("b", |_| "x");
+            f.column("c", |_| Some(cell("y").right()));
+        }
+    }
+    let rows: Vec<Box<dyn DynCells>> = vec![Box::new(A(10)), Box::new(B), Box::new(A(2))];
+    assert_eq!(
+        format!("\n{}", to_grid(&rows)),
+        r#"
+ 10 |
+ x  | y |
+  2 |
+"#
+    );
+}
+
+mod schema_fns {
+    use text_grid::*;
+
+    pub struct Row<'a> {
+        pub name: &'a str,
+        pub values: Vec<u32>,
+    }
+
+    #[cells_schema]
+    pub fn row_columns(f: &mut CellsFormatter<Row<'static>>) {
+        f.column("name", |x| x.name);
+        f.column("total", |x| x.values.iter().sum::<u32>());
+    }
+}
+
+#[test]
+fn cells_schema_attribute_public_fn() {
+    use schema_fns::*;
+    let rows = [
+        Row {
+            name: "a",
+            values: vec![1, 2],
+        },
+        Row {
+            name: "bb",
+            values: vec![],
+        },
+    ];
+    let g = to_grid_with_schema(&rows, row_columns_schema().map_ref());
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ name | total |
+------|-------|
+ a    |     3 |
+ bb   |     0 |
+"#
+    );
+}
+
+#[test]
+fn schema_headers_content_and_stretch() {
+    let schema = cells_schema::<(u32, u32)>(|f| {
+        f.content(|x| x.0);
+        f.stretch().column("b", |x| x.1);
+    });
+    let headers = schema_headers(&schema);
+    assert_eq!(headers.column_count(), 2);
+    assert!(headers.paths()[0].is_empty());
+    assert!(!headers.stretch(0));
+    assert_eq!(headers.find_by_name("b"), Some(1));
+    assert!(headers.stretch(1));
+}
+
+#[test]
+fn cells_macro_nested_and_borrowed() {
+    let names = ["x".to_string(), "yy".to_string()];
+    let g = to_grid(names.iter().enumerate().map(|(i, name)| {
+        cells! {
+            "name": name,
+            "info": cells! { "len": name.len(), "index": i },
+        }
+    }));
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ name |    info     |
+------|-------------|
+      | len | index |
+------|-----|-------|
+ x    |   1 |     0 |
+ yy   |   2 |     1 |
+"#
+    );
+}
+
+#[test]
+fn theme_applies_to_double_rules_colspan_and_elision() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("a");
+        b.push("b");
+        b.push("c");
+    });
+    g.push(|b| {
+        b.push_with_colspan("wide", 2);
+        b.push(1);
+    });
+    g.extend_body([[2, 3, 4], [5, 6, 7]]);
+    g.column_styles = vec![ColumnStyle::default(); 3];
+    g.column_styles[0].rule = VerticalRule::Double;
+    g.options = GridRenderOptions::new()
+        .theme(GridTheme::unicode().padding(2))
+        .frame(true)
+        .max_rows(2);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+┌─────┬─────┬─────┐
+│  a  ║  b  │  c  │
+├───────────┼─────┤
+│  wide     │  1  │
+│  2  ║  3  │  4  │
+│  … 1 more row   │
+└─────┴─────┴─────┘
+"#
+    );
+}
+
+#[test]
+fn max_width_wraps_wide_chars_and_keeps_newlines() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("日本語のテキスト");
+        b.push("short\nabcdefgh ij");
+    });
+    g.column_styles = vec![ColumnStyle::default(); 2];
+    g.column_styles[0].max_width = Some(5);
+    g.column_styles[1].max_width = Some(6);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ 日本 | short  |
+ 語の | abcdef |
+ テキ | gh ij  |
+ スト |        |
+"#
+    );
+}
+
+#[test]
+fn truncate_wide_chars_and_escapes() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push("あいうえお"));
+    g.push(|b| b.push("\x1b[31mabcdefgh\x1b[0m"));
+    g.push(|b| b.push("abc"));
+    g.column_styles = vec![ColumnStyle::default()];
+    g.column_styles[0].truncate = Some(6);
+    assert_eq!(
+        format!("\n{g}"),
+        "
+ あい…  |
+ \x1b[31mabcde…\x1b[0m |
+ abc    |
+"
+    );
+}
+
+#[test]
+fn multi_line_cells_with_crlf() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("a\r\nbbb");
+        b.push(1);
+    });
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ a   | 1 |
+ bbb |   |
+"#
+    );
+}
+
+#[test]
+fn rowspan_in_middle_and_last_columns() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("a");
+        b.push("b");
+        b.push("c");
+    });
+    g.push(|b| {
+        b.push(1);
+        b.push_with_rowspan("span", 3);
+        b.push_with_rowspan("z", 2);
+    });
+    g.push_separator();
+    g.push(|b| b.push(2));
+    g.push_separator();
+    g.push(|b| {
+        b.push(3);
+        b.push("w");
+    });
+    g.push_separator();
+    g.push(|b| {
+        b.push(4);
+        b.push("v");
+        b.push("u");
+    });
+    g.options = GridRenderOptions::new().frame(true);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
++---+------+---+
+| a |  b   | c |
++---+------+---+
+| 1 | span | z |
++---+      |   |
+| 2 |      |   |
++---+      +---+
+| 3 |      | w |
++---+------+---+
+| 4 | v    | u |
++---+------+---+
+"#
+    );
+}
+
+#[test]
+fn rowspan_clipped_at_end_of_body() {
+    let mut g = GridBuilder::new();
+    g.aggregate(1, aggregator::Sum::new());
+    g.push(|b| {
+        b.push_with_rowspan("x", 5);
+        b.push(1);
+    });
+    g.push(|b| b.push(2));
+    g.push_totals();
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ x | 1 |
+   | 2 |
+---|---|
+   | 3 |
+"#
+    );
+}
+
+#[test]
+fn cells_footer_through_box_and_push_footer() {
+    struct X(u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("x", |x| x.0);
+        }
+        fn fmt_footer(f: &mut CellsFormatter<[&Self]>) {
+            f.column("x", |xs| xs.iter().map(|x| x.0).max());
+        }
+    }
+    let mut g = GridBuilder::from_iter_with_schema::<Box<X>>(
+        [Box::new(X(3)), Box::new(X(10))],
+        DefaultCellsSchema::default(),
+    );
+    g.push_footer(|b| b.push("end"));
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+  x  |
+-----|
+   3 |
+  10 |
+-----|
+  10 |
+ end |
+"#
+    );
+}
+
+#[test]
+fn transpose_nested_headers() {
+    struct X {
+        name: &'static str,
+        size: (u32, u32),
+    }
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("name", |x| x.name);
+            f.column_with("size", |f| {
+                f.column("w", |x| x.size.0);
+                f.column("h", |x| x.size.1);
+            });
+        }
+    }
+    let g = to_grid_transposed([
+        X {
+            name: "a",
+            size: (1, 20),
+        },
+        X {
+            name: "b",
+            size: (300, 4),
+        },
+    ]);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ name |   | a  | b   |
+ size | w |  1 | 300 |
+      | h | 20 |   4 |
+"#
+    );
+}
+
+#[test]
+fn to_record_with_none_and_schema() {
+    struct X {
+        a: Option<u32>,
+        b: &'static str,
+    }
+    let schema = cells_schema::<X>(|f| {
+        f.column("a", |x| x.a);
+        f.column_with("group", |f| f.column("b", |x| x.b));
+    });
+    let g = to_record_with_schema(&X { a: None, b: "text" }, schema);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ a       |      |
+ group.b | text |
+"#
+    );
+}
+
+#[test]
+fn render_passes_layout_to_renderer() {
+    #[derive(Default)]
+    struct Log(Vec<String>);
+    impl GridRenderer for Log {
+        fn begin(&mut self, widths: &[usize]) -> std::fmt::Result {
+            self.0.push(format!("begin {widths:?}"));
+            Ok(())
+        }
+        fn row(&mut self, kind: RowKind, cells: &[RenderCell]) -> std::fmt::Result {
+            let cells: Vec<_> = cells
+                .iter()
+                .map(|c| {
+                    format!(
+                        "{}@{}x{}w{}{:?}",
+                        c.text(),
+                        c.column(),
+                        c.colspan(),
+                        c.width(),
+                        c.align()
+                    )
+                })
+                .collect();
+            self.0.push(format!("{kind:?} {}", cells.join(" ")));
+            Ok(())
+        }
+        fn separator(&mut self, kind: SeparatorKind) -> std::fmt::Result {
+            self.0.push(format!("separator {kind:?}"));
+            Ok(())
+        }
+        fn elision(&mut self, count: usize) -> std::fmt::Result {
+            self.0.push(format!("elision {count}"));
+            Ok(())
+        }
+        fn footnote(&mut self, number: usize, text: &str) -> std::fmt::Result {
+            self.0.push(format!("footnote {number} {text}"));
+            Ok(())
+        }
+        fn legend(&mut self, symbol: &str, meaning: &str) -> std::fmt::Result {
+            self.0.push(format!("legend {symbol} {meaning}"));
+            Ok(())
+        }
+        fn end(&mut self) -> std::fmt::Result {
+            self.0.push("end".into());
+            Ok(())
+        }
+    }
+
+    let mut g = GridBuilder::new();
+    g.push_header(|b| b.push_with_colspan(cell("items").footnote("note"), 2));
+    g.set_legend([("*", "star")]);
+    for i in 0..4 {
+        g.push(|b| {
+            b.push("abc");
+            b.push(i * 100);
+        });
+    }
+    g.options = GridRenderOptions::new().max_rows(2);
+    let mut log = Log::default();
+    g.render(&mut log).unwrap();
+    assert_eq!(
+        log.0,
+        [
+            "begin [3, 3]",
+            "Header items[1]@0x2w9Center",
+            "separator Single",
+            "Body abc@0x1w3Left 0@1x1w3Right",
+            "Body abc@0x1w3Left 100@1x1w3Right",
+            "elision 2",
+            "footnote 1 note",
+            "legend * star",
+            "end",
+        ]
+    );
+}
+
+#[test]
+fn cells_write_visits_headers_units_and_merged_bodies() {
+    #[derive(Default)]
+    struct Log(Vec<String>);
+    fn text(cell: &dyn RawCell) -> String {
+        let mut s = String::new();
+        cell.fmt(&mut s);
+        s
+    }
+    impl CellsWrite for Log {
+        fn content(&mut self, cell: Option<&dyn RawCell>, _stretch: bool) {
+            self.0.push(format!("content {:?}", cell.map(text)));
+        }
+        fn merged_body_start(&mut self, cell: &dyn RawCell) {
+            self.0.push(format!("merged {}", text(cell)));
+        }
+        fn merged_body_end(&mut self, _cell: &dyn RawCell) {
+            self.0.push("merged end".into());
+        }
+        fn column_start(&mut self, header: &dyn RawCell) {
+            self.0.push(format!("start {}", text(header)));
+        }
+        fn column_end(&mut self, header: &dyn RawCell) {
+            self.0.push(format!("end {}", text(header)));
+        }
+        fn column_unit(&mut self, unit: &dyn RawCell) {
+            self.0.push(format!("unit {}", text(unit)));
+        }
+    }
+
+    struct X {
+        time: u32,
+        score: Option<u32>,
+    }
+    let schema = cells_schema::<X>(|f| {
+        f.column_with_unit("time", "ms", |x| x.time);
+        f.content_merged(
+            |x| x.score.is_none().then_some("absent"),
+            |f| f.column("score", |x| x.score),
+        );
+    });
+
+    let mut header = Log::default();
+    schema.fmt(&mut CellsFormatter::new(&mut header, None));
+    assert_eq!(
+        header.0,
+        [
+            "start time",
+            "content None",
+            "unit ms",
+            "end time",
+            "start score",
+            "content None",
+            "end score",
+        ]
+    );
+
+    let mut body = Log::default();
+    let x = X {
+        time: 5,
+        score: None,
+    };
+    schema.fmt(&mut CellsFormatter::new(&mut body, Some(&x)));
+    assert_eq!(
+        body.0,
+        [
+            "start time",
+            "content Some(\"5\")",
+            "unit ms",
+            "end time",
+            "merged absent",
+            "start score",
+            "content None",
+            "end score",
+            "merged end",
+        ]
+    );
+}
+
+#[test]
+fn derive_cells_schema_with_footer() {
+    #[derive(CellsSchema)]
+    #[cells(source = Vec<T>, fmt = Self::fmt_body, fmt_footer = Self::fmt_sum)]
+    #[cells(bound(T: Cells + Copy + std::iter::Sum))]
+    struct Columns<T> {
+        len: usize,
+        _phantom: std::marker::PhantomData<T>,
+    }
+    impl<T: Cells + Copy + std::iter::Sum> Columns<T> {
+        fn fmt_body(&self, f: &mut CellsFormatter<Vec<T>>) {
+            for i in 0..self.len {
+                f.column(i, |s| s[i]);
+            }
+        }
+        fn fmt_sum(&self, f: &mut CellsFormatter<[&Vec<T>]>) {
+            for i in 0..self.len {
+                f.column(i, |s| s.iter().map(|x| x[i]).sum::<T>());
+            }
+        }
+    }
+
+    let schema = Columns::<u32> {
+        len: 2,
+        _phantom: std::marker::PhantomData,
+    };
+    let g = to_grid_with_schema([vec![1, 2, 3], vec![10, 20, 30]], schema);
+    assert_eq!(
+        format!("\n{g}"),
+        r"
+ 0  | 1  |
+----|----|
+  1 |  2 |
+ 10 | 20 |
+----|----|
+ 11 | 22 |
+"
+    );
+}
+
+#[test]
+fn column_with_if_by_flag() {
+    struct X {
+        a: u32,
+        b: u32,
+        c: u32,
+    }
+    let schema = |detail: bool| {
+        cells_schema::<X>(move |f| {
+            f.column("a", |x| x.a);
+            f.column_with_if(detail, "detail", |f| {
+                f.column("b", |x| x.b);
+                f.column_if(false, "hidden", |x| x.c);
+                f.column("c", |x| x.c);
+            });
+        })
+    };
+    let rows = [X { a: 1, b: 2, c: 3 }];
+    let g = to_grid_with_schema(&rows, schema(false));
+    assert_eq!(
+        format!("\n{g}"),
+        r"
+ a |
+---|
+ 1 |
+"
+    );
+    let g = to_grid_with_schema(&rows, schema(true));
+    assert_eq!(
+        format!("\n{g}"),
+        r"
+ a | detail |
+---|--------|
+   | b  | c |
+---|----|---|
+ 1 |  2 | 3 |
+"
+    );
 }
@@ -1,5 +1,6 @@
 use std::vec;
 
+use text_grid::aggregator::{Count, Max, Min, Sum};
 use text_grid::*;
 
 #[test]
@@ -201,9 +202,1384 @@ fn stretch() {
     do_test(g, e);
 }
 
+#[test]
+fn lines() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push(cell("aaa"));
+        b.push(cell("b"));
+    });
+    g.push_separator();
+    g.push(|b| {
+        b.push(cell("a"));
+        b.push(cell("bbb"));
+    });
+    let lines: Vec<_> = g.lines().collect();
+    assert_eq!(lines, [" aaa | b   |", "-----|-----|", " a   | bbb |"]);
+    assert_eq!(lines.join("\n") + "\n", g.to_string());
+}
+
+#[test]
+fn lines_empty() {
+    let g = GridBuilder::new();
+    assert_eq!(g.lines().count(), 0);
+}
+
+#[test]
+fn extend() {
+    struct X {
+        a: u32,
+        b: &'static str,
+    }
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.a);
+            f.column("b", |x| x.b);
+        }
+    }
+
+    let mut g: GridBuilder = [X { a: 1, b: "x" }].into_iter().collect();
+    g.extend([X { a: 22, b: "yy" }]);
+    g.extend(vec![X { a: 333, b: "zzz" }]);
+    let e = r"
+  a  |  b  |
+-----|-----|
+   1 | x   |
+  22 | yy  |
+ 333 | zzz |";
+    do_test(g, e);
+}
+
+#[test]
+fn separator_before() {
+    let mut g = GridBuilder::new();
+    for s in ["a", "b", "c"] {
+        g.push(|b| {
+            b.push(s);
+            b.push(s);
+        });
+    }
+    g.push_separator_before(0);
+    g.push_separator_before(1);
+    g.set_separator(1, Some(SeparatorKind::Double));
+
+    let e = r"
+ a | a |
+---|---|
+ b | b |
+===|===|
+ c | c |";
+    do_test(g, e);
+}
+
+#[test]
+fn separator_remove() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push("a"));
+    g.push_separator();
+    g.push(|b| b.push("b"));
+    assert_eq!(g.separator(0), Some(SeparatorKind::Single));
+    g.set_separator(0, None);
+    assert_eq!(g.separator(0), None);
+
+    let e = r"
+ a |
+ b |";
+    do_test(g, e);
+}
+
+#[test]
+fn separator_double_colspan() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push_with_colspan("aaaaa", 2));
+    g.push_separator_with(SeparatorKind::Double);
+    g.push(|b| {
+        b.push("b");
+        b.push("c");
+    });
+
+    let e = r"
+ aaaaa |
+=======|
+ b | c |";
+    do_test(g, e);
+}
+
+#[test]
+fn row_style_marker() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("name");
+        b.push("value");
+    });
+    g.push_separator();
+    g.push(|b| {
+        b.push("a");
+        b.push(1);
+    });
+    g.push_styled(RowStyle::new().marker("->"), |b| {
+        b.push("b");
+        b.push(2);
+    });
+
+    let e = r"
+   name | value |
+  ------|-------|
+   a    |     1 |
+-> b    |     2 |";
+    do_test(g, e);
+}
+
+#[test]
+fn row_style_align() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("aaaa");
+        b.push(1);
+    });
+    g.push(|b| {
+        b.push("b");
+        b.push(2);
+    });
+    g.set_row_style(1, RowStyle::new().align_h(HorizontalAlignment::Center));
+
+    let e = r"
+ aaaa | 1 |
+  b   | 2 |";
+    do_test(g, e);
+}
+
+#[test]
+fn row_style_highlight() {
+    let mut g = GridBuilder::new();
+    g.push_styled(
+        RowStyle::new()
+            .color(Color::Black)
+            .highlight(Color::Rgb(1, 2, 3)),
+        |b| b.push("a"),
+    );
+    assert_eq!(g.to_string(), "\x1b[30;48;2;1;2;3m a |\x1b[0m\n");
+}
+
+#[test]
+fn cell_pad() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("a");
+        b.push(cell("b").pad(1).right());
+    });
+    g.push(|b| {
+        b.push(cell("a").pad_right(3));
+        b.push("bbb");
+    });
+
+    let e = r"
+ a    |  b  |
+ a    | bbb |";
+    do_test(g, e);
+}
+
+#[test]
+fn cell_pad_colspan() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push_with_colspan(cell("x").pad_left(6), 2));
+    g.push(|b| {
+        b.push("a");
+        b.push("b");
+    });
+
+    let e = r"
+       x |
+ a  | b  |";
+    do_test(g, e);
+}
+
+#[test]
+fn cell_min_width() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push(cell("a").min_width(4).right());
+        b.push(cell("b").min_width(2).pad_left(2));
+    });
+    g.push(|b| {
+        b.push("aaaaaa");
+        b.push("b");
+    });
+
+    let e = r"
+      a |   b |
+ aaaaaa | b   |";
+    do_test(g, e);
+}
+
+#[test]
+fn cell_min_width_colspan() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push_with_colspan(cell("x").min_width(9), 2));
+    g.push(|b| {
+        b.push("a");
+        b.push("b");
+    });
+
+    let e = r"
+ x         |
+ a   | b   |";
+    do_test(g, e);
+}
+
+#[test]
+fn set_headers_replace() {
+    #[derive(Cells)]
+    struct X {
+        a: u32,
+        b: u32,
+    }
+    let mut g: GridBuilder = [X { a: 1, b: 2 }, X { a: 300, b: 4 }].into_iter().collect();
+    g.set_headers(&[&["A", "B"], &["first", "second"]]);
+
+    let e = r"
+   A   |   B    |
+-------|--------|
+ first | second |
+-------|--------|
+     1 |      2 |
+   300 |      4 |";
+    do_test(g, e);
+}
+
+#[test]
+fn push_header_colspan() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| b.push_with_colspan("group", 2));
+    g.push_header(|b| {
+        b.push(1);
+        b.push(cell(2).left());
+    });
+    g.push(|b| {
+        b.push("aaa");
+        b.push("bbb");
+    });
+
+    let e = r"
+   group   |
+-----------|
+  1  | 2   |
+-----|-----|
+ aaa | bbb |";
+    do_test(g, e);
+}
+
+#[test]
+fn heatmap_background() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| b.push("v"));
+    g.push(|b| b.push("x"));
+    g.push(|b| b.push(10));
+    g.push(|b| b.push(20));
+    g.heatmap(0, Heatmap::new((0, 0, 0), (255, 255, 255)).background(true));
+
+    let e = "
+ v  |
+----|
+ x  |
+ \x1b[48;2;0;0;0m10\x1b[0m |
+ \x1b[48;2;255;255;255m20\x1b[0m |";
+    do_test(g, e);
+}
+
+#[test]
+fn heatmap_same_values() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push(1));
+    g.push(|b| b.push(1));
+    g.heatmap(0, Heatmap::new((0, 0, 0), (100, 100, 100)));
+
+    let e = "
+ \x1b[38;2;50;50;50m1\x1b[0m |
+ \x1b[38;2;50;50;50m1\x1b[0m |";
+    do_test(g, e);
+}
+
+#[test]
+fn aggregate_min_max_count() {
+    let mut g = GridBuilder::new();
+    g.aggregate(0, Count::new());
+    g.aggregate(1, Min::new());
+    g.aggregate(2, Max::new());
+    g.push_header(|b| {
+        b.push("name");
+        b.push(1000);
+        b.push(1000);
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push(3);
+        b.push(cell(-1.5).right());
+    });
+    g.push(|b| {
+        b.push("b");
+        b.push_with_colspan(100, 2);
+    });
+    g.push(|b| {
+        b.push("");
+        b.push(5);
+        b.push("x");
+    });
+    g.push_totals();
+
+    let e = r"
+ name | 1000 | 1000 |
+------|------|------|
+ a    |    3 | -1.5 |
+ b    |         100 |
+      |    5 | x    |
+------|------|------|
+    2 |    3 | -1.5 |";
+    do_test(g, e);
+}
+
+#[test]
+fn cell_link_width() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push(cell_link("a", "https://example.com").right()));
+    g.push(|b| b.push("abc"));
+    g.push(|b| b.push("\x1b]8;;x\x07b\x1b]8;;\x07"));
+
+    let e = "
+   \x1b]8;;https://example.com\x1b\\a\x1b]8;;\x1b\\ |
+ abc |
+ \x1b]8;;x\x07b\x1b]8;;\x07   |";
+    do_test(g, e);
+}
+
+#[test]
+fn cell_link_escapes_control_characters() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push(cell_link("a", "https://example.com/\x1b\\\x07\n\x7f")));
+
+    let e = "
+ \x1b]8;;https://example.com/%1B\\%07%0A%7F\x1b\\a\x1b]8;;\x1b\\ |";
+    do_test(g, e);
+}
+
+#[test]
+fn vertical_rule_colspan_width() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push_with_colspan("abcdefghij", 2));
+    g.push_separator();
+    g.push(|b| {
+        b.push("a");
+        b.push("b");
+    });
+    g.column_styles = vec![ColumnStyle::default(); 2];
+    g.column_styles[0].rule = VerticalRule::Double;
+    g.column_styles[1].rule = VerticalRule::Double;
+
+    let e = r"
+ abcdefghij ||
+------------||
+ a   || b   ||";
+    do_test(g, e);
+}
+
+#[test]
+fn frame_colspan() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("a");
+        b.push("b");
+    });
+    g.push_separator_with(SeparatorKind::Double);
+    g.push(|b| b.push_with_colspan("ccccc", 2));
+    g.options = GridRenderOptions::new().frame(true);
+
+    let e = r"
++---+---+
+| a | b |
++=======+
+| ccccc |
++-------+";
+    do_test(g, e);
+}
+
+#[test]
+fn frame_empty() {
+    let mut g = GridBuilder::new();
+    g.options = GridRenderOptions::new().frame(true);
+    do_test(g, "");
+}
+
+#[test]
+fn pad_char_align() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push("abcde"));
+    g.push(|b| b.push(1));
+    g.push(|b| b.push(cell("x").center()));
+    g.column_styles = vec![ColumnStyle::default()];
+    g.column_styles[0].pad_char = '·';
+
+    let e = r"
+ abcde |
+ ····1 |
+ ··x·· |";
+    do_test(g, e);
+}
+
+#[test]
+fn pad_char_double_width() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push("abcde"));
+    g.push(|b| b.push("a"));
+    g.push(|b| b.push("ab"));
+    g.column_styles = vec![ColumnStyle::new().pad_char('＊')];
+
+    let e = r"
+ abcde |
+ a＊＊ |
+ ab＊  |";
+    do_test(g, e);
+}
+
+#[test]
+fn max_rows_frame_footer() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("name");
+        b.push("value");
+    });
+    g.aggregate(0, Count::new());
+    g.aggregate(1, Sum::new());
+    for i in 0..4 {
+        g.push(|b| {
+            b.push("item");
+            b.push(i);
+        });
+    }
+    g.push_totals();
+    g.options = GridRenderOptions::new().frame(true).max_rows(1);
+
+    let e = r"
++------+-------+
+| name | value |
++------+-------+
+| item |     0 |
+| … 3 more rows |
++------+-------+
+|    4 |     6 |
++------+-------+";
+    do_test(g, e);
+}
+
+#[test]
+fn max_rows_thousands() {
+    let mut g = GridBuilder::new();
+    g.extend_body(0..1236);
+    g.options = GridRenderOptions::new().max_rows(1);
+
+    let e = r"
+    0 |
+ … 1,235 more rows |";
+    do_test(g, e);
+}
+
+#[test]
+fn max_rows_zero_columns() {
+    let mut g = GridBuilder::new();
+    for _ in 0..3 {
+        g.push(|_| {});
+    }
+    g.options = GridRenderOptions::new().max_rows(1);
+    assert_eq!(g.to_string(), "\n");
+}
+
+#[test]
+fn max_rows_not_exceeded() {
+    let mut g = GridBuilder::new();
+    g.extend_body([1, 2]);
+    g.options = GridRenderOptions::new().max_rows_with_tail(1, 1);
+
+    let e = r"
+ 1 |
+ 2 |";
+    do_test(g, e);
+}
+
+#[test]
+fn merge_vertically_frame() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("k");
+        b.push("v");
+    });
+    for (k, v) in [("a", 1), ("a", 2), ("b", 3)] {
+        g.push(|b| {
+            b.push(k);
+            b.push(v);
+        });
+        g.push_separator();
+    }
+    g.merge_vertically(&[0]);
+    g.options = GridRenderOptions::new().frame(true);
+
+    let e = r"
++---+---+
+| k | v |
++---+---+
+| a | 1 |
+|   +---+
+|   | 2 |
++---+---+
+| b | 3 |
++---+---+";
+    do_test(g, e);
+}
+
+#[test]
+fn merge_vertically_skip_empty() {
+    let mut g = GridBuilder::new();
+    g.extend_body(["", "", "x", "x"]);
+    g.merge_vertically(&[0]);
+
+    let e = r"
+   |
+   |
+ x |
+   |";
+    do_test(g, e);
+}
+
+#[test]
+fn shared_layout_different_columns() {
+    let mut g0 = GridBuilder::new();
+    g0.push_header(|b| {
+        b.push("name");
+        b.push("value");
+    });
+    g0.push(|b| {
+        b.push("a");
+        b.push(1);
+    });
+    let mut g1 = GridBuilder::new();
+    g1.push(|b| b.push_with_colspan("long text", 2));
+    g1.push(|b| {
+        b.push("b");
+        b.push(2);
+        b.push(3);
+    });
+
+    let mut layout = SharedLayout::new();
+    layout.push(&g0);
+    layout.push(&g1);
+    assert_eq!(layout.widths(), &[4, 5, 1]);
+
+    let s = format!(
+        "{}{}",
+        g0.display_with_layout(&layout),
+        g1.display_with_layout(&layout)
+    );
+    let e = r"
+ name | value |
+------|-------|
+ a    |     1 |
+ long text    |
+ b    |     2 | 3 |
+";
+    assert_eq!(format!("\n{s}"), e);
+}
+
+#[test]
+fn view_merged_first_row() {
+    let mut g = GridBuilder::new();
+    for (k, v) in [("a", 1), ("a", 2), ("a", 3)] {
+        g.push(|b| {
+            b.push(k);
+            b.push(v);
+        });
+    }
+    g.push_separator_before(2);
+    g.merge_vertically(&[0]);
+
+    let e = r"
+ a | 2 |
+   |---|
+   | 3 |";
+    do_test(g.view(1..3, 0..2), e);
+}
+
 fn do_test(g: GridBuilder, e: &str) {
     let a = format!("{}", g);
     let e = e.trim_matches('\n');
     let a = a.trim_matches('\n');
     assert!(a == e, "\nexpected :\n{}\nactual :\n{}\n", e, a);
 }
+
+#[test]
+fn cells_matrix_merged_and_totals() {
+    let mut g = GridBuilder::new();
+    g.aggregate(1, Sum::new());
+    g.extend_body([("a", 1), ("a", 2)]);
+    g.push_totals();
+    g.merge_vertically(&[0]);
+    g.options = GridRenderOptions::new().hide_header(true);
+    assert_eq!(g.to_cells_matrix(), [["a", "1"], ["a", "2"], ["", "3"]]);
+    assert!(g.to_header_matrix().is_empty());
+}
+
+#[test]
+fn empty_placeholder_decimal_and_merged() {
+    struct X(&'static str, Option<f64>);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("k", |x| x.0);
+            f.column("v", |x| x.1);
+        }
+    }
+    let mut g = GridBuilder::from_iter_with_schema(
+        [X("a", Some(1.0)), X("a", None), X("", Some(2.5))],
+        DefaultCellsSchema::default(),
+    );
+    g.merge_vertically(&[0]);
+    g.options = GridRenderOptions::new().empty_placeholder("n/a");
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+  k  |   v   |
+-----|-------|
+ a   |   1   |
+     | n/a   |
+ n/a |   2.5 |
+"#
+    );
+}
+
+#[test]
+fn block_align_right_frame_lines() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("a");
+        b.push(1);
+    });
+    g.options = GridRenderOptions::new()
+        .frame(true)
+        .block_align(12, HorizontalAlignment::Right);
+    assert_eq!(
+        g.lines().collect::<Vec<_>>(),
+        ["   +---+---+", "   | a | 1 |", "   +---+---+"]
+    );
+    g.options = GridRenderOptions::new().block_align(3, HorizontalAlignment::Right);
+    assert_eq!(g.to_string(), " a | 1 |\n");
+}
+
+#[test]
+fn flow_multi_column_with_totals() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("k");
+        b.push("v");
+    });
+    g.aggregate(1, Sum::new());
+    g.extend_body([("a", 1), ("b", 2), ("c", 3)]);
+    g.push_totals();
+    assert_eq!(
+        format!("\n{}", g.flow(100)),
+        r#"
+ k | v || k | v || k | v |
+---|---||---|---||---|---|
+ a | 1 || b | 2 || c | 3 |
+---|---||---|---||---|---|
+   | 6 ||   |   ||   |   |
+"#
+    );
+    assert_eq!(g.flow(0).to_string(), g.to_string());
+}
+
+#[test]
+fn flow_empty() {
+    let g = GridBuilder::new();
+    assert_eq!(g.flow(10).to_string(), "");
+}
+
+#[test]
+fn full_width_padding_odd_and_merged() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("東京都");
+        b.push(cell(1).right());
+    });
+    g.push(|b| {
+        b.push("東京都");
+        b.push(cell(12345).right());
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push(cell(12).right());
+    });
+    g.merge_vertically(&[0]);
+    g.options = GridRenderOptions::new().full_width_padding(true);
+    assert_eq!(
+        format!("\n{g}"),
+        "
+ 東京都 | \u{3000}\u{3000}1 |
+ \u{3000}\u{3000}\u{3000} | 12345 |
+ a\u{3000}\u{3000}  | \u{3000} 12 |
+"
+    );
+}
+
+#[test]
+fn footnotes_shared_and_framed() {
+    let mut g = GridBuilder::new();
+    g.options = GridRenderOptions::new().frame(true);
+    g.push_header(|b| {
+        b.push("name");
+        b.push(cell("value").footnote("approximate"));
+    });
+    g.push(|b| {
+        b.push(cell("a").footnote("sampled"));
+        b.push(cell(10).footnote("approximate"));
+    });
+    g.push(|b| {
+        b.push("b");
+        b.push(Some(cell(2).footnote("sampled")));
+    });
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
++------+----------+
+| name | value[1] |
++------+----------+
+| a[2] | 10[1]    |
+| b    | 2[2]     |
++------+----------+
+[1] approximate
+[2] sampled
+"#
+    );
+}
+
+#[test]
+fn legend_wrapped_within_table_width() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("ok");
+        b.push("!!");
+    });
+    g.set_legend([
+        ("ok", "fine"),
+        ("!!", "needs attention"),
+        ("?", "abcdefghij"),
+    ]);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ ok | !! |
+ ok  fine
+ !!  needs
+     atten
+     tion
+ ?   abcde
+     fghij
+"#
+    );
+
+    let mut g = GridBuilder::new();
+    g.set_legend([("x", "no table")]);
+    assert_eq!(g.to_string(), " x  no table\n");
+}
+
+#[test]
+fn retain_rows_keeps_header_and_unmerges() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("group");
+        b.push("value");
+    });
+    for (group, value) in [("a", 1), ("a", 2), ("a", 3), ("b", 4)] {
+        g.push(|b| {
+            b.push(group);
+            b.push(value);
+        });
+    }
+    g.merge_vertically(&[0]);
+    let mut seen = Vec::new();
+    g.retain_rows(|r| {
+        seen.push((r.index(), r.cells().collect::<Vec<_>>().join(",")));
+        r.cell(1) != Some("1")
+    });
+    assert_eq!(
+        seen,
+        [
+            (1, "a,1".to_string()),
+            (2, "a,2".to_string()),
+            (3, "a,3".to_string()),
+            (4, "b,4".to_string()),
+        ]
+    );
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ group | value |
+-------|-------|
+ a     |     2 |
+       |     3 |
+ b     |     4 |
+"#
+    );
+}
+
+#[test]
+fn dedup_rows_with_count_colspan_and_short_rows() {
+    let build = || {
+        let mut g = GridBuilder::new();
+        for (a, b) in [("x", "1"), ("x", "1"), ("y", ""), ("y", "")] {
+            g.push(|r| {
+                r.push(a);
+                if !b.is_empty() {
+                    r.push(b);
+                }
+            });
+        }
+        g.push(|r| r.push_with_colspan("x", 2));
+        g.push(|r| r.push_with_colspan("x", 2));
+        g.push_separator();
+        g.push(|r| {
+            r.push("x");
+            r.push("1");
+        });
+        g
+    };
+    let mut g = build();
+    g.dedup_rows();
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ x | 1 |
+ y |
+ x     |
+-------|
+ x | 1 |
+"#
+    );
+    let mut g = build();
+    g.dedup_rows_with_count();
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ x | 1 | × 2 |
+ y |   | × 2 |
+ x     | × 2 |
+-------|-----|
+ x | 1 |     |
+"#
+    );
+}
+
+#[test]
+fn set_column_align_beyond_styles() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("a");
+        b.push("x");
+        b.push(cell("y").left());
+    });
+    g.push(|b| {
+        b.push("bbb");
+        b.push("xxx");
+        b.push("yyy");
+    });
+    g.push(|b| b.push_with_colspan("z", 3));
+    g.set_column_align(0, HorizontalAlignment::Center);
+    g.set_column_align(2, HorizontalAlignment::Right);
+    assert_eq!(g.column_styles.len(), 3);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+  a  | x   | y   |
+ bbb | xxx | yyy |
+ z               |
+"#
+    );
+}
+
+#[test]
+fn header_layout_with_units_and_content() {
+    struct X {
+        name: &'static str,
+        time: f64,
+    }
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.content(|s| s.name);
+            f.column_with_unit("time", "ms", |s| s.time);
+        }
+    }
+    let mut g = GridBuilder::new();
+    let layout = g.extend_header::<X>();
+    assert_eq!(layout.depth(), 1);
+    assert_eq!(layout.column_count(), 3);
+    assert_eq!(g.column_styles.len(), layout.column_count());
+    assert_eq!(
+        layout.paths(),
+        [vec![], vec!["time".to_string()], vec!["time".to_string()]]
+    );
+    assert_eq!(layout.find(&["time"]), Some(1));
+    assert_eq!(layout.find(&["name"]), None);
+}
+
+#[test]
+fn extend_body_results_without_header() {
+    let mut g = GridBuilder::new();
+    g.extend_body_results([Ok((1, "a")), Err("failed to read"), Ok((22, "b"))]);
+    assert_eq!(
+        format!("\n{g}"),
+        "
+      1 | a     |
+\x1b[31m failed to read |\x1b[0m
+     22 | b     |
+"
+    );
+}
+
+#[test]
+fn auto_align_numeric_ignores_header_and_mixed_columns() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("id");
+        b.push("value");
+        b.push("ratio");
+    });
+    g.push(|b| {
+        b.push("1");
+        b.push("x");
+        b.push("-0.5");
+    });
+    g.push(|b| {
+        b.push("20");
+        b.push("3");
+        b.push("1e3");
+    });
+    g.push(|b| b.push_with_colspan("total", 3));
+    g.auto_align_numeric();
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ id | value | ratio |
+----|-------|-------|
+  1 | x     |  -0.5 |
+ 20 | 3     | 1e3   |
+ total              |
+"#
+    );
+}
+
+#[test]
+fn auto_align_numeric_footer_and_repeated_calls() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("name");
+        b.push("price");
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push("1.5");
+    });
+    g.push(|b| {
+        b.push("b");
+        b.push("10.25");
+    });
+    g.push_footer(|b| {
+        b.push("total");
+        b.push("11.75");
+    });
+    g.auto_align_numeric();
+    g.auto_align_numeric();
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ name  | price |
+-------|-------|
+ a     |  1.5  |
+ b     | 10.25 |
+-------|-------|
+ total | 11.75 |
+"#
+    );
+}
+
+#[test]
+fn nested_grid_in_schema_column() {
+    struct Order {
+        id: u32,
+        items: GridBuilder,
+    }
+    impl Cells for Order {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("id", |x| x.id);
+            f.column("items", |x| &x.items);
+        }
+    }
+    let items = |names: &[&str]| {
+        let mut g = GridBuilder::new();
+        for name in names {
+            g.push(|b| b.push(*name));
+        }
+        g
+    };
+    let g = to_grid([
+        Order {
+            id: 1,
+            items: items(&["a", "bbb"]),
+        },
+        Order {
+            id: 20,
+            items: items(&[]),
+        },
+    ]);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ id | items  |
+----|--------|
+  1 |  a   | |
+    |  bbb | |
+ 20 |        |
+"#
+    );
+}
+
+#[test]
+fn vertical_padding_with_multi_line_cells_and_marker() {
+    let mut g = GridBuilder::new();
+    g.push_styled(RowStyle::new().marker(">"), |b| {
+        b.push("a\nb");
+        b.push(1);
+    });
+    g.push_styled(RowStyle::new().pad_v(0, 0), |b| {
+        b.push_with_colspan("c", 2);
+    });
+    g.options = GridRenderOptions::new().vertical_padding(1, 1);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+    |   |
+> a | 1 |
+  b |   |
+    |   |
+  c     |
+"#
+    );
+}
+
+#[test]
+fn rtl_mirrors_short_rows_colspan_and_rules() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("a");
+        b.push("b");
+        b.push(cell("c").right());
+    });
+    g.push(|b| {
+        b.push_with_colspan("de", 2);
+    });
+    g.column_styles = vec![ColumnStyle::default(); 2];
+    g.column_styles[0].column_end = false;
+    g.options = GridRenderOptions::new().rtl(true);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ c | ba |
+   | de |
+"#
+    );
+}
+
+#[test]
+fn header_resolver_uses_text_as_key_and_keeps_footnote() {
+    let mut g = GridBuilder::new();
+    g.set_header_resolver(std::collections::BTreeMap::from([
+        ("size".to_string(), "Größe".to_string()),
+        ("k".to_string(), "Wert".to_string()),
+    ]));
+    g.push_header(|b| {
+        b.push("name");
+        b.push(cell("size").footnote("bytes"));
+        b.push(cell("value").header_key("k"));
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push(1);
+        b.push("size");
+    });
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ name | Größe[1] | Wert |
+------|----------|------|
+ a    |        1 | size |
+[1] bytes
+"#
+    );
+}
+
+#[test]
+fn header_resolver_is_applied_when_displayed() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("name");
+        b.push(cell("value").header_key("k"));
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push(1);
+    });
+    let mut g2 = g.transpose();
+    g.set_header_resolver(|key: &str| (key == "k").then(|| "v".to_string()));
+    assert_eq!(g.to_header_matrix(), [["name", "v"]]);
+    g.retain_rows(|_| true);
+    assert_eq!(g.to_string(), " name | v |\n------|---|\n a    | 1 |\n");
+    g2.set_header_resolver(|_: &str| Some("x".to_string()));
+    assert_eq!(g2.to_string(), " name  | a |\n value | 1 |\n");
+}
+
+#[test]
+fn select_columns_keeps_border_of_skipped_columns() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push_with_colspan("group", 2);
+        b.push("c");
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push("b");
+        b.push("c");
+    });
+    g.column_styles = vec![ColumnStyle::default(); 3];
+    g.column_styles[0].column_end = false;
+    g.column_styles[1].rule = VerticalRule::Double;
+
+    let s = g.select_columns([0, 2]);
+    assert_eq!(
+        format!("\n{s}"),
+        r#"
+ group || c |
+-------||---|
+ a     || c |
+"#
+    );
+    let s = g.select_columns(["group"]);
+    assert_eq!(
+        format!("\n{s}"),
+        r#"
+ group ||
+-------||
+ a  b  ||
+"#
+    );
+}
+
+#[test]
+fn ansi_escapes_are_excluded_from_width() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("name");
+        b.push("status");
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push("\x1b[31mfailed\x1b[0m");
+    });
+    g.push(|b| {
+        b.push("bb");
+        b.push("\x1b[1;32mok\x1b[m");
+    });
+    assert_eq!(
+        format!("\n{g}"),
+        "
+ name | status |
+------|--------|
+ a    | \x1b[31mfailed\x1b[0m |
+ bb   | \x1b[1;32mok\x1b[m     |
+"
+    );
+}
+
+#[test]
+fn to_grid_with_totals_by_path() {
+    struct Size {
+        w: u32,
+        h: u32,
+    }
+    impl Cells for Size {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("w", |x| x.w);
+            f.column("h", |x| x.h);
+        }
+    }
+    struct X {
+        name: &'static str,
+        size: Size,
+    }
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("name", |x| x.name);
+            f.column("size", |x| &x.size);
+        }
+    }
+    let rows = [
+        X {
+            name: "a",
+            size: Size { w: 1, h: 4 },
+        },
+        X {
+            name: "b",
+            size: Size { w: 2, h: 7 },
+        },
+    ];
+    let g = to_grid_with_totals(rows, |a| {
+        a.count("name").mean("size.w").max("h").sum("unknown")
+    });
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ name |  size   |
+------|---------|
+      |  w  | h |
+------|-----|---|
+ a    |   1 | 4 |
+ b    |   2 | 7 |
+------|-----|---|
+    2 | 1.5 | 7 |
+"#
+    );
+}
+
+#[test]
+fn aggregate_exact_integers_and_current_rows() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("a");
+        b.push(u64::MAX);
+    });
+    g.aggregate(1, Sum::new());
+    g.push(|b| {
+        b.push("b");
+        b.push(1);
+    });
+    g.push(|b| {
+        b.push("c");
+        b.push(9_007_199_254_740_993u64);
+    });
+    g.retain_rows(|row| row.index() != 2);
+    g.push_totals();
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ a | 18446744073709551615 |
+ b |                    1 |
+---|----------------------|
+   | 18446744073709551616 |
+"#
+    );
+}
+
+#[test]
+fn push_totals_does_not_rescan_rows() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct Counting(Rc<Cell<usize>>, Sum);
+    impl Aggregator for Counting {
+        fn push(&mut self, value: &str) {
+            self.0.set(self.0.get() + 1);
+            self.1.push(value);
+        }
+        fn fmt(&self, s: &mut String) {
+            self.1.fmt(s);
+        }
+    }
+    let count = Rc::new(Cell::new(0));
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push(1));
+    g.aggregate(0, Counting(count.clone(), Sum::new()));
+    assert_eq!(count.get(), 1);
+    g.push(|b| b.push(2));
+    g.push(|b| b.push(3));
+    assert_eq!(count.get(), 3);
+    g.push_totals();
+    g.push_totals();
+    assert_eq!(count.get(), 3);
+    assert_eq!(g.to_string(), " 1 |\n 2 |\n 3 |\n---|\n 6 |\n---|\n 6 |\n");
+}
+
+#[test]
+fn aggregate_not_send() {
+    let unit = std::rc::Rc::new("chars");
+    let mut g = GridBuilder::new();
+    g.aggregate(
+        0,
+        AggregateFn::new(
+            0,
+            |n, s: &str| *n += s.len(),
+            move |n| format!("{n} {unit}"),
+        ),
+    );
+    g.extend_body(["abc", "de"]);
+    g.push_totals();
+    assert_eq!(
+        g.to_string(),
+        " abc     |\n de      |\n---------|\n 5 chars |\n"
+    );
+}
+
+#[test]
+fn multi_line_cells() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("name");
+        b.push("value");
+    });
+    g.push(|b| {
+        b.push("a\nbbbbbb");
+        b.push(cell(1).right());
+    });
+    g.push(|b| {
+        b.push("c");
+        b.push(cell("x\nyy\nzzz").right());
+    });
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+  name  | value |
+--------|-------|
+ a      |     1 |
+ bbbbbb |       |
+ c      |     x |
+        |    yy |
+        |   zzz |
+"#
+    );
+}
+
+#[test]
+fn from_iter_without_footer_streams_rows() {
+    use std::cell::RefCell;
+
+    struct X<'a>(u32, &'a RefCell<Vec<String>>);
+    impl Cells for X<'_> {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| {
+                x.1.borrow_mut().push(format!("fmt {}", x.0));
+                x.0
+            });
+        }
+    }
+    let log = RefCell::new(Vec::new());
+    let rows = (0..2).map(|i| {
+        log.borrow_mut().push(format!("next {i}"));
+        X(i, &log)
+    });
+    let g = GridBuilder::from_iter_with_schema(rows, DefaultCellsSchema::default());
+    assert_eq!(log.into_inner(), ["next 0", "fmt 0", "next 1", "fmt 1"]);
+    assert_eq!(format!("\n{g}"), "\n a |\n---|\n 0 |\n 1 |\n");
+}
@@ -1,4 +1,7 @@
-use text_grid::{to_csv, Cells, CellsFormatter};
+use text_grid::{
+    to_csv, to_csv_with_options, write_csv, Cells, CellsFormatter, CsvOptions, CsvQuoteStyle,
+    CsvTerminator, DefaultCellsSchema,
+};
 
 #[test]
 fn to_csv_test() {
@@ -54,3 +57,114 @@ fn to_csv_nested() {
 
     assert_eq!(csv, "a,y.b,y.c\n1,2,3\n4,5,6\n");
 }
+
+#[test]
+fn write_csv_error() {
+    struct X(u8);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.0);
+        }
+    }
+    struct Fail;
+    impl std::io::Write for Fail {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("fail"))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut out = Vec::new();
+    write_csv(&mut out, [X(1), X(2)]).unwrap();
+    assert_eq!(out, b"a\n1\n2\n");
+    assert!(write_csv(Fail, [X(1)]).is_err());
+}
+
+#[test]
+fn csv_escape_formulas() {
+    struct X(&'static str);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.0);
+        }
+    }
+    let rows = [X("=A1"), X("+1"), X("-1.5"), X("-x"), X("\tb"), X("c")];
+    let csv = CsvOptions::new().escape_formulas(true).to_csv(&rows);
+    assert_eq!(csv, "a\n'=A1\n+1\n-1.5\n'-x\n'\tb\nc\n");
+    assert_eq!(to_csv(&rows), "a\n=A1\n+1\n-1.5\n-x\n\tb\nc\n");
+}
+
+#[test]
+fn csv_filter_columns_by_path() {
+    struct X {
+        a: u32,
+        ab: u32,
+        y: [u32; 2],
+    }
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.a);
+            f.column("ab", |x| x.ab);
+            f.column("y", |x| x.y);
+        }
+    }
+    let rows = [X {
+        a: 1,
+        ab: 2,
+        y: [3, 4],
+    }];
+    let csv = CsvOptions::new().include_columns(["a", "y"]).to_csv(&rows);
+    assert_eq!(csv, "a,y.0,y.1\n1,3,4\n");
+    let csv = CsvOptions::new()
+        .include_columns(["y"])
+        .exclude_columns(["y.1"])
+        .to_csv(&rows);
+    assert_eq!(csv, "y.0\n3\n");
+}
+
+#[test]
+fn csv_options_delimiter_quote_and_headers() {
+    let options = CsvOptions::new()
+        .delimiter(b';')
+        .quote_style(CsvQuoteStyle::Always)
+        .terminator(CsvTerminator::Any(b'|'))
+        .headers(false)
+        .exclude_columns(["1"]);
+    let csv = to_csv_with_options([["a;b", "x", "1"]], options);
+    assert_eq!(csv, r#""a;b";"1"|"#);
+}
+
+#[test]
+fn write_csv_with_schema_returns_write_error() {
+    struct FailingWriter;
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk full"))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let rows = (0..10000).map(|i| [i]);
+    let e = CsvOptions::new()
+        .write_csv_with_schema(
+            FailingWriter,
+            rows,
+            DefaultCellsSchema::<[i32; 1]>::default(),
+        )
+        .unwrap_err();
+    assert!(e.is_io_error());
+}
+
+#[test]
+#[should_panic(expected = "delimiter must be an ASCII character")]
+fn delimiter_rejects_non_ascii() {
+    let _ = CsvOptions::new().delimiter(0xff);
+}
+
+#[test]
+#[should_panic(expected = "terminator must be an ASCII character")]
+fn terminator_rejects_non_ascii() {
+    let _ = CsvOptions::new().terminator(CsvTerminator::Any(0x80));
+}
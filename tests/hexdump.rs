@@ -0,0 +1,33 @@
+use text_grid::{to_hexdump, HexdumpOptions};
+
+#[test]
+fn hexdump_multiple_rows() {
+    let bytes: Vec<u8> = (0x1e..0x30).collect();
+    assert_eq!(
+        format!("\n{}", to_hexdump(&bytes)),
+        r##"
+ 00000000 | 1e 1f 20 21 22 23 24 25 | 26 27 28 29 2a 2b 2c 2d | .. !"#$%&'()*+,- |
+ 00000010 | 2e 2f                   |                         | ./               |
+"##
+    );
+}
+
+#[test]
+fn hexdump_group_not_dividing_width() {
+    let g = HexdumpOptions::new()
+        .width(5)
+        .group(3)
+        .to_hexdump(b"abcdefg");
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ 00000000 | 61 62 63 | 64 65 | abcde |
+ 00000005 | 66 67    |       | fg    |
+"#
+    );
+}
+
+#[test]
+fn hexdump_empty() {
+    assert_eq!(to_hexdump(&[]), "");
+}
@@ -0,0 +1,82 @@
+use text_grid::*;
+
+#[test]
+fn to_json_escapes_control_chars_and_empty() {
+    struct X {
+        a: Option<u32>,
+        b: &'static str,
+    }
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.a);
+            f.column("b\\c", |x| x.b);
+        }
+    }
+    let rows = [X {
+        a: None,
+        b: "x\ny\u{1}",
+    }];
+    let s = to_json(rows);
+    assert_eq!(s, r#"[{"a":"","b\\c":"x\ny\u0001"}]"#);
+    let v: serde_json::Value = serde_json::from_str(&s).unwrap();
+    assert_eq!(v[0]["b\\c"], "x\ny\u{1}");
+}
+
+#[test]
+fn to_json_columns_without_header() {
+    struct X(u32, u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.0);
+            f.column("b", |x| x.1);
+            f.column("", |x| x.0 + x.1);
+            f.column_with("c", |f| {
+                f.column("", |x| x.0);
+                f.column("d", |x| x.1);
+            });
+        }
+    }
+    assert_eq!(
+        to_json([X(1, 2)]),
+        r#"[{"a":"1","b":"2","2":"3","c.3":"1","c.d":"2"}]"#
+    );
+}
+
+#[test]
+fn to_json_empty() {
+    assert_eq!(to_json(Vec::<[u32; 1]>::new()), "[]");
+}
+
+#[test]
+fn write_jsonl_writes_each_row_before_next_is_produced() {
+    use std::cell::RefCell;
+    use std::io::Write;
+    use std::rc::Rc;
+
+    struct SharedWriter(Rc<RefCell<Vec<u8>>>);
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let out = Rc::new(RefCell::new(Vec::new()));
+    let lens = RefCell::new(Vec::new());
+    let rows = (0..3).map(|i| {
+        lens.borrow_mut().push(out.borrow().len());
+        [i]
+    });
+    write_jsonl_with_schema(
+        SharedWriter(out.clone()),
+        rows,
+        DefaultCellsSchema::<[i32; 1]>::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(out.borrow().clone()).unwrap(),
+        "{\"0\":\"0\"}\n{\"0\":\"1\"}\n{\"0\":\"2\"}\n"
+    );
+    assert_eq!(*lens.borrow(), [0, 10, 20]);
+}
@@ -0,0 +1,76 @@
+use text_grid::*;
+
+#[test]
+fn to_latex_nested_schema_with_footer() {
+    struct Item {
+        name: &'static str,
+        size: (u32, u32),
+    }
+    struct ItemSchema;
+    impl CellsSchema for ItemSchema {
+        type Source = Item;
+        fn fmt(&self, f: &mut CellsFormatter<Item>) {
+            f.column("name", |x| x.name);
+            f.column_with("size", |f| {
+                f.column("w", |x| x.size.0);
+                f.column("h", |x| x.size.1);
+            });
+        }
+        fn fmt_footer(&self, f: &mut CellsFormatter<[&Item]>) {
+            f.column("name", |_| "100%");
+            f.column_with("size", |f| {
+                f.column("w", |xs| xs.iter().map(|x| x.size.0).sum::<u32>());
+                f.column("h", |xs| xs.iter().map(|x| x.size.1).sum::<u32>());
+            });
+        }
+    }
+    let rows = [
+        Item {
+            name: "{a}",
+            size: (1, 2),
+        },
+        Item {
+            name: "b~c",
+            size: (3, 4),
+        },
+    ];
+    assert_eq!(
+        to_latex_with_schema(rows, ItemSchema),
+        r#"\begin{tabular}{lrr}
+\toprule
+name & \multicolumn{2}{c}{size} \\
+\cmidrule(lr){2-3}
+ & w & h \\
+\midrule
+\{a\} & 1 & 2 \\
+b\textasciitilde{}c & 3 & 4 \\
+\midrule
+100\% & 4 & 6 \\
+\bottomrule
+\end{tabular}
+"#
+    );
+}
+
+#[test]
+fn to_latex_empty() {
+    assert_eq!(GridBuilder::new().to_latex(), "");
+}
+
+#[test]
+fn to_latex_without_header_and_with_colors() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("\x1b[31ma\x1b[0m");
+        b.push(1);
+    });
+    assert_eq!(
+        g.to_latex(),
+        r#"\begin{tabular}{lr}
+\toprule
+a & 1 \\
+\bottomrule
+\end{tabular}
+"#
+    );
+}
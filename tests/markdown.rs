@@ -0,0 +1,172 @@
+use text_grid::*;
+
+#[test]
+fn markdown_nested_headers() {
+    struct X {
+        a: &'static str,
+        y: Y,
+    }
+    struct Y {
+        b: u32,
+        c: f64,
+    }
+    impl Cells for Y {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("b", |x| cell(x.b).center());
+            f.column("c", |x| x.c);
+        }
+    }
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.a);
+            f.column("y", |x| &x.y);
+        }
+    }
+    let rows = [
+        X {
+            a: "x",
+            y: Y { b: 1, c: 1.5 },
+        },
+        X {
+            a: "yy",
+            y: Y { b: 20, c: 10.0 },
+        },
+    ];
+    let s = to_markdown(rows);
+    assert_eq!(
+        format!("\n{s}"),
+        r#"
+| a   | y.b | y.c |
+|-----|:---:|----:|
+| x   |  1  | 1.5 |
+| yy  | 20  |  10 |
+"#
+    );
+}
+
+#[test]
+fn markdown_dominant_align_and_colspan() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("k");
+        b.push("v");
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push(cell("x").right());
+    });
+    g.push(|b| {
+        b.push("b");
+        b.push(cell("yy").right());
+    });
+    g.push(|b| {
+        b.push("c");
+        b.push("zzz");
+    });
+    g.push(|b| b.push_with_colspan("total", 2));
+    assert_eq!(
+        format!("\n{}", g.to_markdown()),
+        r#"
+| k     |   v |
+|-------|----:|
+| a     |   x |
+| b     |  yy |
+| c     | zzz |
+| total |     |
+"#
+    );
+}
+
+#[test]
+fn markdown_empty() {
+    assert_eq!(GridBuilder::new().to_markdown(), "");
+}
+
+#[test]
+fn markdown_escape_pathological() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("#id");
+        b.push("text");
+    });
+    g.push(|b| {
+        b.push("# 1");
+        b.push("a|b`c`\\");
+    });
+    g.push(|b| {
+        b.push("x#");
+        b.push("||");
+    });
+    g.push(|b| {
+        b.push("");
+        b.push("line1\r\nline2");
+    });
+    assert_eq!(
+        format!("\n{}", g.to_markdown()),
+        r#"
+| \#id | text           |
+|------|----------------|
+| \# 1 | a\|b\`c\`\\    |
+| x#   | \|\|           |
+|      | line1<br>line2 |
+"#
+    );
+}
+
+#[test]
+fn markdown_round_trip() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("#id");
+        b.push("text");
+        b.push("n");
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push("x|y\\");
+        b.push(cell(1).center());
+    });
+    g.push(|b| {
+        b.push("b");
+        b.push("`z`");
+        b.push(cell(20).center());
+    });
+    let md = g.to_markdown();
+    let g2 = GridBuilder::from_markdown(&md).unwrap();
+    assert_eq!(g2.to_markdown(), md);
+    assert_eq!(g2.to_cells_matrix(), g.to_cells_matrix());
+}
+
+#[test]
+fn from_markdown_ragged_rows() {
+    let md = "
+intro | not a table
+
+a | b
+--|:-:
+1
+2 | 3 | 4
+| 5 \\| 6 |
+
+| c |
+";
+    let g = GridBuilder::from_markdown(md).unwrap();
+    assert_eq!(g.to_header_matrix(), [["a", "b"]]);
+    assert_eq!(g.to_cells_matrix(), [["1", ""], ["2", "3"], ["5 | 6", ""]]);
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+   a   | b |
+-------|---|
+ 1     |   |
+ 2     | 3 |
+ 5 | 6 |   |
+"#
+    );
+}
+
+#[test]
+fn from_markdown_no_table() {
+    assert!(GridBuilder::from_markdown("a | b\nc | d").is_none());
+    assert!(GridBuilder::from_markdown("").is_none());
+}
@@ -0,0 +1,46 @@
+use text_grid::{to_matrix_grid, MatrixOptions};
+
+#[test]
+fn matrix_ragged_with_column_index() {
+    let rows = vec![vec!["a", "bb"], vec!["ccc"], vec![]];
+    let g = to_matrix_grid(rows, &MatrixOptions::new().column_index(true));
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+   0 |  1 |
+-----|----|
+   a | bb |
+ ccc |    |
+     |    |
+"#
+    );
+}
+
+#[test]
+fn matrix_empty() {
+    let rows: Vec<Vec<u8>> = Vec::new();
+    assert_eq!(
+        to_matrix_grid(rows, &MatrixOptions::new().row_index(true)),
+        ""
+    );
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn array2_view_with_index() {
+    let a = ndarray::Array2::from_shape_fn((2, 3), |(i, j)| i * 10 + j);
+    let g = text_grid::to_array2_grid(
+        &a.t(),
+        &MatrixOptions::new().row_index(true).column_index(true),
+    );
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+   | 0 |  1 |
+---|---|----|
+ 0 | 0 | 10 |
+ 1 | 1 | 11 |
+ 2 | 2 | 12 |
+"#
+    );
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "serde")]
+
+use text_grid::*;
+
+#[test]
+fn serde_round_trip_keeps_styles_and_layout() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("name");
+        b.push("size");
+    });
+    g.push_styled(RowStyle::new().color(Color::Red).marker("!"), |b| {
+        b.push("a");
+        b.push(10);
+    });
+    g.push_separator_with(SeparatorKind::Double);
+    g.push(|b| b.push_with_colspan(cell("total").right(), 2));
+    g.set_column_align(0, HorizontalAlignment::Center);
+    g.set_legend([("!", "failed")]);
+    g.options = GridRenderOptions::new().frame(true);
+
+    let json = serde_json::to_string(&g).unwrap();
+    let g2: GridBuilder = serde_json::from_str(&json).unwrap();
+    assert_eq!(g2.to_string(), g.to_string());
+}
+
+#[test]
+fn serde_deserialized_grid_can_be_extended() {
+    let mut g = GridBuilder::new();
+    g.push(|b| {
+        b.push("a");
+        b.push(1);
+    });
+    let json = serde_json::to_string(&g).unwrap();
+    let mut g: GridBuilder = serde_json::from_str(&json).unwrap();
+    g.push(|b| {
+        b.push("bbb");
+        b.push(20);
+    });
+    assert_eq!(
+        format!("\n{g}"),
+        r#"
+ a   |  1 |
+ bbb | 20 |
+"#
+    );
+}
+
+#[test]
+fn serde_format_error_is_reported() {
+    let mut g = GridBuilder::new();
+    g.push(|b| b.push(try_cell_by(|_| Err(std::fmt::Error))));
+    assert!(serde_json::to_string(&g).is_err());
+}
@@ -0,0 +1,98 @@
+use text_grid::*;
+
+struct X {
+    name: &'static str,
+    values: [u32; 2],
+}
+impl Cells for X {
+    fn fmt(f: &mut CellsFormatter<Self>) {
+        f.column("name", |x| x.name);
+        f.column("values", |x| x.values);
+    }
+}
+
+fn rows() -> [X; 3] {
+    [
+        X {
+            name: "a",
+            values: [1, 2],
+        },
+        X {
+            name: "long name",
+            values: [10, 2000],
+        },
+        X {
+            name: "b",
+            values: [3, 4],
+        },
+    ]
+}
+
+fn write(options: StreamOptions) -> String {
+    let mut out = Vec::new();
+    options.write_grid(&mut out, rows()).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn stream_overflow() {
+    let s = write(StreamOptions::new().sample_rows(1));
+    assert_eq!(
+        format!("\n{s}"),
+        r#"
+ name | values |
+------|--------|
+      | 0  | 1 |
+------|----|---|
+ a    |  1 | 2 |
+ long name | 10 | 2000 |
+ b    |  3 | 4 |
+"#
+    );
+}
+
+#[test]
+fn stream_truncate() {
+    let s = write(
+        StreamOptions::new()
+            .sample_rows(1)
+            .overflow(WidthOverflow::Truncate),
+    );
+    assert_eq!(
+        format!("\n{s}"),
+        r#"
+ name | values |
+------|--------|
+      | 0  | 1 |
+------|----|---|
+ a    |  1 | 2 |
+ lon… | 10 | … |
+ b    |  3 | 4 |
+"#
+    );
+}
+
+#[test]
+fn stream_all_rows_sampled() {
+    let s = write(StreamOptions::new());
+    assert_eq!(s, to_grid(rows()));
+}
+
+#[test]
+fn stream_truncate_multi_line() {
+    let mut out = Vec::new();
+    StreamOptions::new()
+        .sample_rows(1)
+        .overflow(WidthOverflow::Truncate)
+        .write_grid(&mut out, ["abc", "abcdef\nab\nabcdefg"])
+        .unwrap();
+    assert_eq!(
+        format!("\n{}", String::from_utf8(out).unwrap()),
+        r#"
+ abc |
+ ab… |
+ ab  |
+ ab… |
+"#
+    );
+}
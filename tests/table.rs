@@ -0,0 +1,81 @@
+use text_grid::*;
+
+#[test]
+fn table_sort_by_multiple_keys_and_group_path() {
+    struct Row {
+        name: &'static str,
+        w: u32,
+        h: u32,
+    }
+    impl Cells for Row {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("name", |x| x.name);
+            f.column_with("size", |f| {
+                f.column("w", |x| x.w);
+                f.column("h", |x| x.h);
+            });
+        }
+    }
+    let rows = [
+        Row {
+            name: "a",
+            w: 10,
+            h: 2,
+        },
+        Row {
+            name: "b",
+            w: 9,
+            h: 5,
+        },
+        Row {
+            name: "c",
+            w: 10,
+            h: 1,
+        },
+    ];
+    let t = Table::new(rows)
+        .sort_by_desc("size.w")
+        .sort_by("h")
+        .sort_by("unknown")
+        .with(|g: &mut GridBuilder| g.options = GridRenderOptions::new().hide_header(true));
+    assert_eq!(
+        format!("\n{t}"),
+        r#"
+ c | 10 | 1 |
+ a | 10 | 2 |
+ b |  9 | 5 |
+"#
+    );
+}
+
+#[test]
+fn table_options_keep_theme() {
+    struct X(u32, u32);
+    impl Cells for X {
+        fn fmt(f: &mut CellsFormatter<Self>) {
+            f.column("a", |x| x.0);
+            f.column("b", |x| x.1);
+        }
+    }
+    let t = Table::new([X(1, 20)])
+        .with(GridTheme::unicode())
+        .with(GridRenderOptions::new().frame(true));
+    assert_eq!(
+        format!("\n{t}"),
+        r#"
+┌───┬────┐
+│ a │ b  │
+├───┼────┤
+│ 1 │ 20 │
+└───┴────┘
+"#
+    );
+}
+
+#[test]
+fn table_sort_mixed_values() {
+    let t = Table::new(["b", "10", "a", "9", ""])
+        .sort_by("")
+        .with(|g: &mut GridBuilder| g.options = GridRenderOptions::new().hide_header(true));
+    assert_eq!(t.to_string(), " 9  |\n 10 |\n    |\n a  |\n b  |\n");
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "testing")]
+use text_grid::testing::SnapshotScrubber;
+use text_grid::*;
+
+#[test]
+fn mask_column_group_header_and_empty_cells() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| {
+        b.push("name");
+        b.push_with_colspan("time", 2);
+    });
+    g.push(|b| {
+        b.push("a");
+        b.push("10ms");
+        b.push("");
+    });
+    g.push(|b| {
+        b.push("b");
+        b.push("3ms");
+        b.push("12:00");
+    });
+    let s = SnapshotScrubber::new().mask_column("time", "*");
+    assert_eq!(
+        format!("\n{}", s.scrub(&g)),
+        r#"
+ name | time  |
+------|-------|
+ a    | * |   |
+ b    | * | * |
+"#
+    );
+}
+
+#[test]
+fn replace_regex_applied_in_order() {
+    let mut g = GridBuilder::new();
+    g.push_header(|b| b.push("id 42"));
+    g.push(|b| b.push("id 42"));
+    let s = SnapshotScrubber::new()
+        .replace_regex(r"\d+", "N")
+        .replace_with(|s| s.strip_suffix('N').map(|s| format!("{s}<n>")));
+    assert_eq!(
+        format!("\n{}", s.scrub(&g)),
+        r#"
+ id 42  |
+--------|
+ id <n> |
+"#
+    );
+}
@@ -0,0 +1,33 @@
+use text_grid::HorizontalAlignment::*;
+use text_grid::*;
+
+#[test]
+fn push_row() {
+    let mut g = TextGrid::new();
+    g.push_row().push("a", Left).push("bb", Center);
+    g.push_separator();
+    g.push_row().push(1, Right).push(2, Right);
+    let e = r"
+ a | bb |
+---|----|
+ 1 |  2 |";
+    do_test(g, e);
+}
+
+#[test]
+fn push_row_colspan() {
+    let mut g = TextGrid::new();
+    g.push_row().push_with_colspan("title", Center, 2);
+    g.push_row().push("abc", Left).push("def", Right);
+    let e = r"
+   title   |
+ abc | def |";
+    do_test(g, e);
+}
+
+fn do_test(g: TextGrid, e: &str) {
+    let a = format!("{}", g);
+    let e = e.trim_matches('\n');
+    let a = a.trim_matches('\n');
+    assert!(a == e, "\nexpected :\n{}\nactual :\n{}\n", e, a);
+}